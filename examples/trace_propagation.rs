@@ -2,13 +2,11 @@ use otel_tracing::{
     counter, error_log,
     facade::MetricUnit,
     gauge, histogram, info_log, spawn_with_context,
-    telemetry::{init_datadog, shutdown},
+    telemetry::{force_flush, init_datadog, shutdown},
     with_async_span, with_span,
 };
-use std::{
-    sync::Arc,
-    time::{Duration, Instant},
-};
+use otel_tracing::ports::metrics::HistogramExt;
+use std::{sync::Arc, time::Duration};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -39,7 +37,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Main process with logs and metrics
     let result = with_async_span!("main_process", async {
-        let start_time = Instant::now();
+        let total_timer = test_histogram
+            .start_timer()
+            .with_attributes(vec![("step".to_string(), "total".into())]);
 
         // Clone Arc for use in this async block
         let request_counter = test_counter.clone();
@@ -61,7 +61,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let processing_gauge = processing_gauge.clone();
             let duration_histogram = duration_histogram.clone();
 
-            let prepare_start = Instant::now();
+            let timer = duration_histogram
+                .start_timer()
+                .with_attributes(vec![("step".to_string(), "prepare_data".into())]);
 
             // Record metric - set gauge to indicate active task
             processing_gauge.set(1.0, vec![("step".to_string(), "prepare_data".into())]);
@@ -69,12 +71,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // Simulate work
             tokio::time::sleep(Duration::from_secs(1)).await;
 
-            // Record duration in histogram
-            let duration_ms = prepare_start.elapsed().as_millis() as f64;
-            duration_histogram.record(
-                duration_ms,
-                vec![("step".to_string(), "prepare_data".into())],
-            );
+            // Record duration in histogram, in its unit
+            timer.stop();
 
             // Update gauge - no longer active
             processing_gauge.set(0.0, vec![("step".to_string(), "prepare_data".into())]);
@@ -106,7 +104,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     "background_task",
                     [("task_id", i), ("data_size", task_data.len())],
                     async {
-                        let task_start = Instant::now();
+                        let task_timer = duration_histogram.start_timer().with_attributes(vec![
+                            ("step".to_string(), "background_task".into()),
+                            ("task_id".to_string(), i.to_string().into()),
+                        ]);
 
                         // Simulate processing
                         tokio::time::sleep(Duration::from_secs(2)).await;
@@ -116,34 +117,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             // Clone the Arc for this inner span
                             let duration_histogram = duration_histogram.clone();
 
-                            let process_start = Instant::now();
+                            // The duration is recorded when the timer is dropped
+                            let _timer = duration_histogram.start_timer().with_attributes(vec![
+                                ("step".to_string(), "process_data".into()),
+                                ("task_id".to_string(), i.to_string().into()),
+                            ]);
                             tokio::time::sleep(Duration::from_secs(1)).await;
 
-                            let sum = task_data.iter().sum::<i32>();
-
-                            // Record duration in histogram
-                            let duration_ms = process_start.elapsed().as_millis() as f64;
-                            duration_histogram.record(
-                                duration_ms,
-                                vec![
-                                    ("step".to_string(), "process_data".into()),
-                                    ("task_id".to_string(), i.to_string().into()),
-                                ],
-                            );
-
-                            sum
+                            task_data.iter().sum::<i32>()
                         })
                         .await;
 
                         // Record overall task duration
-                        let task_duration_ms = task_start.elapsed().as_millis() as f64;
-                        duration_histogram.record(
-                            task_duration_ms,
-                            vec![
-                                ("step".to_string(), "background_task".into()),
-                                ("task_id".to_string(), i.to_string().into()),
-                            ],
-                        );
+                        task_timer.stop();
 
                         result
                     }
@@ -167,7 +153,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Final aggregation with metrics
         let final_result = with_span!("aggregate_results", {
-            let agg_start = Instant::now();
+            let timer = duration_histogram
+                .start_timer()
+                .with_attributes(vec![("step".to_string(), "aggregate_results".into())]);
 
             // Record metric for this operation
             processing_gauge.set(1.0, vec![("step".to_string(), "aggregate_results".into())]);
@@ -175,30 +163,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let sum = results.iter().sum::<i32>();
 
             // Record completion
-            let duration_ms = agg_start.elapsed().as_millis() as f64;
-            duration_histogram.record(
-                duration_ms,
-                vec![("step".to_string(), "aggregate_results".into())],
-            );
+            timer.stop();
             processing_gauge.set(0.0, vec![("step".to_string(), "aggregate_results".into())]);
 
             sum
         });
 
         // End-to-end duration metric
-        let total_duration_ms = start_time.elapsed().as_millis() as f64;
-        duration_histogram.record(
-            total_duration_ms,
-            vec![("step".to_string(), "total".into())],
-        );
+        total_timer.stop();
 
         final_result
     })
     .await;
 
-    let err: Box<dyn std::error::Error> = "An error occurred".into();
+    let err: Box<dyn std::error::Error + Send + Sync> = "An error occurred".into();
 
-    error_log!(err,
+    error_log!(error: err,
     "operation" => "process_data",
     "user_id" => 42,
     "error_code" => 500,
@@ -206,12 +186,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info_log!("final result", result => result);
 
+    // Make sure everything has been exported before shutting down
+    let report = force_flush(Duration::from_secs(5)).await?;
+    if !report.is_ok() {
+        eprintln!("Failed to flush telemetry: {:?}", report);
+    }
+
     // Shutdown telemetry
     info_log!("Shutting down telemetry");
     shutdown().await?;
 
-    // Allow time for traces to be sent
-    tokio::time::sleep(Duration::from_secs(2)).await;
-
     Ok(())
 }
@@ -0,0 +1,143 @@
+//! Console output of the Datadog logger.
+//!
+//! Besides the formats of `tracing_subscriber`, events can be written as JSON lines
+//! using Datadog's reserved attributes, so that an agent tailing the container's
+//! output correlates them with traces.
+
+use std::io;
+
+use chrono::Utc;
+use opentelemetry::trace::{SpanId, TraceContextExt, TraceId};
+use opentelemetry::Context;
+use serde::ser::{SerializeMap, Serializer as _};
+use tracing::{Event, Level, Subscriber};
+use tracing_serde::SerdeMapVisitor;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+
+/// How events are written to the console
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConsoleFormat {
+    /// The default human readable format of `tracing_subscriber`
+    #[default]
+    Full,
+    /// Like `Full`, on a single shorter line
+    Compact,
+    /// Multi-line output, for local development
+    Pretty,
+    /// The JSON format of `tracing_subscriber`
+    Json,
+    /// JSON lines with Datadog's reserved attributes and trace correlation ids
+    DatadogJson,
+}
+
+/// Formats events as JSON lines Datadog understands without any pipeline.
+///
+/// The level goes in `status`, the target in `logger.name` and, within an active span,
+/// the trace and span ids in `dd.trace_id` and `dd.span_id` in Datadog's 64-bit format.
+#[derive(Debug, Clone)]
+pub struct DatadogJsonFormat {
+    service_name: String,
+}
+
+impl DatadogJsonFormat {
+    pub fn new(service_name: impl Into<String>) -> Self {
+        Self {
+            service_name: service_name.into(),
+        }
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for DatadogJsonFormat
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+    N: for<'writer> FormatFields<'writer> + 'static,
+{
+    fn format_event(
+        &self,
+        _ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> std::fmt::Result {
+        let meta = event.metadata();
+
+        let mut visit = || {
+            let mut serializer = serde_json::Serializer::new(WriteAdaptor::new(&mut writer));
+            let mut serializer = serializer.serialize_map(None)?;
+            serializer.serialize_entry("timestamp", &Utc::now().to_rfc3339())?;
+            serializer.serialize_entry("status", datadog_status(meta.level()))?;
+            serializer.serialize_entry("service", &self.service_name)?;
+            serializer.serialize_entry("logger.name", meta.target())?;
+            if let Some(thread_name) = std::thread::current().name() {
+                serializer.serialize_entry("logger.thread_name", thread_name)?;
+            }
+
+            // The event's fields, the formatted message included
+            let mut visitor = SerdeMapVisitor::new(serializer);
+            event.record(&mut visitor);
+            serializer = visitor.take_serializer()?;
+
+            let context = Context::current();
+            let span = context.span();
+            let span_context = span.span_context();
+            if span_context.is_valid() {
+                serializer
+                    .serialize_entry("dd.trace_id", &datadog_trace_id(span_context.trace_id()))?;
+                serializer
+                    .serialize_entry("dd.span_id", &datadog_span_id(span_context.span_id()))?;
+            }
+
+            serializer.end()
+        };
+
+        visit().map_err(|_| std::fmt::Error)?;
+        writeln!(writer)
+    }
+}
+
+fn datadog_status(level: &Level) -> &'static str {
+    match *level {
+        Level::ERROR => "error",
+        Level::WARN => "warn",
+        Level::INFO => "info",
+        Level::DEBUG => "debug",
+        Level::TRACE => "trace",
+    }
+}
+
+/// The lower 64 bits of the trace id, which is what Datadog correlates on
+pub fn datadog_trace_id(trace_id: TraceId) -> u64 {
+    let bytes = trace_id.to_bytes();
+    u64::from_be_bytes(bytes[8..].try_into().unwrap_or_default())
+}
+
+pub fn datadog_span_id(span_id: SpanId) -> u64 {
+    u64::from_be_bytes(span_id.to_bytes())
+}
+
+// Lets serde_json write into the formatter's `fmt::Write`
+struct WriteAdaptor<'a> {
+    fmt_write: &'a mut dyn std::fmt::Write,
+}
+
+impl<'a> WriteAdaptor<'a> {
+    fn new(fmt_write: &'a mut dyn std::fmt::Write) -> Self {
+        Self { fmt_write }
+    }
+}
+
+impl io::Write for WriteAdaptor<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let s =
+            std::str::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        self.fmt_write.write_str(s).map_err(io::Error::other)?;
+
+        Ok(s.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,27 +1,51 @@
-use crate::domain::telemetry::{get_resource, AttributeValue, LogContext, TelemetryError};
+use crate::adapters::disk_buffer::{BufferedLogExporter, DiskBufferConfig};
+use crate::adapters::datadog::console::{ConsoleFormat, DatadogJsonFormat};
+use crate::adapters::datadog::with_datadog_tags;
+use crate::adapters::instrumented::InstrumentedLogExporter;
+use crate::domain::error_report::ErrorReport;
+use crate::domain::health::{ExportStats, SignalHealth};
+use crate::domain::telemetry::{
+    AttributeValue, LogContext, Signal, TelemetryError, DEFAULT_LOG_FILTER,
+};
 use crate::ports::logger::LoggerPort;
 use crate::LogLevel;
 use async_trait::async_trait;
+use opentelemetry::logs::{AnyValue, LogRecord as _};
+use opentelemetry::InstrumentationScope;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use opentelemetry_otlp::LogExporter;
-use opentelemetry_sdk::logs::SdkLoggerProvider;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::logs::{LogProcessor, SdkLogRecord, SdkLoggerProvider};
+use opentelemetry_sdk::Resource;
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 use tracing::{debug, event};
 use tracing::error;
 use tracing::info;
 use tracing::warn;
 use tracing::Level;
+use tracing::Subscriber;
 use tracing_subscriber::layer::SubscriberExt;
-use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::EnvFilter;
-use tracing_subscriber::Layer;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+/// Directives of the console layer's filter unless configured otherwise
+const DEFAULT_CONSOLE_FILTER: &str = "info,opentelemetry=info";
+
+/// Field of the emitted events carrying the severity text of their [`LogLevel`]
+pub const SEVERITY_FIELD: &str = "log.severity";
 
 pub struct DatadogLogger {
     logger_provider: Mutex<Option<SdkLoggerProvider>>,
     use_high_precision_timestamps: bool,
     service_name: String,
+    stats: Arc<ExportStats>,
+    disk_buffer: Option<DiskBufferConfig>,
+    console: Option<ConsoleFormat>,
+    console_filter: String,
+    install_subscriber: bool,
+    min_levels: Vec<(String, LogLevel)>,
 }
 
 impl DatadogLogger {
@@ -30,6 +54,12 @@ impl DatadogLogger {
             logger_provider: Mutex::new(None),
             use_high_precision_timestamps: true,
             service_name: service_name.as_ref().to_string(),
+            stats: Arc::new(ExportStats::default()),
+            disk_buffer: None,
+            console: Some(ConsoleFormat::default()),
+            console_filter: DEFAULT_CONSOLE_FILTER.to_string(),
+            install_subscriber: true,
+            min_levels: Vec::new(),
         }
     }
 
@@ -38,7 +68,168 @@ impl DatadogLogger {
         self
     }
 
-    // Convert LogLevel to tracing::Level
+    /// Spool log records to disk while the collector is unreachable
+    pub fn with_disk_buffer(mut self, config: DiskBufferConfig) -> Self {
+        self.disk_buffer = Some(config);
+        self
+    }
+
+    /// Write events to the console in the given format
+    pub fn with_console_format(mut self, format: ConsoleFormat) -> Self {
+        self.console = Some(format);
+        self
+    }
+
+    /// Only export events, without writing them to the console
+    pub fn without_console(mut self) -> Self {
+        self.console = None;
+        self
+    }
+
+    /// Filter the console output with `EnvFilter` directives, independently of the export filter
+    pub fn with_console_filter(mut self, directives: impl Into<String>) -> Self {
+        self.console_filter = directives.into();
+        self
+    }
+
+    /// Whether `init` installs the layers as the global subscriber.
+    ///
+    /// Disable it to compose the layers returned by [`DatadogLogger::layers`] with the
+    /// application's own subscriber instead.
+    pub fn with_global_subscriber(mut self, install: bool) -> Self {
+        self.install_subscriber = install;
+        self
+    }
+
+    /// Drop records of `target` and its submodules below `level`.
+    ///
+    /// An empty target applies to every target. When several targets match, the most
+    /// specific one wins, so `with_min_level("", LogLevel::Warn)` together with
+    /// `with_min_level("checkout", LogLevel::Debug)` keeps debug records of `checkout::db`.
+    pub fn with_min_level(mut self, target: impl Into<String>, level: LogLevel) -> Self {
+        let target = target.into();
+        self.min_levels.retain(|(existing, _)| *existing != target);
+        self.min_levels.push((target, level));
+        self
+    }
+
+    // The minimum level of the most specific target configured for `target`
+    fn min_level(&self, target: &str) -> Option<LogLevel> {
+        self.min_levels
+            .iter()
+            .filter(|(prefix, _)| {
+                prefix.is_empty()
+                    || target == prefix
+                    || target
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+    }
+
+    /// Build the layers of this logger for the caller to add to their subscriber.
+    ///
+    /// The first exports events to the collector through OpenTelemetry, filtered with
+    /// `filter`, the second writes them to the console if enabled. Once the layers were
+    /// built, [`LoggerPort::init`] leaves the subscriber alone: the resource and filter
+    /// the service initializes the logger with are ignored, so pass the same ones here.
+    pub fn layers<S>(
+        &self,
+        resource: &Resource,
+        filter: Option<EnvFilter>,
+    ) -> Result<Vec<Box<dyn Layer<S> + Send + Sync>>, TelemetryError>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let mut provider = self.logger_provider.lock().unwrap();
+        if provider.is_some() {
+            return Err(TelemetryError::AlreadyInitialized);
+        }
+
+        let logger_provider = self.build_provider(resource)?;
+        let filter = filter.unwrap_or_else(|| EnvFilter::new(DEFAULT_LOG_FILTER));
+        let mut layers = vec![OpenTelemetryTracingBridge::new(&logger_provider)
+            .with_filter(filter)
+            .boxed()];
+        layers.extend(self.console_layer());
+
+        *provider = Some(logger_provider);
+        Ok(layers)
+    }
+
+    /// Build the layers and install them as the global subscriber.
+    ///
+    /// Fails instead of panicking if the application already installed one, in which
+    /// case nothing is kept and [`DatadogLogger::layers`] can still be used.
+    pub fn try_init(
+        &self,
+        resource: &Resource,
+        filter: Option<EnvFilter>,
+    ) -> Result<(), TelemetryError> {
+        let layers = self.layers(resource, filter)?;
+
+        // The `log` crate is not captured here but by the bridge `facade::init` installs
+        if let Err(e) = tracing::subscriber::set_global_default(Registry::default().with(layers)) {
+            self.logger_provider.lock().unwrap().take();
+            return Err(TelemetryError::config_with_source(
+                "A global tracing subscriber is already set",
+                e,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn build_provider(&self, resource: &Resource) -> Result<SdkLoggerProvider, TelemetryError> {
+        let builder = LogExporter::builder().with_tonic();
+        // Replayed log records must reach the same collector, the same way
+        let builder = match &self.disk_buffer {
+            Some(config) => config.configure_exporter(builder),
+            None => builder,
+        };
+        let exporter = builder
+            .build()
+            .map_err(|e| TelemetryError::exporter_build(Signal::Logs, e))?;
+
+        let builder = SdkLoggerProvider::builder()
+            .with_resource(with_datadog_tags(resource))
+            .with_log_processor(SeverityProcessor);
+        let builder = match &self.disk_buffer {
+            Some(config) => builder.with_batch_exporter(BufferedLogExporter::new(
+                exporter,
+                &config.for_subdirectory("logs"),
+                self.stats.clone(),
+            )?),
+            None => builder.with_batch_exporter(InstrumentedLogExporter::new(
+                exporter,
+                self.stats.clone(),
+            )),
+        };
+
+        Ok(builder.build())
+    }
+
+    fn console_layer<S>(&self) -> Option<Box<dyn Layer<S> + Send + Sync>>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let filter = EnvFilter::new(&self.console_filter);
+        let layer = tracing_subscriber::fmt::layer().with_thread_names(true);
+
+        Some(match self.console? {
+            ConsoleFormat::Full => layer.with_filter(filter).boxed(),
+            ConsoleFormat::Compact => layer.compact().with_filter(filter).boxed(),
+            ConsoleFormat::Pretty => layer.pretty().with_filter(filter).boxed(),
+            ConsoleFormat::Json => layer.json().with_filter(filter).boxed(),
+            ConsoleFormat::DatadogJson => layer
+                .event_format(DatadogJsonFormat::new(&self.service_name))
+                .with_filter(filter)
+                .boxed(),
+        })
+    }
+
+    // Convert LogLevel to tracing::Level, `SeverityProcessor` restores critical records
     fn to_tracing_level(level: LogLevel) -> Level {
         match level {
             LogLevel::Debug => Level::DEBUG,
@@ -62,36 +253,6 @@ impl DatadogLogger {
         }
     }
 
-    // Extract stack trace from an error
-    fn extract_stack_trace(error: &dyn std::error::Error) -> String {
-        // First, try to extract from sources if available
-        let mut error_chain = Vec::new();
-        let mut current_error: Option<&dyn std::error::Error> = Some(error);
-
-        while let Some(err) = current_error {
-            error_chain.push(err.to_string());
-            current_error = err.source();
-        }
-
-        // Join the error chain into a stack-like format
-        error_chain.join("\n    caused by: ")
-    }
-
-    // Extract error kind from a standard error
-    fn extract_error_kind(error: &dyn std::error::Error) -> String {
-        // Try to get the type name using std::any downcast
-        if let Some(error_type) = (|| {
-            let type_id = std::any::TypeId::of::<dyn std::error::Error>();
-            let type_name = std::any::type_name::<dyn std::error::Error>();
-            Some(type_name.split("::").last()?.to_string())
-        })() {
-            error_type
-        } else {
-            // Fallback: use a generic error type
-            "Error".to_string()
-        }
-    }
-
     // Transform flat attributes to a Datadog-compatible nested structure
     fn transform_attributes_to_datadog_format(
         attributes: HashMap<String, AttributeValue>,
@@ -142,82 +303,43 @@ impl DatadogLogger {
 
 #[async_trait]
 impl LoggerPort for DatadogLogger {
-    async fn init(&self, filter: Option<EnvFilter>) -> Result<(), TelemetryError> {
-        let resource = get_resource();
-
-        let exporter = LogExporter::builder()
-            .with_tonic()
-            .build()
-            .map_err(|e| TelemetryError::LoggerInitError(e.to_string()))?;
-
-        let logger_provider = SdkLoggerProvider::builder()
-            .with_resource(resource)
-            .with_batch_exporter(exporter)
-            .build();
-
-        // Create OpenTelemetry layer
-        let otel_layer = OpenTelemetryTracingBridge::new(&logger_provider);
-
-        // Create filter for OpenTelemetry layer
-        let filter_otel = filter.unwrap_or_else(|| {
-            EnvFilter::new("info")
-                .add_directive("opentelemetry=info".parse().unwrap())
-                .add_directive("hyper=off".parse().unwrap())
-                .add_directive("tonic=off".parse().unwrap())
-                .add_directive("h2=off".parse().unwrap())
-                .add_directive("reqwest=off".parse().unwrap())
-        });
-
-        let otel_layer = otel_layer.with_filter(filter_otel);
-
-        // Create a standard formatting layer
-        let filter_fmt =
-            EnvFilter::new("info").add_directive("opentelemetry=info".parse().unwrap());
-
-        let fmt_layer = tracing_subscriber::fmt::layer()
-            .with_thread_names(true)
-            .with_filter(filter_fmt);
-
-        // Initialize the tracing subscriber
-        tracing_subscriber::registry()
-            .with(otel_layer)
-            .with(fmt_layer)
-            .init();
+    async fn init(&self, resource: &Resource, filter: Option<EnvFilter>) -> Result<(), TelemetryError> {
+        // The caller already composed the layers into their own subscriber
+        if self.logger_provider.lock().unwrap().is_some() {
+            warn!("The logger's layers were already built, ignoring the resource and filter");
+            return Ok(());
+        }
 
-        // Store provider for shutdown
-        let mut provider = self.logger_provider.lock().unwrap();
-        *provider = Some(logger_provider);
+        if !self.install_subscriber {
+            return Err(TelemetryError::config(
+                "The logger does not install a global subscriber, add its layers to yours first",
+            ));
+        }
 
-        Ok(())
+        self.try_init(resource, filter)
     }
 
     fn log_error(
         &self,
-        error: Box<dyn std::error::Error>,
+        error: &(dyn std::error::Error + 'static),
         target: Option<&str>,
-        mut attributes: Vec<(String, AttributeValue)>,
+        attributes: Vec<(String, AttributeValue)>,
     ) {
-        // Extract stack trace information
-        let stack_trace = Self::extract_stack_trace(&*error);
-        let error_message = error.to_string();
-        let error_kind = Self::extract_error_kind(&*error);
-        //let thread_name = thread::current().name().unwrap_or("unknown").to_string();
+        self.log_report(ErrorReport::from_dyn(error), target, attributes);
+    }
 
-        // Add Datadog specific stack trace attributes
+    fn log_report(
+        &self,
+        report: ErrorReport,
+        target: Option<&str>,
+        mut attributes: Vec<(String, AttributeValue)>,
+    ) {
+        // Datadog's standard attributes for errors
         attributes.push((
             "logger.name".to_string(),
             AttributeValue::String(target.unwrap_or("app").to_string()),
         ));
-        //attributes.push(("logger.thread_name".to_string(), AttributeValue::String(thread_name)));
-        attributes.push((
-            "error.stack".to_string(),
-            AttributeValue::String(stack_trace),
-        ));
-        attributes.push((
-            "error.message".to_string(),
-            AttributeValue::String(error_message.clone()),
-        ));
-        attributes.push(("error.kind".to_string(), AttributeValue::String(error_kind)));
+        attributes.extend(report.attributes());
 
         // Get current high-precision timestamp
         let now = SystemTime::now()
@@ -231,7 +353,7 @@ impl LoggerPort for DatadogLogger {
 
         let context = LogContext {
             timestamp: Some(timestamp),
-            message: error_message,
+            message: report.message,
             level: LogLevel::Error,
             target: target.map(|s| s.to_string()),
             attributes: attributes.into_iter().collect(),
@@ -242,6 +364,14 @@ impl LoggerPort for DatadogLogger {
 
     fn log(&self, context: LogContext) {
         let target = context.target.as_deref().unwrap_or("app");
+        if self
+            .min_level(target)
+            .is_some_and(|min_level| context.level < min_level)
+        {
+            return;
+        }
+
+        let severity = context.level.severity_text();
         let level = Self::to_tracing_level(context.level);
         let status = Self::to_datadog_status(context.level);
 
@@ -300,31 +430,82 @@ impl LoggerPort for DatadogLogger {
         // Emit the event directly at the appropriate level
         match level {
             Level::ERROR => {
-                error!(parent: None, %target, "{}", full_message);
+                error!(parent: None, %target, log.severity = severity, "{}", full_message);
             }
             Level::WARN => {
-                warn!(parent: None, %target, "{}", full_message);
+                warn!(parent: None, %target, log.severity = severity, "{}", full_message);
             }
             Level::INFO => {
-                info!(parent: None, %target, "{}", full_message);
+                info!(parent: None, %target, log.severity = severity, "{}", full_message);
             }
             Level::DEBUG => {
-                debug!(parent: None, %target, "{}", full_message);
+                debug!(parent: None, %target, log.severity = severity, "{}", full_message);
             }
             Level::TRACE => {
-                tracing::trace!(parent: None, %target, "{}", full_message);
+                tracing::trace!(parent: None, %target, log.severity = severity, "{}", full_message);
             }
         }
     }
 
+    async fn force_flush(&self) -> Result<(), TelemetryError> {
+        let provider = self.logger_provider.lock().unwrap().clone();
+        if let Some(provider) = provider {
+            // The batch processor blocks until the exporter has finished
+            tokio::task::spawn_blocking(move || provider.force_flush())
+                .await
+                .map_err(|e| TelemetryError::export(Signal::Logs, e))?
+                .map_err(|e| TelemetryError::from_sdk(Signal::Logs, e))?;
+        }
+
+        Ok(())
+    }
+
+    fn health(&self) -> Option<SignalHealth> {
+        Some(self.stats.snapshot())
+    }
+
     async fn shutdown(&self) -> Result<(), TelemetryError> {
-        let mut provider = self.logger_provider.lock().unwrap();
-        if let Some(provider) = provider.take() {
-            provider
-                .shutdown()
-                .map_err(|e| TelemetryError::ShutdownError(e.to_string()))?;
+        let provider = self.logger_provider.lock().unwrap().take();
+        if let Some(provider) = provider {
+            // Shutting down blocks on the final export, keep it off the async workers
+            tokio::task::spawn_blocking(move || provider.shutdown())
+                .await
+                .map_err(|e| TelemetryError::export(Signal::Logs, e))?
+                .map_err(|e| TelemetryError::from_sdk(Signal::Logs, e))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Restores the severity of records emitted by [`DatadogLogger`].
+///
+/// `tracing` has no level above `ERROR`, so the logger emits its events with their
+/// original severity in the [`SEVERITY_FIELD`] field. Register this processor before
+/// the exporting one so that critical records are exported as `FATAL`.
+#[derive(Debug, Default)]
+pub struct SeverityProcessor;
+
+impl LogProcessor for SeverityProcessor {
+    fn emit(&self, record: &mut SdkLogRecord, _instrumentation: &InstrumentationScope) {
+        let level = record.attributes_iter().find_map(|(key, value)| match value {
+            AnyValue::String(text) if key.as_str() == SEVERITY_FIELD => {
+                text.as_str().parse::<LogLevel>().ok()
+            }
+            _ => None,
+        });
+
+        if let Some(level) = level {
+            record.set_severity_number(level.severity_number());
+            record.set_severity_text(level.severity_text());
         }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
 
+    fn shutdown(&self) -> OTelSdkResult {
         Ok(())
     }
 }
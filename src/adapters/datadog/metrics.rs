@@ -8,11 +8,16 @@ use opentelemetry::KeyValue;
 use opentelemetry_otlp::MetricExporter;
 use opentelemetry_sdk::metrics::{SdkMeterProvider, Temporality};
 use opentelemetry_sdk::Resource;
-use std::sync::Mutex;
-use tracing::{debug, info};
-
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+use crate::adapters::disk_buffer::{BufferedMetricExporter, DiskBufferConfig};
+use crate::adapters::datadog::with_datadog_tags;
+use crate::adapters::instrumented::InstrumentedMetricExporter;
+use crate::domain::health::{ExportStats, SignalHealth, SELF_TELEMETRY_NAMESPACE};
+use crate::domain::metrics::MetricUnit;
 use crate::domain::telemetry::{
-    get_resource, to_key_value, AttributeValue, MetricContext, TelemetryError,
+    to_key_value, AttributeValue, MetricContext, Signal, TelemetryError,
 };
 use crate::ports::metrics::{Counter, Gauge, Histogram, MetricsPort};
 
@@ -47,7 +52,9 @@ pub struct DatadogMetrics {
     counter_meter_provider: Mutex<Option<SdkMeterProvider>>,
     gauge_meter_provider: Mutex<Option<SdkMeterProvider>>,
     histogram_meter_provider: Mutex<Option<SdkMeterProvider>>,
-    resource: Option<Resource>,
+    #[builder(default)]
+    stats: Arc<ExportStats>,
+    disk_buffer: Option<DiskBufferConfig>,
 }
 
 impl DatadogMetrics {
@@ -56,10 +63,53 @@ impl DatadogMetrics {
             counter_meter_provider: Mutex::new(None),
             gauge_meter_provider: Mutex::new(None),
             histogram_meter_provider: Mutex::new(None),
-            resource: None,
+            stats: Arc::new(ExportStats::default()),
+            disk_buffer: None,
         }
     }
 
+    /// Spool metrics to disk while the collector is unreachable
+    pub fn with_disk_buffer(mut self, config: DiskBufferConfig) -> Self {
+        self.disk_buffer = Some(config);
+        self
+    }
+
+    // Build a meter provider exporting with the given temporality
+    fn build_provider(
+        &self,
+        resource: &Resource,
+        temporality: Temporality,
+        name: &str,
+    ) -> Result<SdkMeterProvider, TelemetryError> {
+        let builder = MetricExporter::builder()
+            .with_tonic()
+            .with_temporality(temporality);
+        // Replayed metrics must reach the same collector, the same way
+        let builder = match &self.disk_buffer {
+            Some(config) => config.configure_exporter(builder),
+            None => builder,
+        };
+        let exporter = builder
+            .build()
+            .map_err(|e| TelemetryError::exporter_build(Signal::Metrics, e))?;
+
+        let builder = SdkMeterProvider::builder().with_resource(resource.clone());
+        let builder = match &self.disk_buffer {
+            // Each provider has its own exporter, and so its own queue
+            Some(config) => builder.with_periodic_exporter(BufferedMetricExporter::new(
+                exporter,
+                &config.for_subdirectory(&format!("metrics-{}", name)),
+                self.stats.clone(),
+            )?),
+            None => builder.with_periodic_exporter(InstrumentedMetricExporter::new(
+                exporter,
+                self.stats.clone(),
+            )),
+        };
+
+        Ok(builder.build())
+    }
+
     fn convert_attributes(attributes: &[(String, AttributeValue)]) -> Vec<KeyValue> {
         attributes
             .iter()
@@ -69,6 +119,11 @@ impl DatadogMetrics {
 
     // Format metric name according to DataDog conventions
     fn format_metric_name(&self, name: &str) -> String {
+        // The crate's own metrics keep their reserved namespace untouched
+        if name.starts_with(&format!("{}.", SELF_TELEMETRY_NAMESPACE)) {
+            return name.to_string();
+        }
+
         // DataDog prefers lowercase names with dots as separators
         let name = name.to_lowercase().replace('_', ".");
 
@@ -83,45 +138,14 @@ impl DatadogMetrics {
 
 #[async_trait]
 impl MetricsPort for DatadogMetrics {
-    async fn init(&self) -> Result<(), TelemetryError> {
-        // Add DataDog-specific resource attributes
-        let resource = get_resource();
-
-        // Counter provider with Delta temporality
-        let counter_exporter = MetricExporter::builder()
-            .with_tonic()
-            .with_temporality(Temporality::Delta)
-            .build()
-            .map_err(|e| TelemetryError::MetricsInitError(e.to_string()))?;
-
-        let counter_provider = SdkMeterProvider::builder()
-            .with_resource(resource.clone())
-            .with_periodic_exporter(counter_exporter)
-            .build();
-
-        // Gauge provider with Cumulative temporality
-        let gauge_exporter = MetricExporter::builder()
-            .with_tonic()
-            .with_temporality(Temporality::Cumulative)
-            .build()
-            .map_err(|e| TelemetryError::MetricsInitError(e.to_string()))?;
-
-        let gauge_provider = SdkMeterProvider::builder()
-            .with_resource(resource.clone())
-            .with_periodic_exporter(gauge_exporter)
-            .build();
+    async fn init(&self, resource: &Resource) -> Result<(), TelemetryError> {
+        let resource = &with_datadog_tags(resource);
 
-        // Histogram provider with Delta temporality
-        let histogram_exporter = MetricExporter::builder()
-            .with_tonic()
-            .with_temporality(Temporality::Delta)
-            .build()
-            .map_err(|e| TelemetryError::MetricsInitError(e.to_string()))?;
-
-        let histogram_provider = SdkMeterProvider::builder()
-            .with_resource(resource)
-            .with_periodic_exporter(histogram_exporter)
-            .build();
+        // Counters and histograms are reported as deltas, gauges as their current value
+        let counter_provider = self.build_provider(resource, Temporality::Delta, "counters")?;
+        let gauge_provider = self.build_provider(resource, Temporality::Cumulative, "gauges")?;
+        let histogram_provider =
+            self.build_provider(resource, Temporality::Delta, "histograms")?;
 
         // Store providers for shutdown
         *self.counter_meter_provider.lock().unwrap() = Some(counter_provider.clone());
@@ -235,7 +259,7 @@ impl MetricsPort for DatadogMetrics {
             histogram_builder
         };
 
-        let histogram_builder = if let Some(unit) = context.unit {
+        let histogram_builder = if let Some(unit) = &context.unit {
             histogram_builder.with_unit(unit.as_str().to_string())
         } else {
             histogram_builder
@@ -246,6 +270,7 @@ impl MetricsPort for DatadogMetrics {
         Box::new(DatadogHistogram {
             histogram,
             name: context.name,
+            unit: context.unit,
             default_attributes: attributes
                 .iter()
                 .map(|(k, v)| to_key_value(k.to_string(), v))
@@ -253,31 +278,78 @@ impl MetricsPort for DatadogMetrics {
         })
     }
 
-    async fn shutdown(&self) -> Result<(), TelemetryError> {
-        info!("Shutting down DatadogMetrics");
-
-        let mut provider = self.counter_meter_provider.lock().unwrap();
-        if let Some(provider) = provider.take() {
-            provider
-                .shutdown()
-                .map_err(|e| TelemetryError::ShutdownError(e.to_string()))?;
-        }
+    async fn force_flush(&self) -> Result<(), TelemetryError> {
+        debug!("Flushing DatadogMetrics");
+
+        let providers: Vec<SdkMeterProvider> = [
+            &self.counter_meter_provider,
+            &self.gauge_meter_provider,
+            &self.histogram_meter_provider,
+        ]
+        .iter()
+        .filter_map(|provider| provider.lock().unwrap().clone())
+        .collect();
+
+        // The periodic reader blocks until the collected points have been exported
+        tokio::task::spawn_blocking(move || {
+            // Every provider is handled even if one fails, the first error is reported
+            let mut errors = providers
+                .iter()
+                .filter_map(|provider| provider.force_flush().err())
+                .collect::<Vec<_>>()
+                .into_iter();
+
+            match errors.next() {
+                Some(error) => {
+                    for other in errors {
+                        warn!("Additional metrics provider error: {}", other);
+                    }
+                    Err(TelemetryError::from_sdk(Signal::Metrics, error))
+                }
+                None => Ok(()),
+            }
+        })
+        .await
+        .map_err(|e| TelemetryError::export(Signal::Metrics, e))?
+    }
 
-        let mut provider = self.gauge_meter_provider.lock().unwrap();
-        if let Some(provider) = provider.take() {
-            provider
-                .shutdown()
-                .map_err(|e| TelemetryError::ShutdownError(e.to_string()))?;
-        }
+    fn health(&self) -> Option<SignalHealth> {
+        Some(self.stats.snapshot())
+    }
 
-        let mut provider = self.histogram_meter_provider.lock().unwrap();
-        if let Some(provider) = provider.take() {
-            provider
-                .shutdown()
-                .map_err(|e| TelemetryError::ShutdownError(e.to_string()))?;
-        }
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        info!("Shutting down DatadogMetrics");
 
-        Ok(())
+        let providers: Vec<SdkMeterProvider> = [
+            &self.counter_meter_provider,
+            &self.gauge_meter_provider,
+            &self.histogram_meter_provider,
+        ]
+        .iter()
+        .filter_map(|provider| provider.lock().unwrap().take())
+        .collect();
+
+        // Shutting down blocks on the final export, keep it off the async workers
+        tokio::task::spawn_blocking(move || {
+            // Every provider is handled even if one fails, the first error is reported
+            let mut errors = providers
+                .iter()
+                .filter_map(|provider| provider.shutdown().err())
+                .collect::<Vec<_>>()
+                .into_iter();
+
+            match errors.next() {
+                Some(error) => {
+                    for other in errors {
+                        warn!("Additional metrics provider error: {}", other);
+                    }
+                    Err(TelemetryError::from_sdk(Signal::Metrics, error))
+                }
+                None => Ok(()),
+            }
+        })
+        .await
+        .map_err(|e| TelemetryError::export(Signal::Metrics, e))?
     }
 }
 struct DatadogCounter {
@@ -321,6 +393,7 @@ impl Gauge for DatadogGauge {
 struct DatadogHistogram {
     histogram: OtelHistogram<f64>,
     name: String,
+    unit: Option<MetricUnit>,
     default_attributes: Vec<KeyValue>,
 }
 
@@ -335,4 +408,8 @@ impl Histogram for DatadogHistogram {
 
         self.histogram.record(value, &combined_attributes);
     }
+
+    fn unit(&self) -> Option<MetricUnit> {
+        self.unit.clone()
+    }
 }
@@ -1,7 +1,11 @@
+mod console;
 mod logger;
 mod metrics;
+mod tags;
 mod tracer;
 
-pub use logger::DatadogLogger;
+pub use console::{datadog_span_id, datadog_trace_id, ConsoleFormat, DatadogJsonFormat};
+pub use logger::{DatadogLogger, SeverityProcessor, SEVERITY_FIELD};
 pub use metrics::DatadogMetrics;
+pub use tags::{with_datadog_tags, DATADOG_TAGS};
 pub use tracer::DatadogTracer;
\ No newline at end of file
@@ -0,0 +1,38 @@
+use opentelemetry::{Key, KeyValue};
+use opentelemetry_sdk::Resource;
+
+/// Resource attributes that Datadog correlates with infrastructure under another tag name
+pub const DATADOG_TAGS: [(&str, &str); 5] = [
+    ("container.id", "container_id"),
+    ("k8s.namespace.name", "kube_namespace"),
+    ("k8s.pod.name", "pod_name"),
+    ("k8s.node.name", "kube_node"),
+    ("k8s.container.name", "kube_container_name"),
+];
+
+/// Add the Datadog tag for every mapped attribute found in the resource
+pub fn with_datadog_tags(resource: &Resource) -> Resource {
+    let tags: Vec<KeyValue> = DATADOG_TAGS
+        .iter()
+        .filter_map(|(attribute, tag)| {
+            let value = resource.get(&Key::from_static_str(attribute))?;
+            Some(KeyValue::new(*tag, value))
+        })
+        .collect();
+
+    if tags.is_empty() {
+        return resource.clone();
+    }
+
+    let attributes = resource
+        .iter()
+        .map(|(key, value)| KeyValue::new(key.clone(), value.clone()))
+        .chain(tags);
+
+    let builder = Resource::builder_empty();
+    match resource.schema_url() {
+        Some(schema_url) => builder.with_schema_url(attributes, schema_url.to_string()),
+        None => builder.with_attributes(attributes),
+    }
+    .build()
+}
@@ -2,48 +2,86 @@ use std::sync::Arc;
 use std::sync::Mutex;
 use async_trait::async_trait;
 use opentelemetry::global;
-use opentelemetry::trace::SpanKind;
-use opentelemetry::trace::{Tracer as OtelTracer, Span as OtelSpan, TraceContextExt};
+use opentelemetry::trace::{Link, SpanKind, Status};
+use opentelemetry::trace::{Tracer as OtelTracer, Span as OtelSpan, TraceContextExt, TracerProvider};
+use opentelemetry::trace::noop::NoopTracer;
 use opentelemetry::Context;
 use opentelemetry::KeyValue;
+use opentelemetry::propagation::TextMapCompositePropagator;
+use opentelemetry_sdk::propagation::{BaggagePropagator, TraceContextPropagator};
 use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
 use opentelemetry_otlp::SpanExporter;
 use tracing::debug;
 use tracing::info;
 
-use crate::domain::telemetry::{SpanContext, AttributeValue, TelemetryError, get_resource, to_key_value};
+use crate::adapters::disk_buffer::{BufferedSpanExporter, DiskBufferConfig};
+use crate::adapters::datadog::with_datadog_tags;
+use crate::adapters::instrumented::InstrumentedSpanExporter;
+use crate::domain::error_report::ErrorReport;
+use crate::domain::health::{ExportStats, SignalHealth};
+use crate::domain::telemetry::{SpanContext, AttributeValue, Signal, TelemetryError, to_key_value};
 use crate::ports::tracer::{TracerPort, Span};
 
 pub struct DatadogTracer {
     tracer_provider: Mutex<Option<SdkTracerProvider>>,
+    stats: Arc<ExportStats>,
+    disk_buffer: Option<DiskBufferConfig>,
 }
 
 impl DatadogTracer {
     pub fn new() -> Self {
         Self {
             tracer_provider: Mutex::new(None),
+            stats: Arc::new(ExportStats::with_queue_tracking()),
+            disk_buffer: None,
         }
     }
+
+    /// Spool spans to disk while the collector is unreachable
+    pub fn with_disk_buffer(mut self, config: DiskBufferConfig) -> Self {
+        self.disk_buffer = Some(config);
+        self
+    }
 }
 
 #[async_trait]
 impl TracerPort for DatadogTracer {
-    async fn init(&self) -> Result<(), TelemetryError> {
+    async fn init(&self, resource: &Resource) -> Result<(), TelemetryError> {
         info!("Initializing DatadogTracer");
-        let resource = get_resource();
-            
-        let exporter = SpanExporter::builder()
-            .with_tonic()
+
+        let builder = SpanExporter::builder().with_tonic();
+        // Replayed spans must reach the same collector, the same way
+        let builder = match &self.disk_buffer {
+            Some(config) => config.configure_exporter(builder),
+            None => builder,
+        };
+        let exporter = builder
             .build()
-            .map_err(|e| TelemetryError::TracerInitError(e.to_string()))?;
+            .map_err(|e| TelemetryError::exporter_build(Signal::Traces, e))?;
             
-        let tracer_provider = SdkTracerProvider::builder()
-            .with_resource(resource)
-            .with_batch_exporter(exporter)
-            .build();
+        let builder = SdkTracerProvider::builder().with_resource(with_datadog_tags(resource));
+        let builder = match &self.disk_buffer {
+            Some(config) => builder.with_batch_exporter(BufferedSpanExporter::new(
+                exporter,
+                &config.for_subdirectory("traces"),
+                self.stats.clone(),
+            )?),
+            None => builder.with_batch_exporter(InstrumentedSpanExporter::new(
+                exporter,
+                self.stats.clone(),
+            )),
+        };
+        let tracer_provider = builder.build();
             
-        // Set global tracer provider
+        // Spans are created from the provider stored below, so that every service keeps
+        // its own resource. The global one serves instrumentation using the global API.
         global::set_tracer_provider(tracer_provider.clone());
+        // Propagate W3C trace context and baggage across services
+        global::set_text_map_propagator(TextMapCompositePropagator::new(vec![
+            Box::new(TraceContextPropagator::new()),
+            Box::new(BaggagePropagator::new()),
+        ]));
         
         // Store provider for shutdown
         let mut provider = self.tracer_provider.lock().unwrap();
@@ -55,55 +93,96 @@ impl TracerPort for DatadogTracer {
     }
     
     fn create_span(&self, context: SpanContext) -> Box<dyn Span> {
-        let tracer = global::tracer("datadog-tracer");
-        
-        let attributes: Vec<KeyValue> = context.attributes.iter()
-            .map(|(k, v)| to_key_value(k.clone(), v))
-            .collect();
+        let provider = self.tracer_provider.lock().unwrap().clone();
+        let (cx, name) = match provider {
+            Some(provider) => start_span(&provider.tracer("datadog-tracer"), context),
+            // Nothing is recorded before init
+            None => start_span(&NoopTracer::new(), context),
+        };
 
-        // Get the current context - will contain parent span if one exists
-        let current_ctx = Context::current();
-        
-        if current_ctx.span().span_context().is_valid() {
-            debug!("Creating child span with parent: {:?}", current_ctx.span().span_context().trace_id());
-        } else {
-            debug!("Creating root span (no parent)");
-        }
-        
-        // Create a span builder
-        let span_builder = tracer.span_builder(context.name.clone())
-            .with_kind(SpanKind::Internal)
-            .with_attributes(attributes);
-            
-        // Start the span within the current context (preserving parent relationship)
-        let span = tracer.build_with_context(span_builder, &current_ctx);
-        
-        // Create a new context with this span
-        let cx = current_ctx.with_span(span);
-        
-        Box::new(DatadogSpan { 
+        Box::new(DatadogSpan {
             ctx: cx,
-            name: context.name,
+            name,
+            stats: self.stats.clone(),
         })
     }
     
+    async fn force_flush(&self) -> Result<(), TelemetryError> {
+        debug!("Flushing DatadogTracer");
+        let provider = self.tracer_provider.lock().unwrap().clone();
+        if let Some(provider) = provider {
+            // The batch processor blocks until the exporter has finished
+            tokio::task::spawn_blocking(move || provider.force_flush())
+                .await
+                .map_err(|e| TelemetryError::export(Signal::Traces, e))?
+                .map_err(|e| TelemetryError::from_sdk(Signal::Traces, e))?;
+        }
+
+        Ok(())
+    }
+
+    fn health(&self) -> Option<SignalHealth> {
+        Some(self.stats.snapshot())
+    }
+
     async fn shutdown(&self) -> Result<(), TelemetryError> {
         info!("Shutting down DatadogTracer");
-        let mut provider = self.tracer_provider.lock().unwrap();
-        if let Some(provider) = provider.take() {
-            provider.shutdown()
-                .map_err(|e| TelemetryError::ShutdownError(e.to_string()))?;
+        let provider = self.tracer_provider.lock().unwrap().take();
+        if let Some(provider) = provider {
+            // Shutting down blocks on the final export, keep it off the async workers
+            tokio::task::spawn_blocking(move || provider.shutdown())
+                .await
+                .map_err(|e| TelemetryError::export(Signal::Traces, e))?
+                .map_err(|e| TelemetryError::from_sdk(Signal::Traces, e))?;
+
+            // Nothing can be exported anymore, whatever is left was lost
+            self.stats.settle();
         }
         
         Ok(())
     }
 }
 
+// Start a span as a child of the current context, returning the context holding it
+// and the span's name
+fn start_span<T>(tracer: &T, context: SpanContext) -> (Context, String)
+where
+    T: OtelTracer,
+    T::Span: Send + Sync + 'static,
+{
+    let attributes: Vec<KeyValue> = context.attributes.iter()
+        .map(|(k, v)| to_key_value(k.clone(), v))
+        .collect();
+
+    // Get the current context - will contain parent span if one exists
+    let current_ctx = Context::current();
+    
+    if current_ctx.span().span_context().is_valid() {
+        debug!("Creating child span with parent: {:?}", current_ctx.span().span_context().trace_id());
+    } else {
+        debug!("Creating root span (no parent)");
+    }
+    
+    // Create a span builder
+    let span_builder = tracer.span_builder(context.name.clone())
+        .with_kind(SpanKind::from(context.kind))
+        .with_attributes(attributes)
+        .with_links(context.links.into_iter().map(Link::with_context).collect());
+        
+    // Start the span within the current context (preserving parent relationship)
+    let span = tracer.build_with_context(span_builder, &current_ctx);
+
+    // Create a new context with this span
+    (current_ctx.with_span(span), context.name)
+}
+
 struct DatadogSpan {
     // The context containing the span
     ctx: Context,
     // Store span name for debugging
     name: String,
+    // Export counters of the tracer that created the span
+    stats: Arc<ExportStats>,
 }
 
 impl Span for DatadogSpan {
@@ -129,7 +208,25 @@ impl Span for DatadogSpan {
     }
     
     fn end(&self) {
-        self.ctx.span().end();
+        let span = self.ctx.span();
+        // A span is handed to the processor once, on its first end, and only if sampled
+        if span.is_recording() && span.span_context().is_sampled() {
+            self.stats.record_produced(1);
+        }
+        span.end();
+    }
+
+    fn record_error(&self, report: &ErrorReport) {
+        for (key, value) in report.attributes() {
+            self.set_attribute(key, value);
+        }
+        self.add_event("exception", report.exception_attributes());
+        // Datadog flags spans as errors from their status
+        self.ctx.span().set_status(Status::error(report.message.clone()));
+    }
+
+    fn set_error_status(&self, description: &str) {
+        self.ctx.span().set_status(Status::error(description.to_string()));
     }
 
     fn get_context(&self) -> Context {
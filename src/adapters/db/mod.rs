@@ -0,0 +1,217 @@
+//! Instrumentation of database clients.
+//!
+//! Operations get a `Client` span following OpenTelemetry's database semantic
+//! conventions, e.g. `SELECT orders` with `db.system`, `db.namespace`,
+//! `db.operation.name` and the query text stripped of its literals. Their duration is
+//! recorded in the `db.client.operation.duration` histogram, and the number of rows
+//! they returned in the `db.client.response.returned_rows` one.
+
+mod query;
+#[cfg(feature = "sqlx")]
+pub mod sqlx;
+
+use std::any::type_name;
+use std::error::Error;
+use std::future::Future;
+use std::sync::Arc;
+
+use opentelemetry::context::FutureExt;
+
+use crate::adapters::operation::{duration_histogram, InFlight};
+use crate::domain::metrics::{DatabaseUnit, MetricUnit};
+use crate::domain::telemetry::{AttributeValue, MetricContext, SpanContext, SpanKind};
+use crate::ports::metrics::{Histogram, MetricsPort};
+use crate::ports::tracer::{Span, TracerPort};
+
+#[cfg(feature = "sqlx")]
+pub use self::sqlx::TracedPool;
+pub use query::sanitize_query;
+
+/// Name of the histogram of operation durations, in seconds
+pub const DURATION_METRIC: &str = "db.client.operation.duration";
+
+/// Name of the histogram of the number of rows returned by operations
+pub const RETURNED_ROWS_METRIC: &str = "db.client.response.returned_rows";
+
+/// What a database span is about: the database, and the operation run on it
+#[derive(Debug, Clone)]
+pub struct DbContext {
+    system: String,
+    namespace: Option<String>,
+    collection: Option<String>,
+    operation: Option<String>,
+    query: Option<String>,
+    attributes: Vec<(String, AttributeValue)>,
+}
+
+impl DbContext {
+    /// An operation on a database system, e.g. `postgresql`, `mysql` or `redis`
+    pub fn new(system: impl Into<String>) -> Self {
+        Self {
+            system: system.into(),
+            namespace: None,
+            collection: None,
+            operation: None,
+            query: None,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// The database the operation runs in, e.g. its name or schema
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// The table or collection the operation runs on
+    pub fn with_collection(mut self, collection: impl Into<String>) -> Self {
+        self.collection = Some(collection.into());
+        self
+    }
+
+    /// The name of the operation, e.g. `SELECT` or `findAndModify`
+    pub fn with_operation(mut self, operation: impl Into<String>) -> Self {
+        self.operation = Some(operation.into());
+        self
+    }
+
+    /// The query run by the operation, whose literals are left out, and whose first
+    /// keyword names the operation unless it was named already
+    pub fn with_query(mut self, text: &str) -> Self {
+        let query = sanitize_query(text);
+        if self.operation.is_none() {
+            self.operation = query::operation_name(&query);
+        }
+        self.query = Some(query);
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Vec<(String, AttributeValue)>) -> Self {
+        self.attributes.extend(attributes);
+        self
+    }
+
+    // The span's name, from the most specific of the operation and its target
+    fn name(&self) -> String {
+        let target = self.collection.as_ref().or(self.namespace.as_ref());
+        match (&self.operation, target) {
+            (Some(operation), Some(target)) => format!("{} {}", operation, target),
+            (Some(operation), None) => operation.clone(),
+            (None, Some(target)) => target.clone(),
+            (None, None) => self.system.clone(),
+        }
+    }
+}
+
+/// Creates the spans of database operations and records their metrics
+#[derive(Clone)]
+pub struct DbTracer {
+    tracer: Arc<dyn TracerPort>,
+    duration: Arc<dyn Histogram>,
+    returned_rows: Arc<dyn Histogram>,
+}
+
+impl DbTracer {
+    pub fn new(tracer: Arc<dyn TracerPort>, metrics: &dyn MetricsPort) -> Self {
+        Self {
+            tracer,
+            duration: duration_histogram(
+                metrics,
+                DURATION_METRIC,
+                "Duration of database client operations",
+            ),
+            returned_rows: Arc::from(
+                metrics.create_histogram(
+                    MetricContext::new(RETURNED_ROWS_METRIC.to_string())
+                        .with_description("Number of rows returned by database operations")
+                        .with_unit(MetricUnit::Database(DatabaseUnit::Row)),
+                ),
+            ),
+        }
+    }
+
+    /// Start an operation, child of the current context, whose span is ended and
+    /// duration recorded once it is dropped
+    pub fn start(&self, context: DbContext) -> DbOperation {
+        let name = context.name();
+
+        // The query text would make the cardinality of metrics unbounded
+        let mut metric_attributes = vec![("db.system".to_string(), context.system.into())];
+        for (key, value) in [
+            ("db.namespace", context.namespace),
+            ("db.collection.name", context.collection),
+            ("db.operation.name", context.operation),
+        ] {
+            if let Some(value) = value {
+                metric_attributes.push((key.to_string(), value.into()));
+            }
+        }
+        let mut attributes = metric_attributes.clone();
+        if let Some(query) = context.query {
+            attributes.push(("db.query.text".to_string(), query.into()));
+        }
+        attributes.extend(context.attributes);
+
+        let span = self.tracer.create_span(
+            SpanContext::new(name)
+                .with_kind(SpanKind::Client)
+                .with_attributes(attributes),
+        );
+        DbOperation {
+            in_flight: InFlight::new(span, self.duration.clone(), metric_attributes.clone()),
+            returned_rows: self.returned_rows.clone(),
+            attributes: metric_attributes,
+        }
+    }
+
+    /// Run an operation to completion, failing its span if it returns an error
+    pub async fn run<F, T, E>(&self, context: DbContext, operation: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: Error + 'static,
+    {
+        let started = self.start(context);
+        let result = operation.with_context(started.span().get_context()).await;
+        if let Err(error) = &result {
+            started.fail(error, type_name::<E>());
+        }
+        result
+    }
+}
+
+/// A database operation in flight, see [`DbTracer::start`]
+pub struct DbOperation {
+    in_flight: InFlight,
+    returned_rows: Arc<dyn Histogram>,
+    // Attributes of the metrics, a subset of the span's
+    attributes: Vec<(String, AttributeValue)>,
+}
+
+impl DbOperation {
+    pub fn span(&self) -> &dyn Span {
+        self.in_flight.span()
+    }
+
+    /// Record how many rows the operation returned
+    pub fn returned_rows(&self, rows: u64) {
+        self.in_flight.span().set_attribute(
+            "db.response.returned_rows".to_string(),
+            AttributeValue::Int(rows as i64),
+        );
+        self.returned_rows
+            .record(rows as f64, self.attributes.clone());
+    }
+
+    /// Record the status code returned by the database, e.g. an SQLSTATE
+    pub fn status_code(&mut self, code: &str) {
+        self.in_flight
+            .record("db.response.status_code", code.into());
+    }
+
+    /// Mark the operation as failed, with the type of error it failed with, e.g. its
+    /// status code
+    pub fn fail(self, error: &(dyn Error + 'static), error_type: &str) {
+        self.in_flight.span().record_exception(error);
+        self.in_flight.fail(error_type, &error.to_string());
+    }
+}
@@ -0,0 +1,148 @@
+//! Sanitisation of query texts, leaving out the values they were written with.
+
+/// Replace the literals of a query with `?` and drop its comments, e.g.
+/// `SELECT * FROM users WHERE name = 'bob' AND age > 42` becomes
+/// `SELECT * FROM users WHERE name = ? AND age > ?`. Placeholders such as `$1` are kept.
+pub fn sanitize_query(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut sanitized = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        i = match c {
+            '\'' => {
+                sanitized.push('?');
+                skip_string(&chars, i + 1)
+            }
+            // Quoted identifiers are kept as they are
+            '"' | '`' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&quote| quote == c)
+                    .map_or(chars.len(), |position| i + position + 2);
+                sanitized.extend(&chars[i..end]);
+                end
+            }
+            '-' if next == Some('-') => chars[i..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |position| i + position),
+            '/' if next == Some('*') => {
+                let end = chars[i + 2..]
+                    .windows(2)
+                    .position(|window| window == ['*', '/'])
+                    .map_or(chars.len(), |position| i + position + 4);
+                // Comments may be all that separates two words
+                sanitized.push(' ');
+                end
+            }
+            '$' if !follows_identifier(&sanitized) => match skip_dollar_quoted(&chars, i) {
+                Some(end) => {
+                    sanitized.push('?');
+                    end
+                }
+                None => {
+                    sanitized.push(c);
+                    i + 1
+                }
+            },
+            c if c.is_ascii_digit() && !follows_identifier(&sanitized) => {
+                sanitized.push('?');
+                skip_number(&chars, i)
+            }
+            c => {
+                sanitized.push(c);
+                i + 1
+            }
+        };
+    }
+    sanitized
+}
+
+/// The operation of a query, from its first keyword, e.g. `SELECT`
+pub(crate) fn operation_name(text: &str) -> Option<String> {
+    let keyword = text
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()?;
+    (!keyword.is_empty()).then(|| keyword.to_ascii_uppercase())
+}
+
+// Whether the next character continues an identifier or placeholder, e.g. `table1` or `$1`
+fn follows_identifier(sanitized: &str) -> bool {
+    sanitized
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+// The end of a string literal starting at `start`, whose quotes are escaped by doubling
+// them or with backslashes
+fn skip_string(chars: &[char], start: usize) -> usize {
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '\'' if chars.get(i + 1) == Some(&'\'') => i += 2,
+            '\'' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+// The end of a dollar-quoted string starting at `start`, e.g. `$body$...$body$`
+fn skip_dollar_quoted(chars: &[char], start: usize) -> Option<usize> {
+    let tag_length = chars[start + 1..]
+        .iter()
+        .position(|&c| c == '$')
+        .filter(|&length| {
+            chars[start + 1..start + 1 + length]
+                .iter()
+                .enumerate()
+                .all(|(j, &c)| c == '_' || c.is_alphabetic() || (j > 0 && c.is_alphanumeric()))
+        })?;
+    let tag = &chars[start..start + tag_length + 2];
+
+    let body = start + tag.len();
+    let end = chars[body..]
+        .windows(tag.len())
+        .position(|window| window == tag)
+        .map_or(chars.len(), |position| body + position + tag.len());
+    Some(end)
+}
+
+// The end of a number starting at `start`, e.g. `42`, `3.14`, `1e-3` or `0xff`
+fn skip_number(chars: &[char], start: usize) -> usize {
+    let mut i = start;
+    if chars[i] == '0' && matches!(chars.get(i + 1), Some('x' | 'X')) {
+        i += 2;
+        while chars.get(i).is_some_and(char::is_ascii_hexdigit) {
+            i += 1;
+        }
+        return i;
+    }
+
+    while chars
+        .get(i)
+        .is_some_and(|c| c.is_ascii_digit() || *c == '.')
+    {
+        i += 1;
+    }
+    if matches!(chars.get(i), Some('e' | 'E')) {
+        let exponent = if matches!(chars.get(i + 1), Some('+' | '-')) {
+            i + 2
+        } else {
+            i + 1
+        };
+        if chars.get(exponent).is_some_and(char::is_ascii_digit) {
+            i = exponent;
+            while chars.get(i).is_some_and(char::is_ascii_digit) {
+                i += 1;
+            }
+        }
+    }
+    i
+}
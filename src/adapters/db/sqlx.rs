@@ -0,0 +1,151 @@
+//! A sqlx pool tracing and measuring the queries run through it.
+//!
+//! The database system is named after the pool's driver, and the query text and
+//! operation are taken from the query's SQL. Failed queries are typed after the
+//! status code returned by the database when there is one, e.g. an SQLSTATE.
+
+use ::sqlx::{Database, Error, Execute, Executor, Pool};
+use opentelemetry::context::FutureExt;
+
+use super::{DbContext, DbOperation, DbTracer};
+use crate::adapters::operation::OTHER_ERROR;
+
+/// Wraps a sqlx pool, tracing and measuring the queries run through it
+pub struct TracedPool<DB: Database> {
+    pool: Pool<DB>,
+    tracer: DbTracer,
+    namespace: Option<String>,
+}
+
+impl<DB: Database> Clone for TracedPool<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            tracer: self.tracer.clone(),
+            namespace: self.namespace.clone(),
+        }
+    }
+}
+
+impl<DB: Database> TracedPool<DB>
+where
+    for<'c> &'c Pool<DB>: Executor<'c, Database = DB>,
+{
+    pub fn new(pool: Pool<DB>, tracer: DbTracer) -> Self {
+        Self {
+            pool,
+            tracer,
+            namespace: None,
+        }
+    }
+
+    /// The database the queries run in, e.g. its name
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// The wrapped pool, whose queries are not traced when run directly
+    pub fn inner(&self) -> &Pool<DB> {
+        &self.pool
+    }
+
+    /// Run a query, returning how many rows it affected
+    pub async fn execute<'q, E>(&self, query: E) -> Result<DB::QueryResult, Error>
+    where
+        E: Execute<'q, DB> + 'q,
+    {
+        let operation = self.start(&query);
+        let context = operation.span().get_context();
+        let result = self.pool.execute(query).with_context(context).await;
+        complete(operation, result, |_| None)
+    }
+
+    /// Run a query, returning every row it selected
+    pub async fn fetch_all<'q, E>(&self, query: E) -> Result<Vec<DB::Row>, Error>
+    where
+        E: Execute<'q, DB> + 'q,
+    {
+        let operation = self.start(&query);
+        let context = operation.span().get_context();
+        let result = self.pool.fetch_all(query).with_context(context).await;
+        complete(operation, result, |rows| Some(rows.len() as u64))
+    }
+
+    /// Run a query, returning the single row it selected
+    pub async fn fetch_one<'q, E>(&self, query: E) -> Result<DB::Row, Error>
+    where
+        E: Execute<'q, DB> + 'q,
+    {
+        let operation = self.start(&query);
+        let context = operation.span().get_context();
+        let result = self.pool.fetch_one(query).with_context(context).await;
+        complete(operation, result, |_| Some(1))
+    }
+
+    /// Run a query, returning the row it selected, if any
+    pub async fn fetch_optional<'q, E>(&self, query: E) -> Result<Option<DB::Row>, Error>
+    where
+        E: Execute<'q, DB> + 'q,
+    {
+        let operation = self.start(&query);
+        let context = operation.span().get_context();
+        let result = self.pool.fetch_optional(query).with_context(context).await;
+        complete(operation, result, |row| Some(u64::from(row.is_some())))
+    }
+
+    fn start<'q, E: Execute<'q, DB>>(&self, query: &E) -> DbOperation {
+        let mut context = DbContext::new(system(DB::NAME)).with_query(query.sql());
+        if let Some(namespace) = &self.namespace {
+            context = context.with_namespace(namespace.clone());
+        }
+        self.tracer.start(context)
+    }
+}
+
+// Record the outcome of a query, with the number of rows it returned if it selected any
+fn complete<T>(
+    mut operation: DbOperation,
+    result: Result<T, Error>,
+    returned_rows: impl FnOnce(&T) -> Option<u64>,
+) -> Result<T, Error> {
+    match &result {
+        Ok(value) => {
+            if let Some(rows) = returned_rows(value) {
+                operation.returned_rows(rows);
+            }
+        }
+        Err(error) => {
+            let error_type = match error {
+                Error::Database(database_error) => match database_error.code() {
+                    Some(code) => {
+                        operation.status_code(&code);
+                        code.into_owned()
+                    }
+                    None => "database".to_string(),
+                },
+                Error::RowNotFound => "row_not_found".to_string(),
+                Error::PoolTimedOut => "pool_timed_out".to_string(),
+                Error::PoolClosed => "pool_closed".to_string(),
+                Error::Io(_) => "io".to_string(),
+                Error::Tls(_) => "tls".to_string(),
+                Error::Protocol(_) => "protocol".to_string(),
+                Error::ColumnDecode { .. } | Error::Decode(_) => "decode".to_string(),
+                _ => OTHER_ERROR.to_string(),
+            };
+            operation.fail(error, &error_type);
+        }
+    }
+    result
+}
+
+// The name of a driver's database system in the semantic conventions
+fn system(name: &str) -> String {
+    match name {
+        "PostgreSQL" => "postgresql".to_string(),
+        "MySQL" => "mysql".to_string(),
+        "SQLite" => "sqlite".to_string(),
+        "MSSQL" => "mssql".to_string(),
+        name => name.to_lowercase(),
+    }
+}
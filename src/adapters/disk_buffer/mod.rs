@@ -0,0 +1,389 @@
+//! Disk buffering for exporters on unreliable links.
+//!
+//! The wrappers in this module hand every batch to the wrapped exporter. When that
+//! export fails, the batch is serialized as an OTLP request and spooled to a bounded
+//! [`DiskQueue`]. A background task replays the queue in order, with exponential
+//! backoff, as soon as the collector is reachable again. While batches are queued,
+//! newer ones are queued behind them so they reach the collector in order.
+//!
+//! Exports, replays and the batches the queue drops are all counted in the signal's
+//! [`ExportStats`], so the wrapped exporter must not count them again.
+//!
+//! Replays reach the collector with the endpoint, metadata and TLS settings of the
+//! [`DiskBufferConfig`], which [`DiskBufferConfig::configure_exporter`] applies to the
+//! wrapped exporter as well. Like the exporter, the replayer also sends the headers set
+//! in `OTEL_EXPORTER_OTLP_HEADERS` or the signal's own variable.
+
+mod queue;
+mod replay;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use opentelemetry_otlp::{WithExportConfig, WithTonicConfig};
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema;
+use opentelemetry_proto::transform::logs::tonic::group_logs_by_resource_and_scope;
+use opentelemetry_proto::transform::trace::tonic::group_spans_by_resource_and_scope;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::logs::{LogBatch, LogExporter};
+use opentelemetry_sdk::metrics::data::ResourceMetrics;
+use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+use opentelemetry_sdk::metrics::Temporality;
+use opentelemetry_sdk::trace::{SpanData, SpanExporter};
+use opentelemetry_sdk::Resource;
+use prost::Message;
+use tonic::metadata::MetadataMap;
+#[cfg(feature = "tls")]
+use tonic::transport::ClientTlsConfig;
+use tracing::warn;
+
+use crate::adapters::instrumented::metric_streams;
+use crate::domain::health::ExportStats;
+use crate::domain::telemetry::{Signal, TelemetryError};
+use replay::Replayer;
+
+pub use queue::{DiskQueue, QueuedBatch};
+
+/// Configuration of an exporter's on-disk queue
+#[derive(Debug, Clone)]
+pub struct DiskBufferConfig {
+    /// Directory holding the queued batches
+    pub directory: PathBuf,
+    /// Largest total size of the queue, the oldest batches are dropped beyond it
+    pub max_bytes: u64,
+    /// Batches older than this are dropped instead of replayed
+    pub max_age: Duration,
+    /// OTLP gRPC endpoint the queued batches are replayed to
+    pub endpoint: String,
+    /// Metadata sent with every replayed batch, e.g. the collector's credentials
+    pub metadata: MetadataMap,
+    /// TLS settings of the connection to the collector
+    #[cfg(feature = "tls")]
+    pub tls: Option<ClientTlsConfig>,
+    /// Delay before the first replay retry
+    pub initial_backoff: Duration,
+    /// Longest delay between replay retries
+    pub max_backoff: Duration,
+}
+
+impl DiskBufferConfig {
+    /// Buffer in `directory`, with a 64 MiB and 24 hour limit
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            max_bytes: 64 * 1024 * 1024,
+            max_age: Duration::from_secs(24 * 60 * 60),
+            endpoint: std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+                .unwrap_or_else(|_| "http://localhost:4317".to_string()),
+            metadata: MetadataMap::new(),
+            #[cfg(feature = "tls")]
+            tls: None,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: MetadataMap) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    #[cfg(feature = "tls")]
+    pub fn with_tls_config(mut self, tls: ClientTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Send the exporter's batches to the collector batches are replayed to, with the
+    /// same metadata and TLS settings
+    pub fn configure_exporter<B>(&self, builder: B) -> B
+    where
+        B: WithExportConfig + WithTonicConfig,
+    {
+        let builder = builder
+            .with_endpoint(self.endpoint.clone())
+            .with_metadata(self.metadata.clone());
+        #[cfg(feature = "tls")]
+        let builder = match &self.tls {
+            Some(tls) => builder.with_tls_config(tls.clone()),
+            None => builder,
+        };
+        builder
+    }
+
+    /// The same configuration, queuing in a subdirectory.
+    /// Every exporter needs a queue of its own.
+    pub fn for_subdirectory(&self, name: &str) -> Self {
+        Self {
+            directory: self.directory.join(name),
+            ..self.clone()
+        }
+    }
+}
+
+// The queue of a single exporter and the task replaying it
+#[derive(Debug)]
+struct SignalBuffer {
+    signal: Signal,
+    queue: Arc<DiskQueue>,
+    replayer: Replayer,
+    stats: Arc<ExportStats>,
+}
+
+impl SignalBuffer {
+    fn open(
+        signal: Signal,
+        config: &DiskBufferConfig,
+        stats: Arc<ExportStats>,
+    ) -> Result<Self, TelemetryError> {
+        let queue = DiskQueue::open(&config.directory, config.max_bytes, config.max_age)
+            .map_err(|e| TelemetryError::exporter_build(signal, e))?;
+        let queue = Arc::new(queue);
+        let replayer = Replayer::spawn(signal, queue.clone(), config, stats.clone())?;
+
+        Ok(Self {
+            signal,
+            queue,
+            replayer,
+            stats,
+        })
+    }
+
+    // Returns true while older batches are waiting to be replayed
+    fn is_backlogged(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    fn spool(&self, request: impl Message, records: u64) {
+        match self.queue.push(&request.encode_to_vec(), records) {
+            Ok(0) => {}
+            Ok(dropped) => {
+                warn!(
+                    "Disk buffer for {} is full, dropped {} records",
+                    self.signal, dropped
+                );
+                self.stats.record_dropped(dropped);
+            }
+            Err(e) => {
+                warn!("Failed to spool {} batch to disk: {}", self.signal, e);
+                self.stats.record_dropped(records);
+            }
+        }
+        self.replayer.wake();
+    }
+}
+
+/// A span exporter spooling batches to disk when the wrapped exporter fails
+#[derive(Debug)]
+pub struct BufferedSpanExporter<E> {
+    inner: E,
+    buffer: SignalBuffer,
+    resource: ResourceAttributesWithSchema,
+}
+
+impl<E> BufferedSpanExporter<E> {
+    /// Wrap `inner`, counting exports and replays in `stats`
+    pub fn new(
+        inner: E,
+        config: &DiskBufferConfig,
+        stats: Arc<ExportStats>,
+    ) -> Result<Self, TelemetryError> {
+        Ok(Self {
+            inner,
+            buffer: SignalBuffer::open(Signal::Traces, config, stats)?,
+            resource: ResourceAttributesWithSchema::default(),
+        })
+    }
+
+    fn encode(&self, batch: Vec<SpanData>) -> ExportTraceServiceRequest {
+        ExportTraceServiceRequest {
+            resource_spans: group_spans_by_resource_and_scope(batch, &self.resource),
+        }
+    }
+}
+
+impl<E: SpanExporter> SpanExporter for BufferedSpanExporter<E> {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        let count = batch.len() as u64;
+        if self.buffer.is_backlogged() {
+            self.buffer.spool(self.encode(batch), count);
+            return Ok(());
+        }
+
+        match self.inner.export(batch.clone()).await {
+            Ok(()) => self.buffer.stats.record_exported(count),
+            Err(e) => {
+                warn!("Failed to export traces, spooling to disk: {}", e);
+                self.buffer.stats.record_error(e);
+                self.buffer.spool(self.encode(batch), count);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> OTelSdkResult {
+        self.buffer.replayer.stop();
+        self.inner.shutdown()
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.resource = resource.into();
+        self.inner.set_resource(resource)
+    }
+}
+
+/// A log exporter spooling batches to disk when the wrapped exporter fails
+#[derive(Debug)]
+pub struct BufferedLogExporter<E> {
+    inner: E,
+    buffer: SignalBuffer,
+    resource: ResourceAttributesWithSchema,
+}
+
+impl<E> BufferedLogExporter<E> {
+    /// Wrap `inner`, counting exports and replays in `stats`
+    pub fn new(
+        inner: E,
+        config: &DiskBufferConfig,
+        stats: Arc<ExportStats>,
+    ) -> Result<Self, TelemetryError> {
+        Ok(Self {
+            inner,
+            buffer: SignalBuffer::open(Signal::Logs, config, stats)?,
+            resource: ResourceAttributesWithSchema::default(),
+        })
+    }
+
+    fn encode(&self, batch: LogBatch<'_>) -> ExportLogsServiceRequest {
+        ExportLogsServiceRequest {
+            resource_logs: group_logs_by_resource_and_scope(batch, &self.resource),
+        }
+    }
+}
+
+impl<E: LogExporter> LogExporter for BufferedLogExporter<E> {
+    async fn export(&self, batch: LogBatch<'_>) -> OTelSdkResult {
+        // The batch is consumed by the export, keep the records around to spool them
+        let records: Vec<_> = batch.iter().collect();
+        let count = records.len() as u64;
+        if self.buffer.is_backlogged() {
+            self.buffer
+                .spool(self.encode(LogBatch::new(&records)), count);
+            return Ok(());
+        }
+
+        match self.inner.export(LogBatch::new(&records)).await {
+            Ok(()) => self.buffer.stats.record_exported(count),
+            Err(e) => {
+                warn!("Failed to export logs, spooling to disk: {}", e);
+                self.buffer.stats.record_error(e);
+                self.buffer
+                    .spool(self.encode(LogBatch::new(&records)), count);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn shutdown(&self) -> OTelSdkResult {
+        self.buffer.replayer.stop();
+        self.inner.shutdown()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.resource = resource.into();
+        self.inner.set_resource(resource)
+    }
+}
+
+/// A metric exporter spooling exports to disk when the wrapped exporter fails
+#[derive(Debug)]
+pub struct BufferedMetricExporter<E> {
+    inner: E,
+    buffer: SignalBuffer,
+}
+
+impl<E> BufferedMetricExporter<E> {
+    /// Wrap `inner`, counting exports and replays in `stats`
+    pub fn new(
+        inner: E,
+        config: &DiskBufferConfig,
+        stats: Arc<ExportStats>,
+    ) -> Result<Self, TelemetryError> {
+        Ok(Self {
+            inner,
+            buffer: SignalBuffer::open(Signal::Metrics, config, stats)?,
+        })
+    }
+}
+
+impl<E: PushMetricExporter> PushMetricExporter for BufferedMetricExporter<E> {
+    async fn export(&self, metrics: &mut ResourceMetrics) -> OTelSdkResult {
+        let count = metric_streams(metrics);
+        if self.buffer.is_backlogged() {
+            self.buffer
+                .spool(ExportMetricsServiceRequest::from(&*metrics), count);
+            return Ok(());
+        }
+
+        match self.inner.export(metrics).await {
+            Ok(()) => self.buffer.stats.record_exported(count),
+            Err(e) => {
+                warn!("Failed to export metrics, spooling to disk: {}", e);
+                self.buffer.stats.record_error(e);
+                self.buffer
+                    .spool(ExportMetricsServiceRequest::from(&*metrics), count);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> OTelSdkResult {
+        self.buffer.replayer.stop();
+        self.inner.shutdown()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.buffer.replayer.stop();
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn temporality(&self) -> Temporality {
+        self.inner.temporality()
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing::warn;
+
+const BATCH_EXTENSION: &str = "batch";
+const TEMP_EXTENSION: &str = "tmp";
+
+/// A batch stored in the queue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedBatch {
+    seq: u64,
+    written_at_ms: u64,
+    records: u64,
+    size: u64,
+}
+
+impl QueuedBatch {
+    // Batches are named after their position in the queue, when they were written and
+    // how many records they hold, so that the queue can be rebuilt from the directory alone
+    fn file_name(&self) -> String {
+        format!(
+            "{:020}-{}-{}.{}",
+            self.seq, self.written_at_ms, self.records, BATCH_EXTENSION
+        )
+    }
+
+    fn parse(path: &Path, size: u64) -> Option<Self> {
+        let mut parts = path.file_stem()?.to_str()?.splitn(3, '-');
+        Some(Self {
+            seq: parts.next()?.parse().ok()?,
+            written_at_ms: parts.next()?.parse().ok()?,
+            records: parts.next()?.parse().ok()?,
+            size,
+        })
+    }
+
+    /// Number of records (spans, log records or metric streams) in the batch
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Size of the serialized batch in bytes
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn age(&self, now_ms: u64) -> Duration {
+        Duration::from_millis(now_ms.saturating_sub(self.written_at_ms))
+    }
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    batches: VecDeque<QueuedBatch>,
+    total_bytes: u64,
+    next_seq: u64,
+    // Records of the batches discarded while looking for the front of the queue
+    discarded_records: u64,
+}
+
+impl QueueState {
+    fn pop_front(&mut self, directory: &Path) -> Option<QueuedBatch> {
+        let batch = self.batches.pop_front()?;
+        self.total_bytes -= batch.size;
+        if let Err(e) = fs::remove_file(directory.join(batch.file_name())) {
+            if e.kind() != io::ErrorKind::NotFound {
+                warn!(
+                    "Failed to remove spooled batch {}: {}",
+                    batch.file_name(),
+                    e
+                );
+            }
+        }
+        Some(batch)
+    }
+}
+
+/// A bounded FIFO queue of serialized batches, persisted in a directory.
+///
+/// Every batch is a single file, so the queue survives restarts and is replayed in
+/// the order it was written. The oldest batches are evicted when the queue grows
+/// over `max_bytes`, and batches older than `max_age` are discarded.
+#[derive(Debug)]
+pub struct DiskQueue {
+    directory: PathBuf,
+    max_bytes: u64,
+    max_age: Duration,
+    state: Mutex<QueueState>,
+}
+
+impl DiskQueue {
+    /// Open the queue in `directory`, picking up batches left by a previous run
+    pub fn open(
+        directory: impl Into<PathBuf>,
+        max_bytes: u64,
+        max_age: Duration,
+    ) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        let mut batches = Vec::new();
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == TEMP_EXTENSION) {
+                // A write that did not complete, the batch was never queued
+                fs::remove_file(&path)?;
+                continue;
+            }
+            if path.extension().is_some_and(|ext| ext == BATCH_EXTENSION) {
+                if let Some(batch) = QueuedBatch::parse(&path, fs::metadata(&path)?.len()) {
+                    batches.push(batch);
+                }
+            }
+        }
+        batches.sort_by_key(|batch| batch.seq);
+
+        let state = QueueState {
+            next_seq: batches.last().map_or(0, |batch| batch.seq + 1),
+            total_bytes: batches.iter().map(|batch| batch.size).sum(),
+            batches: batches.into(),
+            discarded_records: 0,
+        };
+
+        Ok(Self {
+            directory,
+            max_bytes,
+            max_age,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Append a batch of `records` records at the end of the queue.
+    ///
+    /// Returns the number of records dropped to stay within the size limit, which
+    /// includes those of the new batch itself if it is larger than the whole queue.
+    pub fn push(&self, bytes: &[u8], records: u64) -> io::Result<u64> {
+        let size = bytes.len() as u64;
+        let mut state = self.state.lock().unwrap();
+
+        if size > self.max_bytes {
+            return Ok(records);
+        }
+
+        let mut dropped = 0;
+        while state.total_bytes + size > self.max_bytes {
+            if let Some(batch) = state.pop_front(&self.directory) {
+                dropped += batch.records;
+            }
+        }
+
+        let batch = QueuedBatch {
+            seq: state.next_seq,
+            written_at_ms: now_ms(),
+            records,
+            size,
+        };
+
+        // Write next to the final file and rename, so a crash never leaves half a batch
+        let path = self.directory.join(batch.file_name());
+        let temp_path = path.with_extension(TEMP_EXTENSION);
+        fs::write(&temp_path, bytes)?;
+        fs::rename(&temp_path, &path)?;
+
+        state.next_seq += 1;
+        state.total_bytes += size;
+        state.batches.push_back(batch);
+
+        Ok(dropped)
+    }
+
+    /// Read the oldest batch, discarding any batch that expired first.
+    ///
+    /// The batch stays in the queue until it is removed with [`DiskQueue::remove`].
+    pub fn front(&self) -> io::Result<Option<(QueuedBatch, Vec<u8>)>> {
+        let mut state = self.state.lock().unwrap();
+        let now_ms = now_ms();
+
+        while let Some(batch) = state.batches.front() {
+            if batch.age(now_ms) > self.max_age {
+                state.discarded_records += batch.records;
+                state.pop_front(&self.directory);
+                continue;
+            }
+
+            let batch = batch.clone();
+            return match fs::read(self.directory.join(batch.file_name())) {
+                Ok(bytes) => Ok(Some((batch, bytes))),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    // Removed from under us, skip it
+                    state.discarded_records += batch.records;
+                    state.pop_front(&self.directory);
+                    continue;
+                }
+                Err(e) => Err(e),
+            };
+        }
+
+        Ok(None)
+    }
+
+    /// Remove a batch returned by [`DiskQueue::front`] once it has been delivered
+    pub fn remove(&self, batch: &QueuedBatch) {
+        let mut state = self.state.lock().unwrap();
+        if state.batches.front() == Some(batch) {
+            state.pop_front(&self.directory);
+        }
+    }
+
+    /// Records of the batches [`DiskQueue::front`] discarded since the last call, because
+    /// they expired or were removed from the directory
+    pub fn take_discarded(&self) -> u64 {
+        std::mem::take(&mut self.state.lock().unwrap().discarded_records)
+    }
+
+    /// Number of batches in the queue
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().batches.len()
+    }
+
+    /// Returns true if no batch is waiting to be replayed
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total size of the queued batches in bytes
+    pub fn size_bytes(&self) -> u64 {
+        self.state.lock().unwrap().total_bytes
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
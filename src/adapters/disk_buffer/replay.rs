@@ -0,0 +1,262 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use opentelemetry_otlp::{
+    OTEL_EXPORTER_OTLP_HEADERS, OTEL_EXPORTER_OTLP_LOGS_HEADERS,
+    OTEL_EXPORTER_OTLP_METRICS_HEADERS, OTEL_EXPORTER_OTLP_TRACES_HEADERS,
+};
+use opentelemetry_proto::tonic::collector::logs::v1::logs_service_client::LogsServiceClient;
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+use opentelemetry_proto::tonic::collector::metrics::v1::metrics_service_client::MetricsServiceClient;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::collector::trace::v1::trace_service_client::TraceServiceClient;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use percent_encoding::percent_decode_str;
+use prost::Message;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Request, Status};
+use tracing::{debug, info, warn};
+
+use super::queue::DiskQueue;
+use super::DiskBufferConfig;
+use crate::domain::health::ExportStats;
+use crate::domain::telemetry::{Signal, TelemetryError};
+
+// Time given to the collector to accept a replayed batch
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(10);
+
+// The OTLP service client of a signal
+#[derive(Debug, Clone)]
+enum ServiceClient {
+    Traces(TraceServiceClient<Channel>),
+    Metrics(MetricsServiceClient<Channel>),
+    Logs(LogsServiceClient<Channel>),
+}
+
+/// Sends spooled OTLP requests straight to the collector, the way the exporter would
+#[derive(Debug)]
+struct OtlpClient {
+    client: ServiceClient,
+    metadata: MetadataMap,
+}
+
+impl OtlpClient {
+    fn connect(signal: Signal, config: &DiskBufferConfig) -> Result<Self, TelemetryError> {
+        let endpoint = Endpoint::from_shared(config.endpoint.clone())
+            .map_err(|e| {
+                TelemetryError::config_with_source(
+                    format!("Invalid disk buffer endpoint {}", config.endpoint),
+                    e,
+                )
+            })?
+            .timeout(REPLAY_TIMEOUT);
+        #[cfg(feature = "tls")]
+        let endpoint = match &config.tls {
+            Some(tls) => endpoint.tls_config(tls.clone()).map_err(|e| {
+                TelemetryError::config_with_source("Invalid disk buffer TLS configuration", e)
+            })?,
+            None => endpoint,
+        };
+        let channel = endpoint.connect_lazy();
+
+        let mut metadata = config.metadata.clone();
+        for (key, value) in headers_from_env(signal) {
+            match (
+                MetadataKey::from_bytes(key.as_bytes()),
+                MetadataValue::try_from(value.as_str()),
+            ) {
+                (Ok(key), Ok(value)) => {
+                    metadata.insert(key, value);
+                }
+                _ => warn!(
+                    "Ignoring invalid OTLP header {} for {} replays",
+                    key, signal
+                ),
+            }
+        }
+
+        let client = match signal {
+            Signal::Traces => ServiceClient::Traces(TraceServiceClient::new(channel)),
+            Signal::Metrics => ServiceClient::Metrics(MetricsServiceClient::new(channel)),
+            Signal::Logs => ServiceClient::Logs(LogsServiceClient::new(channel)),
+        };
+
+        Ok(Self { client, metadata })
+    }
+
+    async fn send(&self, bytes: &[u8]) -> Result<(), Status> {
+        let decode_error = |e: prost::DecodeError| Status::invalid_argument(e.to_string());
+
+        match self.client.clone() {
+            ServiceClient::Traces(mut client) => {
+                let message = ExportTraceServiceRequest::decode(bytes).map_err(decode_error)?;
+                client.export(self.request(message)).await?;
+            }
+            ServiceClient::Metrics(mut client) => {
+                let message = ExportMetricsServiceRequest::decode(bytes).map_err(decode_error)?;
+                client.export(self.request(message)).await?;
+            }
+            ServiceClient::Logs(mut client) => {
+                let message = ExportLogsServiceRequest::decode(bytes).map_err(decode_error)?;
+                client.export(self.request(message)).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        *request.metadata_mut() = self.metadata.clone();
+        request
+    }
+}
+
+// The headers the OTLP exporter of a signal reads from the environment: those of the
+// signal's own variable, or else of the variable shared by all signals. Values may be
+// percent-encoded.
+fn headers_from_env(signal: Signal) -> Vec<(String, String)> {
+    let signal_headers = match signal {
+        Signal::Traces => OTEL_EXPORTER_OTLP_TRACES_HEADERS,
+        Signal::Metrics => OTEL_EXPORTER_OTLP_METRICS_HEADERS,
+        Signal::Logs => OTEL_EXPORTER_OTLP_LOGS_HEADERS,
+    };
+    let Ok(headers) =
+        std::env::var(signal_headers).or_else(|_| std::env::var(OTEL_EXPORTER_OTLP_HEADERS))
+    else {
+        return Vec::new();
+    };
+
+    headers
+        .split_terminator(',')
+        .filter_map(|header| {
+            let (key, value) = header.split_once('=')?;
+            let value = percent_decode_str(value.trim()).decode_utf8().ok()?;
+            Some((key.trim().to_string(), value.into_owned()))
+        })
+        .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+        .collect()
+}
+
+// Errors the collector would return again for the same batch
+fn is_permanent(status: &Status) -> bool {
+    matches!(
+        status.code(),
+        Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange | Code::Unimplemented
+    )
+}
+
+/// Background task replaying a queue in order whenever the collector is reachable, and
+/// counting what it delivers or drops in the signal's stats
+#[derive(Debug)]
+pub(crate) struct Replayer {
+    notify: Arc<Notify>,
+    task: JoinHandle<()>,
+}
+
+impl Replayer {
+    /// Start replaying `queue`, which must be called from within a tokio runtime
+    pub(crate) fn spawn(
+        signal: Signal,
+        queue: Arc<DiskQueue>,
+        config: &DiskBufferConfig,
+        stats: Arc<ExportStats>,
+    ) -> Result<Self, TelemetryError> {
+        let client = OtlpClient::connect(signal, config)?;
+        let runtime = tokio::runtime::Handle::try_current().map_err(|e| {
+            TelemetryError::config_with_source("Disk buffering requires a tokio runtime", e)
+        })?;
+
+        let notify = Arc::new(Notify::new());
+        let initial_backoff = config.initial_backoff;
+        let max_backoff = config.max_backoff;
+
+        let task = runtime.spawn({
+            let notify = notify.clone();
+            async move {
+                let mut backoff = initial_backoff;
+                loop {
+                    match replay(signal, &queue, &client, &stats).await {
+                        Ok(0) => {
+                            // Nothing left, wait for the next spooled batch
+                            backoff = initial_backoff;
+                            notify.notified().await;
+                        }
+                        Ok(replayed) => {
+                            info!("Replayed {} spooled {} batches", replayed, signal);
+                            backoff = initial_backoff;
+                        }
+                        Err(e) => {
+                            debug!(
+                                "Collector still unreachable for {}, retrying in {:?}: {}",
+                                signal, backoff, e
+                            );
+                            stats.record_error(e.message());
+                            tokio::time::sleep(backoff).await;
+                            backoff = (backoff * 2).min(max_backoff);
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self { notify, task })
+    }
+
+    /// Wake the replayer up after a batch was spooled
+    pub(crate) fn wake(&self) {
+        self.notify.notify_one();
+    }
+
+    /// Stop replaying, whatever is left stays on disk for the next run
+    pub(crate) fn stop(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for Replayer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+// Replay every queued batch, oldest first, until the queue is empty or a send fails
+async fn replay(
+    signal: Signal,
+    queue: &DiskQueue,
+    client: &OtlpClient,
+    stats: &ExportStats,
+) -> Result<u64, Status> {
+    let mut replayed = 0;
+
+    loop {
+        let front = queue.front();
+        // Expired batches are skipped when looking for the front
+        stats.record_dropped(queue.take_discarded());
+        let (batch, bytes) = match front {
+            Ok(Some(front)) => front,
+            Ok(None) => return Ok(replayed),
+            Err(e) => return Err(Status::internal(e.to_string())),
+        };
+
+        match client.send(&bytes).await {
+            Ok(()) => {
+                replayed += 1;
+                stats.record_exported(batch.records());
+            }
+            Err(status) if is_permanent(&status) => {
+                warn!(
+                    "Dropping spooled {} batch rejected by the collector: {}",
+                    signal,
+                    status.message()
+                );
+                stats.record_failed(batch.records(), status.message());
+            }
+            Err(status) => return Err(status),
+        }
+        queue.remove(&batch);
+    }
+}
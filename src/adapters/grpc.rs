@@ -0,0 +1,334 @@
+//! Tower layers tracing and measuring the calls of tonic gRPC servers and clients.
+//!
+//! Calls get a `Server` or `Client` span named after their service and method, e.g.
+//! `helloworld.Greeter/SayHello`, whose trace context is extracted from or injected
+//! in the call's metadata. Their duration, until the status is received, is recorded
+//! in the `rpc.server.call.duration` or `rpc.client.call.duration` histogram.
+//!
+//! Servers take the layer with `Server::builder().layer(..)`, and clients wrap their
+//! channel with it before creating the generated client.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{self, ready, Poll};
+
+use http::{HeaderMap, Request, Response};
+use http_body::{Body, Frame, SizeHint};
+use opentelemetry::Context;
+use percent_encoding::percent_decode;
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::adapters::http::propagation::{extract_context, inject_context};
+use crate::adapters::operation::{duration_histogram, InFlight, OTHER_ERROR};
+use crate::domain::telemetry::{SpanContext, SpanKind};
+use crate::ports::metrics::{Histogram, MetricsPort};
+use crate::ports::tracer::TracerPort;
+
+/// Name of the histogram of server call durations, in seconds
+pub const SERVER_DURATION_METRIC: &str = "rpc.server.call.duration";
+
+/// Name of the histogram of client call durations, in seconds
+pub const CLIENT_DURATION_METRIC: &str = "rpc.client.call.duration";
+
+/// Traces and measures the calls handled by a gRPC server
+#[derive(Clone)]
+pub struct GrpcServerLayer {
+    tracer: Arc<dyn TracerPort>,
+    duration: Arc<dyn Histogram>,
+}
+
+impl GrpcServerLayer {
+    pub fn new(tracer: Arc<dyn TracerPort>, metrics: &dyn MetricsPort) -> Self {
+        Self {
+            tracer,
+            duration: duration_histogram(
+                metrics,
+                SERVER_DURATION_METRIC,
+                "Duration of gRPC server calls",
+            ),
+        }
+    }
+}
+
+impl<S> Layer<S> for GrpcServerLayer {
+    type Service = TracedGrpcService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TracedGrpcService {
+            inner,
+            tracer: self.tracer.clone(),
+            duration: self.duration.clone(),
+            kind: SpanKind::Server,
+        }
+    }
+}
+
+/// Traces and measures the calls sent through a gRPC channel
+#[derive(Clone)]
+pub struct GrpcClientLayer {
+    tracer: Arc<dyn TracerPort>,
+    duration: Arc<dyn Histogram>,
+}
+
+impl GrpcClientLayer {
+    pub fn new(tracer: Arc<dyn TracerPort>, metrics: &dyn MetricsPort) -> Self {
+        Self {
+            tracer,
+            duration: duration_histogram(
+                metrics,
+                CLIENT_DURATION_METRIC,
+                "Duration of gRPC client calls",
+            ),
+        }
+    }
+}
+
+impl<S> Layer<S> for GrpcClientLayer {
+    type Service = TracedGrpcService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TracedGrpcService {
+            inner,
+            tracer: self.tracer.clone(),
+            duration: self.duration.clone(),
+            kind: SpanKind::Client,
+        }
+    }
+}
+
+/// A server or channel whose calls are traced and measured, see [`GrpcServerLayer`]
+/// and [`GrpcClientLayer`]
+#[derive(Clone)]
+pub struct TracedGrpcService<S> {
+    inner: S,
+    tracer: Arc<dyn TracerPort>,
+    duration: Arc<dyn Histogram>,
+    kind: SpanKind,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for TracedGrpcService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+{
+    type Response = Response<TracedBody<ResBody>>;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        // Paths are `/{package.service}/{method}`
+        let name = request.uri().path().trim_start_matches('/').to_string();
+        let (service, method) = name.split_once('/').unwrap_or((&name, ""));
+
+        let metric_attributes = vec![
+            ("rpc.system".to_string(), "grpc".into()),
+            ("rpc.service".to_string(), service.into()),
+            ("rpc.method".to_string(), method.into()),
+        ];
+        let mut attributes = metric_attributes.clone();
+        if let Some(host) = request.uri().host() {
+            attributes.push(("server.address".to_string(), host.into()));
+        }
+        if let Some(port) = request.uri().port_u16() {
+            attributes.push(("server.port".to_string(), i64::from(port).into()));
+        }
+
+        let parent = match self.kind {
+            SpanKind::Server => extract_context(request.headers()),
+            _ => Context::current(),
+        };
+        let span = {
+            let _guard = parent.attach();
+            self.tracer.create_span(
+                SpanContext::new(name.clone())
+                    .with_kind(self.kind)
+                    .with_attributes(attributes),
+            )
+        };
+
+        let context = span.get_context();
+        if self.kind == SpanKind::Client {
+            inject_context(&context, request.headers_mut());
+        }
+        let inner = {
+            let _guard = context.clone().attach();
+            self.inner.call(request)
+        };
+
+        ResponseFuture {
+            inner,
+            context,
+            in_flight: Some(InFlight::new(
+                span,
+                self.duration.clone(),
+                metric_attributes,
+            )),
+            kind: self.kind,
+        }
+    }
+}
+
+pin_project! {
+    /// Response of a [`TracedGrpcService`]
+    pub struct ResponseFuture<F> {
+        #[pin]
+        inner: F,
+        context: Context,
+        in_flight: Option<InFlight>,
+        kind: SpanKind,
+    }
+}
+
+impl<F, ResBody, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<ResBody>, E>>,
+{
+    type Output = Result<Response<TracedBody<ResBody>>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _guard = this.context.clone().attach();
+        let result = ready!(this.inner.poll(cx));
+        let mut in_flight = this.in_flight.take();
+
+        let response = match result {
+            Ok(response) => response,
+            Err(error) => {
+                if let Some(in_flight) = in_flight {
+                    in_flight.fail(OTHER_ERROR, "The call failed without a status");
+                }
+                return Poll::Ready(Err(error));
+            }
+        };
+
+        // Calls failing before sending any message have their status in the headers,
+        // the others in the trailers
+        if let Some(status) = Status::from_headers(response.headers()) {
+            if let Some(in_flight) = in_flight.take() {
+                status.complete(in_flight, *this.kind);
+            }
+        }
+
+        let kind = *this.kind;
+        Poll::Ready(Ok(response.map(|inner| TracedBody {
+            inner,
+            in_flight,
+            kind,
+        })))
+    }
+}
+
+pin_project! {
+    /// Body of a response of a [`TracedGrpcService`], completing the call once its
+    /// status is received
+    pub struct TracedBody<B> {
+        #[pin]
+        inner: B,
+        in_flight: Option<InFlight>,
+        kind: SpanKind,
+    }
+}
+
+impl<B: Body> Body for TracedBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        let frame = ready!(this.inner.poll_frame(cx));
+
+        match &frame {
+            Some(Ok(frame)) => {
+                if let Some(status) = frame.trailers_ref().and_then(Status::from_headers) {
+                    if let Some(in_flight) = this.in_flight.take() {
+                        status.complete(in_flight, *this.kind);
+                    }
+                }
+            }
+            Some(Err(_)) => {
+                if let Some(in_flight) = this.in_flight.take() {
+                    in_flight.fail(OTHER_ERROR, "The response could not be received");
+                }
+            }
+            None => {}
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+// The status of a call, from the `grpc-status` and `grpc-message` metadata
+struct Status {
+    code: i64,
+    message: Option<String>,
+}
+
+impl Status {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let code = headers.get("grpc-status")?.to_str().ok()?.parse().ok()?;
+        let message = headers
+            .get("grpc-message")
+            // Messages are percent-encoded in the metadata
+            .map(|value| {
+                percent_decode(value.as_bytes())
+                    .decode_utf8_lossy()
+                    .into_owned()
+            });
+        Some(Self { code, message })
+    }
+
+    // Servers are not at fault for most of the statuses they return, e.g. `NOT_FOUND`
+    fn is_error(&self, kind: SpanKind) -> bool {
+        match kind {
+            SpanKind::Server => matches!(self.code, 2 | 4 | 12 | 13 | 14 | 15),
+            _ => self.code != 0,
+        }
+    }
+
+    fn complete(self, mut in_flight: InFlight, kind: SpanKind) {
+        in_flight.record("rpc.grpc.status_code", self.code.into());
+        if self.is_error(kind) {
+            let name = code_name(self.code);
+            in_flight.fail(name, self.message.as_deref().unwrap_or(name));
+        }
+    }
+}
+
+fn code_name(code: i64) -> &'static str {
+    match code {
+        0 => "OK",
+        1 => "CANCELLED",
+        2 => "UNKNOWN",
+        3 => "INVALID_ARGUMENT",
+        4 => "DEADLINE_EXCEEDED",
+        5 => "NOT_FOUND",
+        6 => "ALREADY_EXISTS",
+        7 => "PERMISSION_DENIED",
+        8 => "RESOURCE_EXHAUSTED",
+        9 => "FAILED_PRECONDITION",
+        10 => "ABORTED",
+        11 => "OUT_OF_RANGE",
+        12 => "UNIMPLEMENTED",
+        13 => "INTERNAL",
+        14 => "UNAVAILABLE",
+        15 => "DATA_LOSS",
+        16 => "UNAUTHENTICATED",
+        _ => OTHER_ERROR,
+    }
+}
@@ -0,0 +1,163 @@
+//! A reqwest client tracing and measuring the requests it sends.
+//!
+//! Each request gets a `Client` span, child of the current context, whose trace
+//! context is injected in the request's headers. Their duration, until the response
+//! headers are received, is recorded in the `http.client.request.duration` histogram.
+
+use std::sync::Arc;
+
+use reqwest::{Client, IntoUrl, Method, Request, RequestBuilder, Response};
+
+use super::propagation::inject_context;
+use super::request::{method, OTHER_METHOD};
+use crate::adapters::operation::{duration_histogram, InFlight, OTHER_ERROR};
+use crate::domain::telemetry::{SpanContext, SpanKind};
+use crate::ports::metrics::{Histogram, MetricsPort};
+use crate::ports::tracer::TracerPort;
+
+/// Name of the histogram of request durations, in seconds
+pub const CLIENT_DURATION_METRIC: &str = "http.client.request.duration";
+
+// Replaces the values of query parameters in `url.full`, as the conventions recommend
+const REDACTED_QUERY_VALUE: &str = "REDACTED";
+
+/// Wraps a reqwest client, tracing and measuring the requests sent through it
+#[derive(Clone)]
+pub struct TracedClient {
+    client: Client,
+    tracer: Arc<dyn TracerPort>,
+    duration: Arc<dyn Histogram>,
+}
+
+impl TracedClient {
+    pub fn new(client: Client, tracer: Arc<dyn TracerPort>, metrics: &dyn MetricsPort) -> Self {
+        Self {
+            client,
+            tracer,
+            duration: duration_histogram(
+                metrics,
+                CLIENT_DURATION_METRIC,
+                "Duration of HTTP client requests",
+            ),
+        }
+    }
+
+    /// The wrapped client, whose requests are not traced when sent directly
+    pub fn inner(&self) -> &Client {
+        &self.client
+    }
+
+    /// Start building a request, to be sent with [`TracedClient::send`]
+    pub fn request<U: IntoUrl>(&self, method: Method, url: U) -> RequestBuilder {
+        self.client.request(method, url)
+    }
+
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    pub fn post<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::POST, url)
+    }
+
+    pub fn put<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::PUT, url)
+    }
+
+    pub fn delete<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::DELETE, url)
+    }
+
+    /// Build and send a request, with the client it was built from
+    pub async fn send(&self, request: RequestBuilder) -> reqwest::Result<Response> {
+        let (client, request) = request.build_split();
+        self.execute_with(&client, request?).await
+    }
+
+    /// Send a request
+    pub async fn execute(&self, request: Request) -> reqwest::Result<Response> {
+        self.execute_with(&self.client, request).await
+    }
+
+    async fn execute_with(
+        &self,
+        client: &Client,
+        mut request: Request,
+    ) -> reqwest::Result<Response> {
+        let method = method(request.method());
+        let url = request.url();
+
+        let mut metric_attributes = vec![
+            ("http.request.method".to_string(), method.into()),
+            ("url.scheme".to_string(), url.scheme().into()),
+        ];
+        if let Some(host) = url.host_str() {
+            metric_attributes.push(("server.address".to_string(), host.into()));
+        }
+        if let Some(port) = url.port_or_known_default() {
+            metric_attributes.push(("server.port".to_string(), i64::from(port).into()));
+        }
+
+        // Credentials never leave the process, nor do query values such as api keys,
+        // tokens or signatures: only the names of the parameters are kept
+        let mut full_url = url.clone();
+        let _ = full_url.set_username("");
+        let _ = full_url.set_password(None);
+        if full_url.query().is_some() {
+            let names: Vec<String> = full_url
+                .query_pairs()
+                .map(|(name, _)| name.into_owned())
+                .collect();
+            full_url
+                .query_pairs_mut()
+                .clear()
+                .extend_pairs(names.iter().map(|name| (name, REDACTED_QUERY_VALUE)));
+        }
+
+        let mut attributes = metric_attributes.clone();
+        attributes.push(("url.full".to_string(), full_url.as_str().into()));
+        if method == OTHER_METHOD {
+            attributes.push((
+                "http.request.method_original".to_string(),
+                request.method().as_str().into(),
+            ));
+        }
+
+        let span = self.tracer.create_span(
+            SpanContext::new(method.to_string())
+                .with_kind(SpanKind::Client)
+                .with_attributes(attributes),
+        );
+        inject_context(&span.get_context(), request.headers_mut());
+
+        let in_flight = InFlight::new(span, self.duration.clone(), metric_attributes);
+        match client.execute(request).await {
+            Ok(response) => {
+                // Unlike servers, clients fail on client errors too
+                let status = response.status();
+                in_flight.respond(status, status.is_client_error() || status.is_server_error());
+                Ok(response)
+            }
+            Err(error) => {
+                in_flight.span().record_exception(&error);
+                in_flight.fail(error_type(&error), &error.to_string());
+                Err(error)
+            }
+        }
+    }
+}
+
+// Why a request failed without a response
+fn error_type(error: &reqwest::Error) -> &'static str {
+    if error.is_timeout() {
+        "timeout"
+    } else if error.is_connect() {
+        "connect"
+    } else if error.is_redirect() {
+        "redirect"
+    } else if error.is_body() || error.is_decode() {
+        "body"
+    } else {
+        OTHER_ERROR
+    }
+}
@@ -0,0 +1,15 @@
+//! Instrumentation of HTTP servers and clients.
+//!
+//! Requests are traced and measured following OpenTelemetry's HTTP semantic
+//! conventions, and trace context travels in their headers through the global
+//! text map propagator.
+
+pub mod client;
+pub(crate) mod propagation;
+mod request;
+#[cfg(feature = "tower")]
+pub mod server;
+
+pub use client::TracedClient;
+#[cfg(feature = "tower")]
+pub use server::{HttpServerLayer, HttpServerService};
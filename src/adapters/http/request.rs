@@ -0,0 +1,38 @@
+use http::{Method, StatusCode};
+
+use crate::adapters::operation::InFlight;
+
+// Methods outside of the well-known ones, which would make cardinality unbounded
+pub(super) const OTHER_METHOD: &str = "_OTHER";
+
+pub(super) fn method(method: &Method) -> &'static str {
+    match *method {
+        Method::GET => "GET",
+        Method::HEAD => "HEAD",
+        Method::POST => "POST",
+        Method::PUT => "PUT",
+        Method::DELETE => "DELETE",
+        Method::CONNECT => "CONNECT",
+        Method::OPTIONS => "OPTIONS",
+        Method::TRACE => "TRACE",
+        Method::PATCH => "PATCH",
+        _ => OTHER_METHOD,
+    }
+}
+
+impl InFlight {
+    // Record the response status, failing the request if it is an error status
+    pub(super) fn respond(mut self, status: StatusCode, is_error: bool) {
+        self.record(
+            "http.response.status_code",
+            i64::from(status.as_u16()).into(),
+        );
+
+        if is_error {
+            self.fail(
+                status.as_str(),
+                status.canonical_reason().unwrap_or_default(),
+            );
+        }
+    }
+}
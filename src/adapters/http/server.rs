@@ -0,0 +1,212 @@
+//! A tower layer tracing and measuring the requests handled by an HTTP server.
+//!
+//! Each request gets a `Server` span, child of the trace context propagated by the
+//! caller, named after its method and route, e.g. `GET /users/{id}`. Routes are
+//! known from axum's `MatchedPath` with the `axum` feature. Their duration is recorded
+//! in the `http.server.request.duration` histogram.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{self, ready, Poll};
+
+use http::{Request, Response};
+use opentelemetry::Context;
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use super::propagation::extract_context;
+use super::request::{method, OTHER_METHOD};
+use crate::adapters::operation::{duration_histogram, InFlight, OTHER_ERROR};
+use crate::domain::telemetry::{SpanContext, SpanKind};
+use crate::ports::metrics::{Histogram, MetricsPort};
+use crate::ports::tracer::TracerPort;
+
+/// Name of the histogram of request durations, in seconds
+pub const SERVER_DURATION_METRIC: &str = "http.server.request.duration";
+
+/// Traces and measures every request of the services it wraps
+#[derive(Clone)]
+pub struct HttpServerLayer {
+    tracer: Arc<dyn TracerPort>,
+    duration: Arc<dyn Histogram>,
+}
+
+impl HttpServerLayer {
+    pub fn new(tracer: Arc<dyn TracerPort>, metrics: &dyn MetricsPort) -> Self {
+        Self {
+            tracer,
+            duration: duration_histogram(
+                metrics,
+                SERVER_DURATION_METRIC,
+                "Duration of HTTP server requests",
+            ),
+        }
+    }
+}
+
+impl<S> Layer<S> for HttpServerLayer {
+    type Service = HttpServerService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpServerService {
+            inner,
+            tracer: self.tracer.clone(),
+            duration: self.duration.clone(),
+        }
+    }
+}
+
+/// A service whose requests are traced and measured, see [`HttpServerLayer`]
+#[derive(Clone)]
+pub struct HttpServerService<S> {
+    inner: S,
+    tracer: Arc<dyn TracerPort>,
+    duration: Arc<dyn Histogram>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for HttpServerService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let method = method(request.method());
+        let route = route(&request);
+        let scheme = request.uri().scheme_str().unwrap_or("http").to_string();
+
+        let mut metric_attributes = vec![
+            ("http.request.method".to_string(), method.into()),
+            ("url.scheme".to_string(), scheme.into()),
+        ];
+        if let Some(route) = &route {
+            metric_attributes.push(("http.route".to_string(), route.as_str().into()));
+        }
+
+        let mut attributes = metric_attributes.clone();
+        attributes.push(("url.path".to_string(), request.uri().path().into()));
+        if method == OTHER_METHOD {
+            attributes.push((
+                "http.request.method_original".to_string(),
+                request.method().as_str().into(),
+            ));
+        }
+        if let Some(address) = client_address(&request) {
+            attributes.push(("client.address".to_string(), address.into()));
+        }
+        if let Some(agent) = header(&request, http::header::USER_AGENT) {
+            attributes.push(("user_agent.original".to_string(), agent.into()));
+        }
+
+        let name = match &route {
+            Some(route) => format!("{} {}", method, route),
+            None => method.to_string(),
+        };
+        let span = {
+            let _guard = extract_context(request.headers()).attach();
+            self.tracer.create_span(
+                SpanContext::new(name)
+                    .with_kind(SpanKind::Server)
+                    .with_attributes(attributes),
+            )
+        };
+
+        // The handler runs within the request's span
+        let context = span.get_context();
+        let inner = {
+            let _guard = context.clone().attach();
+            self.inner.call(request)
+        };
+
+        ResponseFuture {
+            inner,
+            context,
+            in_flight: Some(InFlight::new(
+                span,
+                self.duration.clone(),
+                metric_attributes,
+            )),
+        }
+    }
+}
+
+pin_project! {
+    /// Response of a [`HttpServerService`]
+    pub struct ResponseFuture<F> {
+        #[pin]
+        inner: F,
+        context: Context,
+        in_flight: Option<InFlight>,
+    }
+}
+
+impl<F, ResBody, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<ResBody>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _guard = this.context.clone().attach();
+        let result = ready!(this.inner.poll(cx));
+
+        if let Some(in_flight) = this.in_flight.take() {
+            match &result {
+                // Client errors are not errors of the server
+                Ok(response) => {
+                    let status = response.status();
+                    in_flight.respond(status, status.is_server_error())
+                }
+                Err(_) => in_flight.fail(OTHER_ERROR, "The request could not be handled"),
+            }
+        }
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(feature = "axum")]
+fn route<B>(request: &Request<B>) -> Option<String> {
+    request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|path| path.as_str().to_string())
+}
+
+#[cfg(not(feature = "axum"))]
+fn route<B>(_request: &Request<B>) -> Option<String> {
+    None
+}
+
+fn header<B>(request: &Request<B>, name: http::header::HeaderName) -> Option<&str> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+// The original client behind proxies, or the peer the request came from
+fn client_address<B>(request: &Request<B>) -> Option<String> {
+    let forwarded = header(request, http::HeaderName::from_static("x-forwarded-for"))
+        .and_then(|value| value.split(',').next())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    #[cfg(feature = "axum")]
+    let forwarded = forwarded.or_else(|| {
+        request
+            .extensions()
+            .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+            .map(|info| info.0.ip().to_string())
+    });
+
+    forwarded
+}
@@ -0,0 +1,136 @@
+//! Exporter wrappers that count what is exported for the adapters' health reports.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::logs::{LogBatch, LogExporter};
+use opentelemetry_sdk::metrics::data::ResourceMetrics;
+use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+use opentelemetry_sdk::metrics::Temporality;
+use opentelemetry_sdk::trace::{SpanData, SpanExporter};
+use opentelemetry_sdk::Resource;
+
+use crate::domain::health::ExportStats;
+
+/// A span exporter that records every export in [`ExportStats`]
+#[derive(Debug)]
+pub struct InstrumentedSpanExporter<E> {
+    inner: E,
+    stats: Arc<ExportStats>,
+}
+
+impl<E> InstrumentedSpanExporter<E> {
+    pub fn new(inner: E, stats: Arc<ExportStats>) -> Self {
+        Self { inner, stats }
+    }
+}
+
+impl<E: SpanExporter> SpanExporter for InstrumentedSpanExporter<E> {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        let count = batch.len() as u64;
+        let result = self.inner.export(batch).await;
+        match &result {
+            Ok(()) => self.stats.record_exported(count),
+            Err(e) => self.stats.record_failed(count, e),
+        }
+        result
+    }
+
+    fn shutdown(&mut self) -> OTelSdkResult {
+        self.inner.shutdown()
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource)
+    }
+}
+
+/// A log exporter that records every export in [`ExportStats`]
+#[derive(Debug)]
+pub struct InstrumentedLogExporter<E> {
+    inner: E,
+    stats: Arc<ExportStats>,
+}
+
+impl<E> InstrumentedLogExporter<E> {
+    pub fn new(inner: E, stats: Arc<ExportStats>) -> Self {
+        Self { inner, stats }
+    }
+}
+
+impl<E: LogExporter> LogExporter for InstrumentedLogExporter<E> {
+    async fn export(&self, batch: LogBatch<'_>) -> OTelSdkResult {
+        let count = batch.iter().count() as u64;
+        let result = self.inner.export(batch).await;
+        match &result {
+            Ok(()) => self.stats.record_exported(count),
+            Err(e) => self.stats.record_failed(count, e),
+        }
+        result
+    }
+
+    fn shutdown(&self) -> OTelSdkResult {
+        self.inner.shutdown()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource)
+    }
+}
+
+// Metrics are counted per exported stream (instrument and scope), not per data point
+pub(crate) fn metric_streams(metrics: &ResourceMetrics) -> u64 {
+    metrics
+        .scope_metrics
+        .iter()
+        .map(|scope| scope.metrics.len() as u64)
+        .sum()
+}
+
+/// A metric exporter that records every export in [`ExportStats`].
+///
+/// Metrics are counted per exported stream (instrument and scope), not per data point.
+#[derive(Debug)]
+pub struct InstrumentedMetricExporter<E> {
+    inner: E,
+    stats: Arc<ExportStats>,
+}
+
+impl<E> InstrumentedMetricExporter<E> {
+    pub fn new(inner: E, stats: Arc<ExportStats>) -> Self {
+        Self { inner, stats }
+    }
+}
+
+impl<E: PushMetricExporter> PushMetricExporter for InstrumentedMetricExporter<E> {
+    async fn export(&self, metrics: &mut ResourceMetrics) -> OTelSdkResult {
+        let count = metric_streams(metrics);
+        let result = self.inner.export(metrics).await;
+        match &result {
+            Ok(()) => self.stats.record_exported(count),
+            Err(e) => self.stats.record_failed(count, e),
+        }
+        result
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> OTelSdkResult {
+        self.inner.shutdown()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn temporality(&self) -> Temporality {
+        self.inner.temporality()
+    }
+}
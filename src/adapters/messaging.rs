@@ -0,0 +1,222 @@
+//! Trace context propagation through message headers, and spans of messaging
+//! operations.
+//!
+//! Producers inject the context of their `send` span in the headers of the messages
+//! they send, and consumers continue that trace in their `process` span, or link the
+//! messages of a batch to the single span processing them. Spans follow OpenTelemetry's
+//! messaging semantic conventions, e.g. `send orders` with `messaging.system`,
+//! `messaging.destination.name` and `messaging.operation.type` attributes.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use opentelemetry::global;
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry::Context;
+
+use crate::domain::telemetry::{AttributeValue, SpanContext, SpanKind};
+use crate::ports::tracer::{Span, TracerPort};
+
+/// Byte-valued headers of a message, e.g. Kafka record headers or NATS headers
+pub trait MessageCarrier {
+    /// The value of a header
+    fn get(&self, key: &str) -> Option<&[u8]>;
+
+    /// Set a header, replacing its previous value
+    fn set(&mut self, key: &str, value: Vec<u8>);
+
+    /// The names of the headers
+    fn keys(&self) -> Vec<&str>;
+}
+
+impl MessageCarrier for HashMap<String, Vec<u8>> {
+    fn get(&self, key: &str) -> Option<&[u8]> {
+        HashMap::get(self, key).map(Vec::as_slice)
+    }
+
+    fn set(&mut self, key: &str, value: Vec<u8>) {
+        self.insert(key.to_string(), value);
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        HashMap::keys(self).map(String::as_str).collect()
+    }
+}
+
+// Ordered headers, which may repeat a name: the first one wins
+impl MessageCarrier for Vec<(String, Vec<u8>)> {
+    fn get(&self, key: &str) -> Option<&[u8]> {
+        self.iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    fn set(&mut self, key: &str, value: Vec<u8>) {
+        self.retain(|(name, _)| name != key);
+        self.push((key.to_string(), value));
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.iter().map(|(name, _)| name.as_str()).collect()
+    }
+}
+
+// Reads propagated fields from headers, skipping those that are not UTF-8
+struct CarrierExtractor<'a, C: ?Sized>(&'a C);
+
+impl<C: MessageCarrier + ?Sized> Extractor for CarrierExtractor<'_, C> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .get(key)
+            .and_then(|value| std::str::from_utf8(value).ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys()
+    }
+}
+
+// Writes propagated fields to headers
+struct CarrierInjector<'a, C: ?Sized>(&'a mut C);
+
+impl<C: MessageCarrier + ?Sized> Injector for CarrierInjector<'_, C> {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.set(key, value.into_bytes());
+    }
+}
+
+/// Propagate the context to the consumers of a message in its headers
+pub fn inject_context<C: MessageCarrier + ?Sized>(context: &Context, headers: &mut C) {
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(context, &mut CarrierInjector(headers))
+    });
+}
+
+/// The context propagated by the producer of a message, or the current one if none
+pub fn extract_context<C: MessageCarrier + ?Sized>(headers: &C) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&CarrierExtractor(headers)))
+}
+
+/// What a messaging span is about: the system and destination of a message, or batch
+/// of messages
+#[derive(Debug, Clone)]
+pub struct MessagingContext {
+    system: String,
+    destination: String,
+    message_id: Option<String>,
+    attributes: Vec<(String, AttributeValue)>,
+}
+
+impl MessagingContext {
+    /// A message of a system, e.g. `kafka` or `nats`, sent to or received from a
+    /// destination, e.g. a topic or subject
+    pub fn new(system: impl Into<String>, destination: impl Into<String>) -> Self {
+        Self {
+            system: system.into(),
+            destination: destination.into(),
+            message_id: None,
+            attributes: Vec::new(),
+        }
+    }
+
+    pub fn with_message_id(mut self, message_id: impl Into<String>) -> Self {
+        self.message_id = Some(message_id.into());
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Vec<(String, AttributeValue)>) -> Self {
+        self.attributes.extend(attributes);
+        self
+    }
+
+    fn span(self, operation: &str, kind: SpanKind) -> SpanContext {
+        let mut attributes = vec![
+            ("messaging.system".to_string(), self.system.into()),
+            (
+                "messaging.destination.name".to_string(),
+                self.destination.clone().into(),
+            ),
+            ("messaging.operation.type".to_string(), operation.into()),
+            ("messaging.operation.name".to_string(), operation.into()),
+        ];
+        if let Some(message_id) = self.message_id {
+            attributes.push(("messaging.message.id".to_string(), message_id.into()));
+        }
+        attributes.extend(self.attributes);
+
+        SpanContext::new(format!("{} {}", operation, self.destination))
+            .with_kind(kind)
+            .with_attributes(attributes)
+    }
+}
+
+/// Creates the spans of message producers and consumers, carrying their trace
+/// context through the headers of the messages
+#[derive(Clone)]
+pub struct MessagingTracer {
+    tracer: Arc<dyn TracerPort>,
+}
+
+impl MessagingTracer {
+    pub fn new(tracer: Arc<dyn TracerPort>) -> Self {
+        Self { tracer }
+    }
+
+    /// A `Producer` span sending a message, child of the current context, whose
+    /// context is injected in the message's headers
+    pub fn send<C: MessageCarrier + ?Sized>(
+        &self,
+        context: MessagingContext,
+        headers: &mut C,
+    ) -> Box<dyn Span> {
+        let span = self
+            .tracer
+            .create_span(context.span("send", SpanKind::Producer));
+        inject_context(&span.get_context(), headers);
+        span
+    }
+
+    /// A `Consumer` span processing a message, continuing the trace of its producer
+    pub fn process<C: MessageCarrier + ?Sized>(
+        &self,
+        context: MessagingContext,
+        headers: &C,
+    ) -> Box<dyn Span> {
+        let _guard = extract_context(headers).attach();
+        self.tracer
+            .create_span(context.span("process", SpanKind::Consumer))
+    }
+
+    /// A `Consumer` span processing a batch of messages, child of the current context
+    /// and linked to the trace of each message's producer
+    pub fn process_batch<'a, C: MessageCarrier + ?Sized + 'a>(
+        &self,
+        context: MessagingContext,
+        batch: impl IntoIterator<Item = &'a C>,
+    ) -> Box<dyn Span> {
+        let mut count = 0;
+        let mut links = Vec::new();
+        for headers in batch {
+            count += 1;
+            // Messages without a context must not be linked to the current one
+            let producer = global::get_text_map_propagator(|propagator| {
+                propagator.extract_with_context(&Context::new(), &CarrierExtractor(headers))
+            });
+            let producer = producer.span().span_context().clone();
+            if producer.is_valid() {
+                links.push(producer);
+            }
+        }
+
+        self.tracer.create_span(
+            context
+                .span("process", SpanKind::Consumer)
+                .with_attributes(vec![(
+                    "messaging.batch.message_count".to_string(),
+                    AttributeValue::Int(count),
+                )])
+                .with_links(links),
+        )
+    }
+}
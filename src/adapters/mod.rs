@@ -1 +1,11 @@
-pub mod datadog;
\ No newline at end of file
+pub mod datadog;
+pub mod db;
+pub mod disk_buffer;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod http;
+pub mod instrumented;
+pub mod messaging;
+pub(crate) mod operation;
+pub mod process;
+pub mod rate_limited;
@@ -0,0 +1,78 @@
+//! Helpers shared by the instrumentation of HTTP requests, gRPC calls and database
+//! operations, each traced by a span and measured by a duration histogram.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::domain::metrics::{MetricUnit, TimeUnit};
+use crate::domain::telemetry::{AttributeValue, MetricContext};
+use crate::ports::metrics::{Histogram, MetricsPort};
+use crate::ports::tracer::Span;
+
+// Error type of operations that failed without a more specific one
+pub(crate) const OTHER_ERROR: &str = "_OTHER";
+
+// A histogram of operation durations, in seconds
+pub(crate) fn duration_histogram(
+    metrics: &dyn MetricsPort,
+    name: &str,
+    description: &str,
+) -> Arc<dyn Histogram> {
+    Arc::from(
+        metrics.create_histogram(
+            MetricContext::new(name.to_string())
+                .with_description(description)
+                .with_unit(MetricUnit::Time(TimeUnit::Second)),
+        ),
+    )
+}
+
+// An operation in flight, whose span is ended and duration recorded once dropped, even
+// if it was cancelled before completing
+pub(crate) struct InFlight {
+    span: Box<dyn Span>,
+    duration: Arc<dyn Histogram>,
+    started: Instant,
+    // Attributes of the duration, a subset of the span's
+    attributes: Vec<(String, AttributeValue)>,
+}
+
+impl InFlight {
+    pub(crate) fn new(
+        span: Box<dyn Span>,
+        duration: Arc<dyn Histogram>,
+        attributes: Vec<(String, AttributeValue)>,
+    ) -> Self {
+        Self {
+            span,
+            duration,
+            started: Instant::now(),
+            attributes,
+        }
+    }
+
+    pub(crate) fn span(&self) -> &dyn Span {
+        self.span.as_ref()
+    }
+
+    // Set an attribute of both the span and the duration
+    pub(crate) fn record(&mut self, key: &str, value: AttributeValue) {
+        self.span.set_attribute(key.to_string(), value.clone());
+        self.attributes.push((key.to_string(), value));
+    }
+
+    pub(crate) fn fail(mut self, error_type: &str, description: &str) {
+        self.record("error.type", error_type.into());
+        self.span.set_error_status(description);
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.duration.record(
+            self.started.elapsed().as_secs_f64(),
+            std::mem::take(&mut self.attributes),
+        );
+        self.span.end();
+    }
+}
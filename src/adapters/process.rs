@@ -0,0 +1,358 @@
+//! Process and host metrics read from the Linux proc filesystem, following the
+//! OpenTelemetry `process.*` and `system.*` semantic conventions.
+//!
+//! Files that cannot be read, e.g. on other platforms, are skipped along with their
+//! metrics. Counters are recorded as the increments of the kernel's totals since the
+//! previous sample.
+//!
+//! The conventions measure `process.cpu.time` and `system.cpu.time` in seconds, but
+//! counters only take whole numbers: they are recorded in milliseconds instead, which
+//! keeps every clock tick, with their unit set accordingly.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::domain::metrics::{BytesUnit, CpuUnit, MetricUnit, SystemUnit, TimeUnit};
+use crate::domain::telemetry::{AttributeValue, MetricContext};
+use crate::ports::metrics::{Counter, Gauge, MetricsPort};
+
+// Clock ticks per second of the times in `stat` files, fixed at 100 for user space
+const USER_HZ: u64 = 100;
+
+// What `/proc/self/stat` tells about the process
+#[derive(Debug, Clone, Copy)]
+struct ProcessStat {
+    minor_faults: u64,
+    major_faults: u64,
+    user_ticks: u64,
+    system_ticks: u64,
+}
+
+impl ProcessStat {
+    fn parse(stat: &str) -> Option<Self> {
+        // The command name is in parentheses and may hold spaces, the fields after it
+        // start with the third one, the state
+        let (_, fields) = stat.rsplit_once(')')?;
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        let field = |number: usize| fields.get(number - 3)?.parse().ok();
+
+        Some(Self {
+            minor_faults: field(10)?,
+            major_faults: field(12)?,
+            user_ticks: field(14)?,
+            system_ticks: field(15)?,
+        })
+    }
+
+    fn cpu_seconds(&self) -> f64 {
+        (self.user_ticks + self.system_ticks) as f64 / USER_HZ as f64
+    }
+}
+
+// The value of a `key: value` line, e.g. `VmRSS:   1692 kB` in `status` or
+// `read_bytes: 0` in `io`
+fn field(text: &str, key: &str) -> Option<u64> {
+    text.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name != key {
+            return None;
+        }
+        value.split_whitespace().next()?.parse().ok()
+    })
+}
+
+fn ticks_to_millis(ticks: u64) -> u64 {
+    ticks * 1000 / USER_HZ
+}
+
+// A counter of a kernel total, e.g. the page faults of the process, recording how much
+// each series grew since the previous sample
+struct TotalCounter {
+    counter: Box<dyn Counter>,
+    attribute: &'static str,
+    previous: HashMap<String, u64>,
+}
+
+impl TotalCounter {
+    fn new(counter: Box<dyn Counter>, attribute: &'static str) -> Self {
+        Self {
+            counter,
+            attribute,
+            previous: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, value: &'static str, total: u64) {
+        self.record_series(
+            value.to_string(),
+            vec![(self.attribute.to_string(), AttributeValue::from(value))],
+            total,
+        );
+    }
+
+    // Record a series identified by a key rather than by the value of the attribute
+    fn record_series(
+        &mut self,
+        key: String,
+        attributes: Vec<(String, AttributeValue)>,
+        total: u64,
+    ) {
+        let previous = self.previous.insert(key, total).unwrap_or(0);
+        if total > previous {
+            self.counter.add(total - previous, attributes);
+        }
+    }
+}
+
+/// Records the resources used by this process and the CPU time of the host, from
+/// `/proc/self/{stat,status,fd,io,net/dev}` and `/proc/stat`.
+pub struct ProcessMetrics {
+    proc_root: PathBuf,
+    cpu_time: TotalCounter,
+    cpu_utilization: Box<dyn Gauge>,
+    memory_usage: Box<dyn Gauge>,
+    memory_virtual: Box<dyn Gauge>,
+    paging_faults: TotalCounter,
+    threads: Box<dyn Gauge>,
+    file_descriptors: Box<dyn Gauge>,
+    disk_io: TotalCounter,
+    network_io: TotalCounter,
+    system_cpu_time: TotalCounter,
+    logical_cpus: Box<dyn Gauge>,
+    // When the previous sample was taken, with the process's CPU time then
+    previous_cpu: Option<(Instant, f64)>,
+}
+
+impl ProcessMetrics {
+    pub fn new(metrics: &dyn MetricsPort) -> Self {
+        let context = |name: &str, description: &str, unit: MetricUnit| {
+            MetricContext::new(name.to_string())
+                .with_description(description)
+                .with_unit(unit)
+        };
+        let bytes = || MetricUnit::Bytes(BytesUnit::Byte);
+        let millis = || MetricUnit::Time(TimeUnit::Millisecond);
+
+        Self {
+            proc_root: PathBuf::from("/proc"),
+            cpu_time: TotalCounter::new(
+                metrics.create_counter(context(
+                    "process.cpu.time",
+                    "CPU time spent by the process",
+                    millis(),
+                )),
+                "cpu.mode",
+            ),
+            cpu_utilization: metrics.create_gauge(context(
+                "process.cpu.utilization",
+                "CPUs kept busy by the process since the previous sample",
+                MetricUnit::Cpu(CpuUnit::Core),
+            )),
+            memory_usage: metrics.create_gauge(context(
+                "process.memory.usage",
+                "Physical memory in use by the process",
+                bytes(),
+            )),
+            memory_virtual: metrics.create_gauge(context(
+                "process.memory.virtual",
+                "Virtual memory committed by the process",
+                bytes(),
+            )),
+            paging_faults: TotalCounter::new(
+                metrics.create_counter(context(
+                    "process.paging.faults",
+                    "Page faults of the process",
+                    MetricUnit::System(SystemUnit::Fault),
+                )),
+                "process.paging.fault_type",
+            ),
+            threads: metrics.create_gauge(context(
+                "process.thread.count",
+                "Threads of the process",
+                MetricUnit::System(SystemUnit::Thread),
+            )),
+            file_descriptors: metrics.create_gauge(context(
+                "process.unix.file_descriptor.count",
+                "File descriptors open in the process",
+                MetricUnit::Count,
+            )),
+            disk_io: TotalCounter::new(
+                metrics.create_counter(context(
+                    "process.disk.io",
+                    "Bytes the process read from and wrote to storage",
+                    bytes(),
+                )),
+                "disk.io.direction",
+            ),
+            network_io: TotalCounter::new(
+                metrics.create_counter(context(
+                    "system.network.io",
+                    "Bytes received and transmitted by the network interfaces",
+                    bytes(),
+                )),
+                "network.io.direction",
+            ),
+            system_cpu_time: TotalCounter::new(
+                metrics.create_counter(context(
+                    "system.cpu.time",
+                    "CPU time spent by the host in each mode",
+                    millis(),
+                )),
+                "cpu.mode",
+            ),
+            logical_cpus: metrics.create_gauge(context(
+                "system.cpu.logical.count",
+                "Logical CPUs of the host",
+                MetricUnit::System(SystemUnit::Cpu),
+            )),
+            previous_cpu: None,
+        }
+    }
+
+    /// Read the proc files from another location than `/proc`
+    pub fn with_proc_root(mut self, proc_root: impl Into<PathBuf>) -> Self {
+        self.proc_root = proc_root.into();
+        self
+    }
+
+    /// Read the proc files and record what they hold
+    pub fn record(&mut self) {
+        if let Some(stat) = self
+            .read("self/stat")
+            .and_then(|stat| ProcessStat::parse(&stat))
+        {
+            self.record_stat(stat);
+        }
+        if let Some(status) = self.read("self/status") {
+            for (gauge, key) in [
+                (&self.memory_usage, "VmRSS"),
+                (&self.memory_virtual, "VmSize"),
+            ] {
+                if let Some(kilobytes) = field(&status, key) {
+                    gauge.set((kilobytes * 1024) as f64, Vec::new());
+                }
+            }
+            if let Some(threads) = field(&status, "Threads") {
+                self.threads.set(threads as f64, Vec::new());
+            }
+        }
+        let fd_dir = self.proc_root.join("self/fd");
+        if let Ok(entries) = fs::read_dir(&fd_dir) {
+            // The directory being read is listed too, through the descriptor reading it
+            let listing = fs::canonicalize(&fd_dir).ok();
+            let count = entries
+                .filter_map(Result::ok)
+                .filter(|entry| {
+                    !fs::read_link(entry.path()).is_ok_and(|target| Some(target) == listing)
+                })
+                .count();
+            self.file_descriptors.set(count as f64, Vec::new());
+        }
+        if let Some(io) = self.read("self/io") {
+            for (direction, key) in [("read", "read_bytes"), ("write", "write_bytes")] {
+                if let Some(bytes) = field(&io, key) {
+                    self.disk_io.record(direction, bytes);
+                }
+            }
+        }
+        if let Some(dev) = self.read("self/net/dev") {
+            self.record_network_io(&dev);
+        }
+        if let Some(stat) = self.read("stat") {
+            self.record_system_stat(&stat);
+        }
+    }
+
+    fn read(&self, path: &str) -> Option<String> {
+        fs::read_to_string(self.proc_root.join(path)).ok()
+    }
+
+    fn record_stat(&mut self, stat: ProcessStat) {
+        self.cpu_time
+            .record("user", ticks_to_millis(stat.user_ticks));
+        self.cpu_time
+            .record("system", ticks_to_millis(stat.system_ticks));
+        self.paging_faults.record("minor", stat.minor_faults);
+        self.paging_faults.record("major", stat.major_faults);
+
+        let (now, cpu_seconds) = (Instant::now(), stat.cpu_seconds());
+        if let Some((at, previous)) = self.previous_cpu {
+            let elapsed = now.duration_since(at).as_secs_f64();
+            if elapsed > 0.0 {
+                self.cpu_utilization
+                    .set((cpu_seconds - previous).max(0.0) / elapsed, Vec::new());
+            }
+        }
+        self.previous_cpu = Some((now, cpu_seconds));
+    }
+
+    // Interfaces follow two header lines, with 8 receive fields and 8 transmit fields,
+    // e.g. `  eth0: 98765432 54321 0 0 0 0 0 0 1234567 4321 0 0 0 0 0 0`. They are
+    // those of the process's network namespace, i.e. of its container if it has one.
+    fn record_network_io(&mut self, dev: &str) {
+        for line in dev.lines().skip(2) {
+            let Some((interface, fields)) = line.split_once(':') else {
+                continue;
+            };
+            let interface = interface.trim();
+            let bytes: Vec<u64> = fields
+                .split_whitespace()
+                .filter_map(|field| field.parse().ok())
+                .collect();
+            if bytes.len() < 16 {
+                continue;
+            }
+            for (direction, total) in [("receive", bytes[0]), ("transmit", bytes[8])] {
+                self.network_io.record_series(
+                    format!("{}/{}", interface, direction),
+                    vec![
+                        ("network.interface.name".to_string(), interface.into()),
+                        ("network.io.direction".to_string(), direction.into()),
+                    ],
+                    total,
+                );
+            }
+        }
+    }
+
+    // The first line of `/proc/stat` adds up the time of all CPUs, e.g.
+    // `cpu  170633 0 24955 662213 2116 0 20 8399 0 0`, followed by one line per CPU
+    fn record_system_stat(&mut self, stat: &str) {
+        let mut lines = stat.lines();
+        let ticks: Vec<u64> = lines
+            .next()
+            .and_then(|line| line.strip_prefix("cpu "))
+            .map(|line| {
+                line.split_whitespace()
+                    .filter_map(|tick| tick.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let tick = |index: usize| ticks.get(index).copied().unwrap_or(0);
+        if ticks.len() >= 8 {
+            for (mode, total) in [
+                ("user", tick(0)),
+                ("nice", tick(1)),
+                ("system", tick(2)),
+                ("idle", tick(3)),
+                ("iowait", tick(4)),
+                ("interrupt", tick(5) + tick(6)),
+                ("steal", tick(7)),
+            ] {
+                self.system_cpu_time.record(mode, ticks_to_millis(total));
+            }
+        }
+
+        let cpus = lines
+            .filter(|line| {
+                line.strip_prefix("cpu")
+                    .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+            })
+            .count();
+        if cpus > 0 {
+            self.logical_cpus.set(cpus as f64, Vec::new());
+        }
+    }
+}
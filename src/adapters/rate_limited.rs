@@ -0,0 +1,298 @@
+//! A logger wrapper keeping hot call sites from flooding the backend.
+//!
+//! Records are rate-limited per call site, identified by their target and message
+//! template, with a token bucket each. Debug and info records can additionally be
+//! sampled, and the number of suppressed records is periodically logged instead.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry::Context;
+use opentelemetry_sdk::Resource;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing_subscriber::EnvFilter;
+
+use crate::domain::error_report::ErrorReport;
+use crate::domain::health::SignalHealth;
+use crate::domain::telemetry::{AttributeValue, LogContext, LogLevel, TelemetryError};
+use crate::ports::logger::LoggerPort;
+
+// Summaries are logged on a tokio interval, which can't tick every zero seconds
+const MIN_SUMMARY_INTERVAL: Duration = Duration::from_millis(1);
+
+/// How many records a call site may log, and how often suppressions are reported
+#[derive(Debug, Clone)]
+pub struct LogRateLimit {
+    per_second: f64,
+    burst: u32,
+    sample_ratio: f64,
+    summary_interval: Duration,
+}
+
+impl Default for LogRateLimit {
+    /// 10 records per second after a burst of 50, nothing sampled, summaries every minute
+    fn default() -> Self {
+        Self {
+            per_second: 10.0,
+            burst: 50,
+            sample_ratio: 1.0,
+            summary_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+impl LogRateLimit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Let each call site log `per_second` records on average, and up to `burst` at once
+    pub fn with_rate(mut self, per_second: f64, burst: u32) -> Self {
+        self.per_second = per_second.max(0.0);
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Keep only this ratio of trace, debug and info records.
+    ///
+    /// Within a trace the decision is made on the trace id, so a trace keeps all of
+    /// its records or none of them.
+    pub fn with_sample_ratio(mut self, ratio: f64) -> Self {
+        self.sample_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// How often the number of suppressed records is logged, at most every millisecond
+    pub fn with_summary_interval(mut self, interval: Duration) -> Self {
+        self.summary_interval = interval.max(MIN_SUMMARY_INTERVAL);
+        self
+    }
+}
+
+/// Wraps a logger, rate-limiting and sampling what reaches it.
+///
+/// Errors logged within a sampled trace are always kept, so that the traces that
+/// are exported come with their errors.
+pub struct RateLimitedLogger<L> {
+    inner: Arc<L>,
+    limiter: Arc<Limiter>,
+    summary_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl<L: LoggerPort + 'static> RateLimitedLogger<L> {
+    pub fn new(inner: L, config: LogRateLimit) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            limiter: Arc::new(Limiter {
+                config,
+                buckets: Mutex::new(HashMap::new()),
+                sampled: AtomicU64::new(0),
+            }),
+            summary_task: Mutex::new(None),
+        }
+    }
+
+    /// Log how many records were suppressed since the last summary
+    pub fn log_summaries(&self) {
+        self.limiter.log_summaries(self.inner.as_ref());
+    }
+}
+
+#[async_trait]
+impl<L: LoggerPort + 'static> LoggerPort for RateLimitedLogger<L> {
+    async fn init(
+        &self,
+        resource: &Resource,
+        filter: Option<EnvFilter>,
+    ) -> Result<(), TelemetryError> {
+        self.inner.init(resource, filter).await?;
+
+        let (inner, limiter) = (self.inner.clone(), self.limiter.clone());
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(limiter.config.summary_interval);
+            // The first tick completes immediately
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                limiter.log_summaries(inner.as_ref());
+            }
+        });
+        if let Some(previous) = self.summary_task.lock().unwrap().replace(task) {
+            previous.abort();
+        }
+
+        Ok(())
+    }
+
+    fn log(&self, context: LogContext) {
+        let target = context.target.clone().unwrap_or_default();
+        if self.limiter.admit(context.level, target, &context.message) {
+            self.inner.log(context);
+        }
+    }
+
+    fn log_error(
+        &self,
+        error: &(dyn std::error::Error + 'static),
+        target: Option<&str>,
+        attributes: Vec<(String, AttributeValue)>,
+    ) {
+        let call_site = target.unwrap_or_default().to_string();
+        if self
+            .limiter
+            .admit(LogLevel::Error, call_site, &error.to_string())
+        {
+            self.inner.log_error(error, target, attributes);
+        }
+    }
+
+    fn log_report(
+        &self,
+        report: ErrorReport,
+        target: Option<&str>,
+        attributes: Vec<(String, AttributeValue)>,
+    ) {
+        let call_site = target.unwrap_or_default().to_string();
+        if self
+            .limiter
+            .admit(LogLevel::Error, call_site, &report.message)
+        {
+            self.inner.log_report(report, target, attributes);
+        }
+    }
+
+    async fn force_flush(&self) -> Result<(), TelemetryError> {
+        self.log_summaries();
+        self.inner.force_flush().await
+    }
+
+    fn health(&self) -> Option<SignalHealth> {
+        self.inner.health()
+    }
+
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        if let Some(task) = self.summary_task.lock().unwrap().take() {
+            task.abort();
+        }
+        self.log_summaries();
+        self.inner.shutdown().await
+    }
+}
+
+// A call site: its target and message template
+type CallSite = (String, String);
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+    suppressed: u64,
+}
+
+struct Limiter {
+    config: LogRateLimit,
+    buckets: Mutex<HashMap<CallSite, Bucket>>,
+    // Records seen by the sampler outside of any trace
+    sampled: AtomicU64,
+}
+
+impl Limiter {
+    fn admit(&self, level: LogLevel, target: String, message: &str) -> bool {
+        let span_context = Context::current().span().span_context().clone();
+        let is_error = matches!(level, LogLevel::Error | LogLevel::Critical);
+        if is_error && span_context.is_sampled() {
+            return true;
+        }
+
+        let is_verbose = matches!(level, LogLevel::Trace | LogLevel::Debug | LogLevel::Info);
+        if is_verbose && self.config.sample_ratio < 1.0 {
+            let keep = if span_context.is_valid() {
+                let bytes = span_context.trace_id().to_bytes();
+                let id = u64::from_be_bytes(bytes[8..].try_into().unwrap_or_default()) >> 1;
+                (id as f64) < self.config.sample_ratio * (1u64 << 63) as f64
+            } else {
+                // Keep exactly the ratio, evenly spread
+                let seen = self.sampled.fetch_add(1, Ordering::Relaxed) as f64;
+                ((seen + 1.0) * self.config.sample_ratio).floor()
+                    > (seen * self.config.sample_ratio).floor()
+            };
+            if !keep {
+                return false;
+            }
+        }
+
+        let now = Instant::now();
+        let burst = self.config.burst as f64;
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets
+            .entry((target, template(message)))
+            .or_insert_with(|| Bucket {
+                tokens: burst,
+                refilled_at: now,
+                suppressed: 0,
+            });
+
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.config.per_second).min(burst);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            bucket.suppressed += 1;
+            false
+        }
+    }
+
+    fn log_summaries(&self, logger: &dyn LoggerPort) {
+        let now = Instant::now();
+        let mut summaries = Vec::new();
+        {
+            let mut buckets = self.buckets.lock().unwrap();
+            for ((target, template), bucket) in buckets.iter_mut() {
+                if bucket.suppressed > 0 {
+                    summaries.push((target.clone(), template.clone(), bucket.suppressed));
+                    bucket.suppressed = 0;
+                }
+            }
+            // Forget call sites that went quiet, they start again with a full bucket
+            let idle = self.config.summary_interval;
+            buckets.retain(|_, bucket| now.duration_since(bucket.refilled_at) < idle);
+        }
+
+        for (target, template, suppressed) in summaries {
+            let mut context = LogContext::new(
+                format!("Suppressed {} log records: {}", suppressed, template),
+                LogLevel::Warn,
+            )
+            .with_attribute("log.suppressed_count", suppressed.into());
+            if !target.is_empty() {
+                context = context.with_target(&target);
+            }
+            logger.log(context);
+        }
+    }
+}
+
+// Messages differing only by their numbers come from the same call site
+fn template(message: &str) -> String {
+    let mut template = String::with_capacity(message.len());
+    let mut in_number = false;
+    for c in message.chars() {
+        if c.is_ascii_digit() {
+            if !in_number {
+                template.push('#');
+            }
+            in_number = true;
+        } else {
+            template.push(c);
+            in_number = false;
+        }
+    }
+    template
+}
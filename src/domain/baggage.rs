@@ -0,0 +1,44 @@
+//! Baggage entries copied onto telemetry as attributes.
+//!
+//! Baggage travels with the context, across spawned tasks and through propagators,
+//! but is not recorded anywhere by itself. Entries such as a tenant id are worth
+//! finding on every span, log record and metric point created while they are set.
+
+use opentelemetry::baggage::BaggageExt;
+use opentelemetry::Context;
+
+use crate::domain::telemetry::AttributeValue;
+
+/// The keys of the baggage entries copied onto telemetry, as attributes of the same name
+#[derive(Debug, Clone, Default)]
+pub struct BaggageAttributes {
+    keys: Vec<String>,
+}
+
+impl BaggageAttributes {
+    pub fn new<I, K>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        Self {
+            keys: keys.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The selected entries of a context's baggage, as attributes
+    pub fn from_context(&self, context: &Context) -> Vec<(String, AttributeValue)> {
+        let baggage = context.baggage();
+        self.keys
+            .iter()
+            .filter_map(|key| {
+                let value = baggage.get(key)?;
+                Some((key.clone(), value.as_str().into()))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,302 @@
+use std::time::Duration;
+
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::resource::ResourceDetector;
+use opentelemetry_sdk::Resource;
+use serde_json::Value;
+use tracing::debug;
+
+use super::env::Env;
+use super::metadata::{self, string, DEFAULT_TIMEOUT};
+
+const EC2_METADATA_ENDPOINT: &str = "http://169.254.169.254";
+
+/// Detects `cloud.*` and `aws.ecs.*` from the ECS task metadata endpoint (v4)
+#[derive(Debug, Clone)]
+pub struct EcsResourceDetector {
+    env: Env,
+    timeout: Duration,
+}
+
+impl Default for EcsResourceDetector {
+    fn default() -> Self {
+        Self {
+            env: Env::default(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl EcsResourceDetector {
+    /// Read `ECS_CONTAINER_METADATA_URI_V4` from the given environment instead of the process one
+    pub fn with_env<K, V>(mut self, env: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env = Env::with_vars(env);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The detected attributes, empty outside of ECS or if the endpoint can't be reached
+    pub fn attributes(&self) -> Vec<KeyValue> {
+        // The agent injects the endpoint into every container of a task
+        let Some(endpoint) = self.env.var("ECS_CONTAINER_METADATA_URI_V4") else {
+            return Vec::new();
+        };
+
+        let fetch = |url: String| {
+            metadata::get(&url, &[], self.timeout)
+                .and_then(|body| serde_json::from_str::<Value>(&body).map_err(Into::into))
+        };
+        let (container, task) = match (fetch(endpoint.clone()), fetch(format!("{}/task", endpoint)))
+        {
+            (Ok(container), Ok(task)) => (container, task),
+            (Err(e), _) | (_, Err(e)) => {
+                debug!("ECS task metadata endpoint unavailable: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut attributes = vec![
+            KeyValue::new("cloud.provider", "aws"),
+            KeyValue::new("cloud.platform", "aws_ecs"),
+        ];
+
+        let task_arn = string(&task, "TaskARN");
+        if let Some(arn) = task_arn.as_deref().and_then(Arn::parse) {
+            attributes.push(KeyValue::new("cloud.region", arn.region.to_string()));
+            attributes.push(KeyValue::new("cloud.account.id", arn.account.to_string()));
+
+            // Tasks on EC2 report the cluster by name only
+            if let Some(cluster) = string(&task, "Cluster") {
+                let cluster_arn = if cluster.starts_with("arn:") {
+                    cluster
+                } else {
+                    format!(
+                        "arn:aws:ecs:{}:{}:cluster/{}",
+                        arn.region, arn.account, cluster
+                    )
+                };
+                attributes.push(KeyValue::new("aws.ecs.cluster.arn", cluster_arn));
+            }
+        }
+
+        let fields = [
+            (&task, "TaskARN", "aws.ecs.task.arn"),
+            (&task, "Family", "aws.ecs.task.family"),
+            (&task, "Revision", "aws.ecs.task.revision"),
+            (&task, "AvailabilityZone", "cloud.availability_zone"),
+            (&container, "ContainerARN", "aws.ecs.container.arn"),
+            (&container, "DockerId", "container.id"),
+            (&container, "Name", "container.name"),
+        ];
+        attributes.extend(
+            fields
+                .iter()
+                .filter_map(|(json, field, key)| Some(KeyValue::new(*key, string(json, field)?))),
+        );
+        if let Some(launch_type) = string(&task, "LaunchType") {
+            attributes.push(KeyValue::new(
+                "aws.ecs.launchtype",
+                launch_type.to_lowercase(),
+            ));
+        }
+
+        attributes
+    }
+}
+
+impl ResourceDetector for EcsResourceDetector {
+    fn detect(&self) -> Resource {
+        Resource::builder_empty()
+            .with_attributes(self.attributes())
+            .build()
+    }
+}
+
+/// Detects `cloud.*` and `faas.*` from the environment of a Lambda function
+#[derive(Debug, Clone, Default)]
+pub struct LambdaResourceDetector {
+    env: Env,
+}
+
+impl LambdaResourceDetector {
+    /// Read variables from the given environment instead of the process one
+    pub fn with_env<K, V>(mut self, env: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env = Env::with_vars(env);
+        self
+    }
+
+    /// The detected attributes, empty outside of Lambda
+    pub fn attributes(&self) -> Vec<KeyValue> {
+        let Some(function_name) = self.env.var("AWS_LAMBDA_FUNCTION_NAME") else {
+            return Vec::new();
+        };
+
+        let mut attributes = vec![
+            KeyValue::new("cloud.provider", "aws"),
+            KeyValue::new("cloud.platform", "aws_lambda"),
+            KeyValue::new("faas.name", function_name),
+        ];
+
+        let vars = [
+            ("AWS_REGION", "cloud.region"),
+            ("AWS_LAMBDA_FUNCTION_VERSION", "faas.version"),
+            ("AWS_LAMBDA_LOG_STREAM_NAME", "faas.instance"),
+        ];
+        attributes.extend(
+            vars.iter()
+                .filter_map(|(var, key)| Some(KeyValue::new(*key, self.env.var(var)?))),
+        );
+
+        // Configured in MiB, reported in bytes
+        if let Some(memory) = self
+            .env
+            .var("AWS_LAMBDA_FUNCTION_MEMORY_SIZE")
+            .and_then(|memory| memory.parse::<i64>().ok())
+        {
+            attributes.push(KeyValue::new("faas.max_memory", memory * 1024 * 1024));
+        }
+
+        attributes
+    }
+}
+
+impl ResourceDetector for LambdaResourceDetector {
+    fn detect(&self) -> Resource {
+        Resource::builder_empty()
+            .with_attributes(self.attributes())
+            .build()
+    }
+}
+
+/// Detects `cloud.*` and `host.*` for EKS pods from the EC2 instance metadata service (IMDSv2)
+#[derive(Debug, Clone)]
+pub struct EksResourceDetector {
+    endpoint: String,
+    env: Env,
+    timeout: Duration,
+}
+
+impl Default for EksResourceDetector {
+    fn default() -> Self {
+        Self {
+            endpoint: EC2_METADATA_ENDPOINT.to_string(),
+            env: Env::default(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl EksResourceDetector {
+    /// Query another instance metadata endpoint
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Read variables from the given environment instead of the process one
+    pub fn with_env<K, V>(mut self, env: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env = Env::with_vars(env);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The detected attributes, empty outside of EKS or if the metadata service can't be reached
+    pub fn attributes(&self) -> Vec<KeyValue> {
+        if self.env.var("KUBERNETES_SERVICE_HOST").is_none() {
+            return Vec::new();
+        }
+
+        let document = match self.identity_document() {
+            Ok(document) => document,
+            Err(e) => {
+                // Pods only reach IMDSv2 when the hop limit of the node allows it
+                debug!("EC2 instance metadata unavailable: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut attributes = vec![
+            KeyValue::new("cloud.provider", "aws"),
+            KeyValue::new("cloud.platform", "aws_eks"),
+        ];
+        let fields = [
+            ("region", "cloud.region"),
+            ("accountId", "cloud.account.id"),
+            ("availabilityZone", "cloud.availability_zone"),
+            ("instanceId", "host.id"),
+            ("instanceType", "host.type"),
+            ("imageId", "host.image.id"),
+        ];
+        attributes.extend(
+            fields
+                .iter()
+                .filter_map(|(field, key)| Some(KeyValue::new(*key, string(&document, field)?))),
+        );
+        attributes
+    }
+
+    fn identity_document(&self) -> std::io::Result<Value> {
+        let token = metadata::put(
+            &format!("{}/latest/api/token", self.endpoint),
+            &[("X-aws-ec2-metadata-token-ttl-seconds", "60")],
+            self.timeout,
+        )?;
+        let document = metadata::get(
+            &format!(
+                "{}/latest/dynamic/instance-identity/document",
+                self.endpoint
+            ),
+            &[("X-aws-ec2-metadata-token", token.trim())],
+            self.timeout,
+        )?;
+        Ok(serde_json::from_str(&document)?)
+    }
+}
+
+impl ResourceDetector for EksResourceDetector {
+    fn detect(&self) -> Resource {
+        Resource::builder_empty()
+            .with_attributes(self.attributes())
+            .build()
+    }
+}
+
+// `arn:<partition>:<service>:<region>:<account>:<resource>`
+struct Arn<'a> {
+    region: &'a str,
+    account: &'a str,
+}
+
+impl<'a> Arn<'a> {
+    fn parse(arn: &'a str) -> Option<Self> {
+        let mut parts = arn.splitn(6, ':');
+        if parts.next()? != "arn" {
+            return None;
+        }
+        let (_partition, _service) = (parts.next()?, parts.next()?);
+        Some(Self {
+            region: parts.next()?,
+            account: parts.next()?,
+        })
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::resource::ResourceDetector;
+use opentelemetry_sdk::Resource;
+use serde_json::Value;
+use tracing::debug;
+
+use super::metadata::{self, string, DEFAULT_TIMEOUT};
+
+const IMDS_ENDPOINT: &str = "http://169.254.169.254";
+const IMDS_API_VERSION: &str = "2021-12-13";
+
+/// Detects `cloud.*`, `host.*` and `azure.*` from the Azure instance metadata service
+#[derive(Debug, Clone)]
+pub struct AzureResourceDetector {
+    endpoint: String,
+    timeout: Duration,
+}
+
+impl Default for AzureResourceDetector {
+    fn default() -> Self {
+        Self {
+            endpoint: IMDS_ENDPOINT.to_string(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl AzureResourceDetector {
+    /// Query another instance metadata endpoint
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The detected attributes, empty outside of Azure or if IMDS can't be reached
+    pub fn attributes(&self) -> Vec<KeyValue> {
+        let url = format!(
+            "{}/metadata/instance/compute?api-version={}&format=json",
+            self.endpoint, IMDS_API_VERSION
+        );
+        let compute = match metadata::get(&url, &[("Metadata", "true")], self.timeout)
+            .and_then(|body| serde_json::from_str::<Value>(&body).map_err(Into::into))
+        {
+            Ok(compute) => compute,
+            Err(e) => {
+                debug!("Azure instance metadata unavailable: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut attributes = vec![
+            KeyValue::new("cloud.provider", "azure"),
+            KeyValue::new("cloud.platform", "azure_vm"),
+        ];
+        let fields = [
+            ("location", "cloud.region"),
+            ("zone", "cloud.availability_zone"),
+            ("subscriptionId", "cloud.account.id"),
+            ("resourceId", "cloud.resource_id"),
+            ("vmId", "host.id"),
+            ("name", "host.name"),
+            ("vmSize", "host.type"),
+            ("resourceGroupName", "azure.resourcegroup.name"),
+            ("vmScaleSetName", "azure.vm.scaleset.name"),
+        ];
+        attributes.extend(
+            fields
+                .iter()
+                .filter_map(|(field, key)| Some(KeyValue::new(*key, string(&compute, field)?))),
+        );
+        attributes
+    }
+}
+
+impl ResourceDetector for AzureResourceDetector {
+    fn detect(&self) -> Resource {
+        Resource::builder_empty()
+            .with_attributes(self.attributes())
+            .build()
+    }
+}
@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::PathBuf;
+
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::resource::ResourceDetector;
+use opentelemetry_sdk::Resource;
+
+// Runtimes prefix the container id in systemd scope names, e.g. `docker-<id>.scope`
+const SCOPE_PREFIXES: [&str; 4] = ["docker-", "cri-containerd-", "crio-", "libpod-"];
+
+// Directories whose children are named after the container, in mount sources
+const MOUNT_PARENTS: [&str; 3] = ["containers", "sandboxes", "overlay-containers"];
+
+/// Detects `container.id` from the cgroup (v1) or mount (v2) information of the process
+#[derive(Debug, Clone)]
+pub struct ContainerResourceDetector {
+    cgroup_path: PathBuf,
+    mountinfo_path: PathBuf,
+}
+
+impl Default for ContainerResourceDetector {
+    fn default() -> Self {
+        Self {
+            cgroup_path: PathBuf::from("/proc/self/cgroup"),
+            mountinfo_path: PathBuf::from("/proc/self/mountinfo"),
+        }
+    }
+}
+
+impl ContainerResourceDetector {
+    /// Read the cgroup and mountinfo files from other locations
+    pub fn with_paths(cgroup_path: impl Into<PathBuf>, mountinfo_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cgroup_path: cgroup_path.into(),
+            mountinfo_path: mountinfo_path.into(),
+        }
+    }
+
+    /// The id of the container this process runs in, if any
+    pub fn container_id(&self) -> Option<String> {
+        // cgroup v2 only has `0::/` in the cgroup file, the id is then found in the mounts
+        fs::read_to_string(&self.cgroup_path)
+            .ok()
+            .and_then(|cgroup| container_id_from_cgroup(&cgroup))
+            .or_else(|| {
+                fs::read_to_string(&self.mountinfo_path)
+                    .ok()
+                    .and_then(|mountinfo| container_id_from_mountinfo(&mountinfo))
+            })
+    }
+}
+
+impl ResourceDetector for ContainerResourceDetector {
+    fn detect(&self) -> Resource {
+        let mut builder = Resource::builder_empty();
+        if let Some(id) = self.container_id() {
+            builder = builder.with_attribute(KeyValue::new("container.id", id));
+        }
+        builder.build()
+    }
+}
+
+// Lines look like `12:pids:/kubepods/burstable/pod<uid>/<id>` or
+// `1:name=systemd:/system.slice/docker-<id>.scope`
+fn container_id_from_cgroup(cgroup: &str) -> Option<String> {
+    cgroup.lines().find_map(|line| {
+        let path = line.splitn(3, ':').nth(2)?;
+        let last = path.rsplit('/').next()?;
+        let last = last.strip_suffix(".scope").unwrap_or(last);
+        let id = SCOPE_PREFIXES
+            .iter()
+            .find_map(|prefix| last.strip_prefix(prefix))
+            .unwrap_or(last);
+
+        is_container_id(id).then(|| id.to_string())
+    })
+}
+
+// The mount source (4th field) of `/etc/hostname` and friends points into the runtime's
+// directory for the container, e.g. `/var/lib/docker/containers/<id>/hostname`
+fn container_id_from_mountinfo(mountinfo: &str) -> Option<String> {
+    mountinfo.lines().find_map(|line| {
+        let root = line.split_whitespace().nth(3)?;
+        let segments: Vec<&str> = root.split('/').collect();
+
+        segments.windows(2).find_map(|pair| {
+            (MOUNT_PARENTS.contains(&pair[0]) && is_container_id(pair[1]))
+                .then(|| pair[1].to_string())
+        })
+    })
+}
+
+// Container ids are 64 hex characters, or `<32 hex>-<digits>` for ECS tasks
+fn is_container_id(id: &str) -> bool {
+    let is_hex = |s: &str| s.bytes().all(|b| b.is_ascii_hexdigit());
+
+    match id.split_once('-') {
+        None => id.len() == 64 && is_hex(id),
+        Some((task, index)) => {
+            task.len() == 32
+                && is_hex(task)
+                && (1..=10).contains(&index.len())
+                && index.bytes().all(|b| b.is_ascii_digit())
+        }
+    }
+}
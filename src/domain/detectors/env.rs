@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+/// The environment a detector reads, the process one unless overridden
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Env(Option<HashMap<String, String>>);
+
+impl Env {
+    pub(crate) fn with_vars<K, V>(vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Self(Some(
+            vars.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        ))
+    }
+
+    /// The value of a variable, treating empty values as unset
+    pub(crate) fn var(&self, name: &str) -> Option<String> {
+        let value = match &self.0 {
+            Some(vars) => vars.get(name).cloned(),
+            None => std::env::var(name).ok(),
+        };
+        value.filter(|value| !value.is_empty())
+    }
+}
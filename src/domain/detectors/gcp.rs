@@ -0,0 +1,162 @@
+use std::io;
+use std::time::Duration;
+
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::resource::ResourceDetector;
+use opentelemetry_sdk::Resource;
+use tracing::debug;
+
+use super::env::Env;
+use super::metadata::{self, DEFAULT_TIMEOUT};
+
+// The address of `metadata.google.internal`, used directly so that detection does not
+// wait on DNS outside of GCP
+const METADATA_HOST: &str = "169.254.169.254";
+
+/// Detects `cloud.*`, `host.*` and `faas.*` from the GCE metadata server.
+///
+/// The platform is told apart from the environment: Cloud Run and Cloud Functions set
+/// `K_SERVICE`, GKE pods get `KUBERNETES_SERVICE_HOST`, anything else is Compute Engine.
+#[derive(Debug, Clone)]
+pub struct GcpResourceDetector {
+    endpoint: Option<String>,
+    env: Env,
+    timeout: Duration,
+}
+
+impl Default for GcpResourceDetector {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            env: Env::default(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl GcpResourceDetector {
+    /// Query another metadata server, `GCE_METADATA_HOST` is used otherwise
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Read variables from the given environment instead of the process one
+    pub fn with_env<K, V>(mut self, env: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env = Env::with_vars(env);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn endpoint(&self) -> String {
+        self.endpoint.clone().unwrap_or_else(|| {
+            let host = self
+                .env
+                .var("GCE_METADATA_HOST")
+                .unwrap_or_else(|| METADATA_HOST.to_string());
+            format!("http://{}", host)
+        })
+    }
+
+    fn fetch(&self, path: &str) -> io::Result<String> {
+        let url = format!("{}/computeMetadata/v1/{}", self.endpoint(), path);
+        metadata::get(&url, &[("Metadata-Flavor", "Google")], self.timeout)
+            .map(|value| value.trim().to_string())
+    }
+
+    // Values that may be missing, such as the region outside of serverless platforms
+    fn optional(&self, path: &str) -> Option<String> {
+        self.fetch(path).ok().filter(|value| !value.is_empty())
+    }
+
+    /// The detected attributes, empty outside of GCP or if the metadata server can't be reached
+    pub fn attributes(&self) -> Vec<KeyValue> {
+        let project_id = match self.fetch("project/project-id") {
+            Ok(project_id) => project_id,
+            Err(e) => {
+                debug!("GCE metadata server unavailable: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut attributes = vec![
+            KeyValue::new("cloud.provider", "gcp"),
+            KeyValue::new("cloud.account.id", project_id),
+        ];
+
+        // Zones and regions are reported as `projects/<number>/zones/<zone>`
+        let zone = self.optional("instance/zone").map(last_segment);
+        let region = self
+            .optional("instance/region")
+            .map(last_segment)
+            .or_else(|| zone.as_deref().and_then(region_of_zone));
+
+        if let Some(service) = self.env.var("K_SERVICE") {
+            let platform = if self.env.var("FUNCTION_TARGET").is_some() {
+                "gcp_cloud_functions"
+            } else {
+                "gcp_cloud_run"
+            };
+            attributes.push(KeyValue::new("cloud.platform", platform));
+            attributes.push(KeyValue::new("faas.name", service));
+            if let Some(revision) = self.env.var("K_REVISION") {
+                attributes.push(KeyValue::new("faas.version", revision));
+            }
+            if let Some(id) = self.optional("instance/id") {
+                attributes.push(KeyValue::new("faas.instance", id));
+            }
+        } else {
+            let platform = if self.env.var("KUBERNETES_SERVICE_HOST").is_some() {
+                if let Some(cluster) = self.optional("instance/attributes/cluster-name") {
+                    attributes.push(KeyValue::new("k8s.cluster.name", cluster));
+                }
+                "gcp_kubernetes_engine"
+            } else {
+                "gcp_compute_engine"
+            };
+            attributes.push(KeyValue::new("cloud.platform", platform));
+
+            let host = [("instance/id", "host.id"), ("instance/name", "host.name")];
+            attributes.extend(
+                host.iter()
+                    .filter_map(|(path, key)| Some(KeyValue::new(*key, self.optional(path)?))),
+            );
+            if let Some(machine_type) = self.optional("instance/machine-type") {
+                attributes.push(KeyValue::new("host.type", last_segment(machine_type)));
+            }
+            if let Some(zone) = &zone {
+                attributes.push(KeyValue::new("cloud.availability_zone", zone.clone()));
+            }
+        }
+
+        if let Some(region) = region {
+            attributes.push(KeyValue::new("cloud.region", region));
+        }
+        attributes
+    }
+}
+
+impl ResourceDetector for GcpResourceDetector {
+    fn detect(&self) -> Resource {
+        Resource::builder_empty()
+            .with_attributes(self.attributes())
+            .build()
+    }
+}
+
+fn last_segment(value: String) -> String {
+    value.rsplit('/').next().unwrap_or_default().to_string()
+}
+
+// `us-central1-a` is in `us-central1`
+fn region_of_zone(zone: &str) -> Option<String> {
+    zone.rsplit_once('-').map(|(region, _)| region.to_string())
+}
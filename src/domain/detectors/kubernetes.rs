@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::resource::ResourceDetector;
+use opentelemetry_sdk::Resource;
+
+use super::env::Env;
+
+/// Downward API environment variables and the attribute each one populates.
+///
+/// They are expected to be set in the pod spec, e.g.
+/// `K8S_POD_NAME` from `fieldRef: { fieldPath: metadata.name }`.
+pub const DOWNWARD_API_VARS: [(&str, &str); 5] = [
+    ("K8S_POD_NAME", "k8s.pod.name"),
+    ("K8S_POD_UID", "k8s.pod.uid"),
+    ("K8S_NAMESPACE_NAME", "k8s.namespace.name"),
+    ("K8S_NODE_NAME", "k8s.node.name"),
+    ("K8S_CONTAINER_NAME", "k8s.container.name"),
+];
+
+/// Detects `k8s.*` attributes from the downward API and the mounted service account
+#[derive(Debug, Clone)]
+pub struct KubernetesResourceDetector {
+    namespace_path: PathBuf,
+    env: Env,
+}
+
+impl Default for KubernetesResourceDetector {
+    fn default() -> Self {
+        Self {
+            namespace_path: PathBuf::from(
+                "/var/run/secrets/kubernetes.io/serviceaccount/namespace",
+            ),
+            env: Env::default(),
+        }
+    }
+}
+
+impl KubernetesResourceDetector {
+    /// Read the service account namespace from another file
+    pub fn with_namespace_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.namespace_path = path.into();
+        self
+    }
+
+    /// Read variables from the given environment instead of the process one
+    pub fn with_env<K, V>(mut self, env: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env = Env::with_vars(env);
+        self
+    }
+
+    /// The detected `k8s.*` attributes, empty outside of Kubernetes
+    pub fn attributes(&self) -> Vec<KeyValue> {
+        let namespace_file = fs::read_to_string(&self.namespace_path)
+            .ok()
+            .map(|namespace| namespace.trim().to_string())
+            .filter(|namespace| !namespace.is_empty());
+
+        // Every pod gets the API server address, use it to tell whether we run in one
+        let in_cluster =
+            self.env.var("KUBERNETES_SERVICE_HOST").is_some() || namespace_file.is_some();
+        if !in_cluster {
+            return Vec::new();
+        }
+
+        let mut attributes: HashMap<&str, String> = DOWNWARD_API_VARS
+            .iter()
+            .filter_map(|(var, key)| Some((*key, self.env.var(var)?)))
+            .collect();
+
+        if let Some(namespace) = namespace_file {
+            attributes.entry("k8s.namespace.name").or_insert(namespace);
+        }
+        // The hostname of a pod is its name unless overridden in the spec
+        if let Some(hostname) = self.env.var("HOSTNAME") {
+            attributes.entry("k8s.pod.name").or_insert(hostname);
+        }
+
+        let mut attributes: Vec<KeyValue> = attributes
+            .into_iter()
+            .map(|(key, value)| KeyValue::new(key, value))
+            .collect();
+        attributes.sort_by(|a, b| a.key.as_str().cmp(b.key.as_str()));
+        attributes
+    }
+}
+
+impl ResourceDetector for KubernetesResourceDetector {
+    fn detect(&self) -> Resource {
+        Resource::builder_empty()
+            .with_attributes(self.attributes())
+            .build()
+    }
+}
@@ -0,0 +1,157 @@
+//! A minimal blocking HTTP client for the metadata services of cloud providers.
+//!
+//! Resource detection is synchronous and may run inside a tokio runtime, where an
+//! async client can't be blocked on. The metadata services are plain HTTP on a local
+//! or link-local address, so a request over a `TcpStream` is all that is needed.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+/// How long a detector waits for a metadata service before giving up
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Send a `GET` request and return the body of a `200 OK` response
+pub(crate) fn get(url: &str, headers: &[(&str, &str)], timeout: Duration) -> io::Result<String> {
+    request("GET", url, headers, timeout)
+}
+
+/// Send a `PUT` request without a body and return the body of a `200 OK` response
+pub(crate) fn put(url: &str, headers: &[(&str, &str)], timeout: Duration) -> io::Result<String> {
+    request("PUT", url, headers, timeout)
+}
+
+fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    timeout: Duration,
+) -> io::Result<String> {
+    let deadline = Instant::now() + timeout;
+    let (host, path) = split_url(url)?;
+
+    let addr = host
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| invalid(format!("Could not resolve {}", host)))?;
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(remaining(deadline)?))?;
+    stream.set_write_timeout(Some(remaining(deadline)?))?;
+
+    let mut request = format!("{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n");
+    if method != "GET" {
+        request.push_str("Content-Length: 0\r\n");
+    }
+    for (name, value) in headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes())?;
+
+    let mut reader = BufReader::new(stream);
+    let mut status_line = String::new();
+    reader.read_line(&mut status_line)?;
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .ok_or_else(|| invalid(format!("Malformed status line from {}", host)))?;
+    if status != "200" {
+        return Err(invalid(format!("{} {} returned {}", method, url, status)));
+    }
+
+    let mut content_length = None;
+    let mut chunked = false;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse::<usize>().ok();
+            } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            }
+        }
+    }
+
+    reader
+        .get_ref()
+        .set_read_timeout(Some(remaining(deadline)?))?;
+    let body = match (chunked, content_length) {
+        (true, _) => read_chunked(&mut reader)?,
+        (false, Some(length)) => {
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body)?;
+            body
+        }
+        (false, None) => {
+            let mut body = Vec::new();
+            reader.read_to_end(&mut body)?;
+            body
+        }
+    };
+
+    String::from_utf8(body).map_err(|e| invalid(e.to_string()))
+}
+
+// Only `http://host[:port]/path` is supported, which is what metadata services use
+fn split_url(url: &str) -> io::Result<(String, &str)> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| invalid(format!("Unsupported metadata url {}", url)))?;
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let host = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+    Ok((host, path))
+}
+
+fn read_chunked(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let mut size_line = String::new();
+        reader.read_line(&mut size_line)?;
+        let size = size_line.trim().split(';').next().unwrap_or_default();
+        let size = usize::from_str_radix(size, 16).map_err(|e| invalid(e.to_string()))?;
+        if size == 0 {
+            return Ok(body);
+        }
+
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        // Every chunk ends with CRLF
+        reader.read_line(&mut String::new())?;
+    }
+}
+
+/// A non-empty string or number field of a JSON document, as a string
+pub(crate) fn string(json: &Value, field: &str) -> Option<String> {
+    match json.get(field)? {
+        Value::String(value) if !value.is_empty() => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn remaining(deadline: Instant) -> io::Result<Duration> {
+    deadline
+        .checked_duration_since(Instant::now())
+        .filter(|remaining| !remaining.is_zero())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::TimedOut, "Metadata request timed out"))
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,27 @@
+//! Resource detectors for the environments our services run in.
+//!
+//! The cloud detectors query metadata services over the network and are opt-in,
+//! behind the `aws`, `gcp` and `azure` features.
+
+#[cfg(feature = "aws")]
+mod aws;
+#[cfg(feature = "azure")]
+mod azure;
+mod container;
+mod env;
+#[cfg(feature = "gcp")]
+mod gcp;
+mod kubernetes;
+#[cfg(any(feature = "aws", feature = "gcp", feature = "azure"))]
+mod metadata;
+
+#[cfg(feature = "aws")]
+pub use aws::{EcsResourceDetector, EksResourceDetector, LambdaResourceDetector};
+#[cfg(feature = "azure")]
+pub use azure::AzureResourceDetector;
+pub use container::ContainerResourceDetector;
+#[cfg(feature = "gcp")]
+pub use gcp::GcpResourceDetector;
+pub use kubernetes::KubernetesResourceDetector;
+#[cfg(any(feature = "aws", feature = "gcp", feature = "azure"))]
+pub use metadata::DEFAULT_TIMEOUT;
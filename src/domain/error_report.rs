@@ -0,0 +1,132 @@
+//! Errors described for logs and spans.
+//!
+//! An [`ErrorReport`] keeps what is worth exporting about an error once it is boxed
+//! or about to be dropped: the name of its concrete type, the messages of its whole
+//! chain of sources and, when backtraces are enabled, where it was reported from.
+
+use std::any::type_name;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::error::Error;
+
+use crate::domain::telemetry::AttributeValue;
+
+/// Kind of errors whose concrete type is no longer known, e.g. `Box<dyn Error>`
+pub const UNKNOWN_ERROR_KIND: &str = "Error";
+
+/// An error's type name, message chain and backtrace
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorReport {
+    /// Name of the error's type, e.g. `std::io::error::Error`
+    pub kind: String,
+    /// The error's own message
+    pub message: String,
+    /// Messages of the error's sources, the closest one first
+    pub causes: Vec<String>,
+    /// The backtrace captured with the error, or where it was reported from
+    pub backtrace: Option<String>,
+}
+
+impl ErrorReport {
+    /// Describe an error whose concrete type is known
+    pub fn new<E: Error + 'static>(error: &E) -> Self {
+        let mut report = Self::from_dyn(error);
+        report.kind = type_name::<E>().to_string();
+        report
+    }
+
+    /// Describe a type-erased error, whose kind is reported as [`UNKNOWN_ERROR_KIND`]
+    pub fn from_dyn(error: &(dyn Error + 'static)) -> Self {
+        let mut causes = Vec::new();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            causes.push(cause.to_string());
+            source = cause.source();
+        }
+
+        Self {
+            kind: UNKNOWN_ERROR_KIND.to_string(),
+            message: error.to_string(),
+            causes,
+            backtrace: captured(&Backtrace::capture()),
+        }
+    }
+
+    /// Describe an `anyhow::Error`, with the backtrace it captured when created
+    #[cfg(feature = "anyhow")]
+    pub fn from_anyhow(error: &anyhow::Error) -> Self {
+        let mut chain = error.chain().map(ToString::to_string);
+        Self {
+            kind: type_name::<anyhow::Error>().to_string(),
+            message: chain.next().unwrap_or_default(),
+            causes: chain.collect(),
+            backtrace: captured(error.backtrace()).or_else(|| captured(&Backtrace::capture())),
+        }
+    }
+
+    /// Describe an `eyre::Report`
+    #[cfg(feature = "eyre")]
+    pub fn from_eyre(report: &eyre::Report) -> Self {
+        let mut chain = report.chain().map(ToString::to_string);
+        Self {
+            kind: type_name::<eyre::Report>().to_string(),
+            message: chain.next().unwrap_or_default(),
+            causes: chain.collect(),
+            backtrace: captured(&Backtrace::capture()),
+        }
+    }
+
+    /// The message chain followed by the backtrace, as exported in `error.stack`
+    pub fn stack(&self) -> String {
+        let mut stack = std::iter::once(self.message.as_str())
+            .chain(self.causes.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join("\n    caused by: ");
+        if let Some(backtrace) = &self.backtrace {
+            stack.push_str("\n\nstack backtrace:\n");
+            stack.push_str(backtrace);
+        }
+        stack
+    }
+
+    /// The `error.kind`, `error.message` and `error.stack` attributes
+    pub fn attributes(&self) -> Vec<(String, AttributeValue)> {
+        vec![
+            ("error.kind".to_string(), self.kind.as_str().into()),
+            ("error.message".to_string(), self.message.as_str().into()),
+            ("error.stack".to_string(), self.stack().into()),
+        ]
+    }
+
+    /// Attributes of the `exception` span event, following OpenTelemetry's conventions
+    pub fn exception_attributes(&self) -> Vec<(String, AttributeValue)> {
+        vec![
+            ("exception.type".to_string(), self.kind.as_str().into()),
+            (
+                "exception.message".to_string(),
+                self.message.as_str().into(),
+            ),
+            ("exception.stacktrace".to_string(), self.stack().into()),
+        ]
+    }
+}
+
+#[cfg(feature = "anyhow")]
+impl From<&anyhow::Error> for ErrorReport {
+    fn from(error: &anyhow::Error) -> Self {
+        Self::from_anyhow(error)
+    }
+}
+
+#[cfg(feature = "eyre")]
+impl From<&eyre::Report> for ErrorReport {
+    fn from(report: &eyre::Report) -> Self {
+        Self::from_eyre(report)
+    }
+}
+
+fn captured(backtrace: &Backtrace) -> Option<String> {
+    match backtrace.status() {
+        BacktraceStatus::Captured => Some(backtrace.to_string()),
+        _ => None,
+    }
+}
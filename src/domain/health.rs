@@ -0,0 +1,179 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use super::telemetry::Signal;
+
+/// Namespace reserved for the crate's own metrics
+pub const SELF_TELEMETRY_NAMESPACE: &str = "otel_tracing";
+
+/// Point-in-time view of how well a signal is being exported
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignalHealth {
+    /// Records (spans, log records or metric streams) successfully exported
+    pub exported: u64,
+    /// Export calls that returned an error
+    pub failed: u64,
+    /// Records that were lost, either in a failed export or before reaching the exporter
+    pub dropped: u64,
+    /// Records waiting to be exported, if the adapter can tell. Records a batch
+    /// processor discards because its queue is full still count here, and only move to
+    /// `dropped` once the adapter shuts down.
+    pub queue_depth: Option<u64>,
+    /// When the last export succeeded
+    pub last_export: Option<SystemTime>,
+    /// The error returned by the last failed export
+    pub last_error: Option<String>,
+}
+
+impl SignalHealth {
+    /// Returns true if the last export attempt succeeded, or nothing was exported yet
+    pub fn is_healthy(&self) -> bool {
+        self.last_error.is_none()
+    }
+}
+
+/// Health of every signal, `None` meaning the adapter does not report health
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthSnapshot {
+    pub traces: Option<SignalHealth>,
+    pub metrics: Option<SignalHealth>,
+    pub logs: Option<SignalHealth>,
+}
+
+impl HealthSnapshot {
+    /// Get the health of a single signal
+    pub fn get(&self, signal: Signal) -> Option<&SignalHealth> {
+        match signal {
+            Signal::Traces => self.traces.as_ref(),
+            Signal::Metrics => self.metrics.as_ref(),
+            Signal::Logs => self.logs.as_ref(),
+        }
+    }
+
+    /// Returns true if no reporting signal is currently failing to export
+    pub fn is_healthy(&self) -> bool {
+        [&self.traces, &self.metrics, &self.logs]
+            .into_iter()
+            .flatten()
+            .all(SignalHealth::is_healthy)
+    }
+}
+
+/// Live export counters shared between an adapter and its exporters
+#[derive(Debug, Default)]
+pub struct ExportStats {
+    produced: AtomicU64,
+    exported: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+    tracks_queue: bool,
+    last_export: Mutex<Option<SystemTime>>,
+    last_error: Mutex<Option<String>>,
+}
+
+impl ExportStats {
+    /// Create stats that also track the queue depth from the records produced
+    pub fn with_queue_tracking() -> Self {
+        Self {
+            tracks_queue: true,
+            ..Self::default()
+        }
+    }
+
+    /// Count records handed to the export pipeline
+    pub fn record_produced(&self, count: u64) {
+        self.produced.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Count records successfully exported
+    pub fn record_exported(&self, count: u64) {
+        self.exported.fetch_add(count, Ordering::Relaxed);
+        *self.last_export.lock().unwrap() = Some(SystemTime::now());
+        *self.last_error.lock().unwrap() = None;
+    }
+
+    /// Count an export call that failed, losing `count` records
+    pub fn record_failed(&self, count: u64, error: impl ToString) {
+        self.record_error(error);
+        self.dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Count an export call that failed without losing its records, e.g. kept for a retry
+    pub fn record_error(&self, error: impl ToString) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+        *self.last_error.lock().unwrap() = Some(error.to_string());
+    }
+
+    /// Count records lost before reaching the exporter
+    pub fn record_dropped(&self, count: u64) {
+        self.dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Call after a successful flush: anything still queued was discarded by the pipeline
+    pub fn settle(&self) {
+        if let Some(pending) = self.queue_depth() {
+            self.record_dropped(pending);
+        }
+    }
+
+    fn queue_depth(&self) -> Option<u64> {
+        self.tracks_queue.then(|| {
+            self.produced
+                .load(Ordering::Relaxed)
+                .saturating_sub(self.exported.load(Ordering::Relaxed))
+                .saturating_sub(self.dropped.load(Ordering::Relaxed))
+        })
+    }
+
+    /// Take a snapshot of the counters
+    pub fn snapshot(&self) -> SignalHealth {
+        SignalHealth {
+            exported: self.exported.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            queue_depth: self.queue_depth(),
+            last_export: *self.last_export.lock().unwrap(),
+            last_error: self.last_error.lock().unwrap().clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_exports_count_as_dropped() {
+        let stats = ExportStats::with_queue_tracking();
+        stats.record_produced(10);
+        stats.record_exported(4);
+        stats.record_failed(3, "connection refused");
+
+        let health = stats.snapshot();
+        assert_eq!(health.exported, 4);
+        assert_eq!(health.failed, 1);
+        assert_eq!(health.dropped, 3);
+        assert_eq!(health.queue_depth, Some(3));
+        assert_eq!(health.last_error.as_deref(), Some("connection refused"));
+        assert!(!health.is_healthy());
+
+        // Anything left after a flush never reached the exporter
+        stats.settle();
+        let health = stats.snapshot();
+        assert_eq!(health.dropped, 6);
+        assert_eq!(health.queue_depth, Some(0));
+    }
+
+    #[test]
+    fn test_successful_export_clears_last_error() {
+        let stats = ExportStats::default();
+        stats.record_failed(1, "timeout");
+        stats.record_exported(2);
+
+        let health = stats.snapshot();
+        assert!(health.is_healthy());
+        assert!(health.last_export.is_some());
+        assert_eq!(health.queue_depth, None);
+    }
+}
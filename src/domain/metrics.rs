@@ -1,4 +1,5 @@
 use std::fmt;
+use std::time::Duration;
 
 const NS: &str = "ns";
 
@@ -289,6 +290,22 @@ impl TimeUnit {
             Self::Week => "wk",
         }
     }
+
+    /// A duration expressed in this unit, e.g. `1.5` for 1500ms in seconds
+    pub fn convert(&self, duration: Duration) -> f64 {
+        let nanos = duration.as_nanos() as f64;
+        let seconds = duration.as_secs_f64();
+        match self {
+            Self::Nanosecond => nanos,
+            Self::Microsecond => nanos / 1e3,
+            Self::Millisecond => nanos / 1e6,
+            Self::Second => seconds,
+            Self::Minute => seconds / 60.0,
+            Self::Hour => seconds / 3_600.0,
+            Self::Day => seconds / 86_400.0,
+            Self::Week => seconds / 604_800.0,
+        }
+    }
 }
 
 /// Percentage-related units
@@ -1,2 +1,9 @@
+pub mod baggage;
+pub mod detectors;
+pub mod error_report;
+pub mod health;
+pub mod redaction;
+pub mod resource;
+pub mod scope;
 pub mod telemetry;
 pub mod metrics;
\ No newline at end of file
@@ -0,0 +1,391 @@
+//! Scrubbing of sensitive data out of telemetry.
+//!
+//! A [`Redactor`] is applied by the `TelemetryService` to log records and span
+//! attributes before they are handed to any adapter, so secrets and personal data
+//! never leave the process whatever the backend.
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use regex::{Match, Regex};
+use sha2::{Digest, Sha256};
+
+use crate::domain::error_report::ErrorReport;
+use crate::domain::telemetry::{AttributeValue, LogContext, Signal, SpanContext, TelemetryError};
+
+/// Replacement of masked values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// What happens to sensitive data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedactionAction {
+    /// Remove the attribute altogether
+    Drop,
+    /// Replace the data with [`REDACTED`]
+    Mask,
+    /// Replace the data with a salted SHA-256 digest, so equal values can still be correlated
+    Hash,
+}
+
+/// Sensitive data recognized in string values without any configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PiiDetector {
+    Email,
+    /// JSON Web Tokens, e.g. bearer tokens
+    Jwt,
+    /// Card numbers of 13 to 19 digits with the prefix of a card network, passing the
+    /// Luhn check
+    CreditCard,
+    /// IPv4 and IPv6 addresses
+    IpAddress,
+}
+
+impl PiiDetector {
+    pub const ALL: [PiiDetector; 4] = [
+        PiiDetector::Email,
+        PiiDetector::Jwt,
+        PiiDetector::CreditCard,
+        PiiDetector::IpAddress,
+    ];
+
+    fn patterns(&self) -> &'static [&'static str] {
+        match self {
+            PiiDetector::Email => &[r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"],
+            PiiDetector::Jwt => &[r"eyJ[A-Za-z0-9_-]+\.eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*"],
+            PiiDetector::CreditCard => &[r"\b\d(?:[ -]?\d){12,18}\b"],
+            PiiDetector::IpAddress => &[
+                r"\b(?:\d{1,3}\.){3}\d{1,3}\b",
+                r"(?i)(?:[0-9a-f]{0,4}:){2,7}[0-9a-f]{0,4}",
+            ],
+        }
+    }
+
+    // Drops the false positives the patterns let through, given the match in its text
+    fn confirms(&self, text: &str, candidate: Match<'_>) -> bool {
+        match self {
+            PiiDetector::Email | PiiDetector::Jwt => true,
+            PiiDetector::CreditCard => {
+                has_issuer_prefix(candidate.as_str()) && passes_luhn(candidate.as_str())
+            }
+            PiiDetector::IpAddress => {
+                let address = candidate.as_str();
+                address.parse::<Ipv4Addr>().is_ok()
+                    || (address.parse::<Ipv6Addr>().is_ok() && is_standalone_ipv6(text, candidate))
+            }
+        }
+    }
+}
+
+// Rust paths such as `std::io::Error` parse as IPv6 fragments (`d::`, `::E`), so an
+// address must stand on its own and have at least two non-empty groups
+fn is_standalone_ipv6(text: &str, candidate: Match<'_>) -> bool {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    let before = text[..candidate.start()].chars().next_back();
+    let after = text[candidate.end()..].chars().next();
+    let groups = candidate
+        .as_str()
+        .split(':')
+        .filter(|group| !group.is_empty())
+        .count();
+    !before.is_some_and(is_identifier) && !after.is_some_and(is_identifier) && groups >= 2
+}
+
+// Card numbers start with the prefix of a card network, unlike timestamps or ids that
+// happen to pass the Luhn check
+fn has_issuer_prefix(candidate: &str) -> bool {
+    let mut digits = candidate.chars().filter(char::is_ascii_digit);
+    matches!(
+        (digits.next(), digits.next()),
+        (Some('4' | '6'), _)
+            | (Some('5'), Some('1'..='5'))
+            | (Some('2'), Some('2'..='7'))
+            | (Some('3'), Some('4' | '7'))
+    )
+}
+
+fn passes_luhn(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+#[derive(Debug, Clone)]
+struct ValueRule {
+    regex: Regex,
+    action: RedactionAction,
+    detector: Option<PiiDetector>,
+}
+
+/// Rules scrubbing attributes and messages.
+///
+/// Attributes whose key matches a key rule are handled as a whole. Other string values,
+/// as well as log messages, have the parts matching a value rule or a detector
+/// replaced. A value rule dropping its match drops the whole attribute, and only
+/// masks its match within a message.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    key_rules: Vec<(String, RedactionAction)>,
+    value_rules: Vec<ValueRule>,
+    signals: Vec<Signal>,
+    salt: String,
+}
+
+impl Default for Redactor {
+    /// Mask passwords, secrets, tokens and credentials, as well as every built-in detector
+    fn default() -> Self {
+        let mut redactor = Self::new();
+        for key in [
+            "password",
+            "passwd",
+            "secret",
+            "token",
+            "authorization",
+            "cookie",
+            "api_key",
+        ] {
+            redactor = redactor.with_key_rule(key, RedactionAction::Mask);
+        }
+        for detector in PiiDetector::ALL {
+            redactor = redactor.with_detector(detector, RedactionAction::Mask);
+        }
+        redactor
+    }
+}
+
+impl Redactor {
+    /// A redactor without any rule, scrubbing logs and traces once configured
+    pub fn new() -> Self {
+        Self {
+            key_rules: Vec::new(),
+            value_rules: Vec::new(),
+            signals: vec![Signal::Logs, Signal::Traces],
+            salt: String::new(),
+        }
+    }
+
+    /// Apply `action` to attributes whose key contains `key`, ignoring case.
+    ///
+    /// `password` matches `password` as well as `db.password` or `X-Password`. When
+    /// several rules match a key, the last one added wins.
+    pub fn with_key_rule(mut self, key: impl Into<String>, action: RedactionAction) -> Self {
+        self.key_rules.push((key.into().to_lowercase(), action));
+        self
+    }
+
+    /// Apply `action` to parts of string values matching the regular expression `pattern`
+    pub fn with_value_rule(
+        mut self,
+        pattern: &str,
+        action: RedactionAction,
+    ) -> Result<Self, TelemetryError> {
+        let regex = Regex::new(pattern).map_err(|e| {
+            TelemetryError::config_with_source(format!("Invalid redaction pattern {}", pattern), e)
+        })?;
+        self.value_rules.push(ValueRule {
+            regex,
+            action,
+            detector: None,
+        });
+        Ok(self)
+    }
+
+    /// Apply `action` to the data recognized by a built-in detector
+    pub fn with_detector(mut self, detector: PiiDetector, action: RedactionAction) -> Self {
+        for pattern in detector.patterns() {
+            self.value_rules.push(ValueRule {
+                regex: Regex::new(pattern).expect("built-in patterns are valid"),
+                action,
+                detector: Some(detector),
+            });
+        }
+        self
+    }
+
+    /// Only scrub these signals. Metric attributes are never scrubbed, as they are
+    /// meant to be low-cardinality labels rather than data.
+    pub fn with_signals(mut self, signals: impl IntoIterator<Item = Signal>) -> Self {
+        self.signals = signals.into_iter().collect();
+        self
+    }
+
+    /// Salt hashed values, so they can't be matched against digests of guessed values
+    pub fn with_hash_salt(mut self, salt: impl Into<String>) -> Self {
+        self.salt = salt.into();
+        self
+    }
+
+    /// Whether the signal is scrubbed
+    pub fn applies_to(&self, signal: Signal) -> bool {
+        self.signals.contains(&signal)
+    }
+
+    /// Scrub the message and attributes of a log record
+    pub fn redact_log(&self, mut context: LogContext) -> LogContext {
+        if !self.applies_to(Signal::Logs) {
+            return context;
+        }
+
+        context.message = self.redact_text(&context.message).into_owned();
+        context.attributes = self
+            .redact_attributes(context.attributes.into_iter().collect())
+            .into_iter()
+            .collect();
+        context
+    }
+
+    /// Scrub the attributes a span is created with
+    pub fn redact_span(&self, mut context: SpanContext) -> SpanContext {
+        if self.applies_to(Signal::Traces) {
+            context.attributes = self.redact_attributes(context.attributes);
+        }
+        context
+    }
+
+    /// Scrub a list of attributes, leaving out the dropped ones
+    pub fn redact_attributes(
+        &self,
+        attributes: Vec<(String, AttributeValue)>,
+    ) -> Vec<(String, AttributeValue)> {
+        attributes
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let value = self.redact_attribute(&key, value)?;
+                Some((key, value))
+            })
+            .collect()
+    }
+
+    /// Scrub a single attribute, `None` meaning it is dropped
+    pub fn redact_attribute(&self, key: &str, value: AttributeValue) -> Option<AttributeValue> {
+        let lowercase_key = key.to_lowercase();
+        if let Some((_, action)) = self
+            .key_rules
+            .iter()
+            .rev()
+            .find(|(rule, _)| lowercase_key.contains(rule.as_str()))
+        {
+            return match action {
+                RedactionAction::Drop => None,
+                RedactionAction::Mask => Some(AttributeValue::String(REDACTED.to_string())),
+                RedactionAction::Hash => {
+                    Some(AttributeValue::String(self.hash(&value.to_string())))
+                }
+            };
+        }
+
+        let AttributeValue::String(text) = value else {
+            return Some(value);
+        };
+        let dropped = self.value_rules.iter().any(|rule| {
+            rule.action == RedactionAction::Drop && self.find(rule, &text).next().is_some()
+        });
+        if dropped {
+            return None;
+        }
+
+        Some(AttributeValue::String(self.redact_text(&text).into_owned()))
+    }
+
+    /// Replace the parts of free text matching a value rule or a detector
+    pub fn redact_text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for rule in &self.value_rules {
+            let matches: Vec<(usize, usize)> = self.find(rule, &text).collect();
+            if matches.is_empty() {
+                continue;
+            }
+
+            let mut redacted = String::with_capacity(text.len());
+            let mut last = 0;
+            for (start, end) in matches {
+                redacted.push_str(&text[last..start]);
+                match rule.action {
+                    RedactionAction::Hash => redacted.push_str(&self.hash(&text[start..end])),
+                    RedactionAction::Drop | RedactionAction::Mask => redacted.push_str(REDACTED),
+                }
+                last = end;
+            }
+            redacted.push_str(&text[last..]);
+            text = Cow::Owned(redacted);
+        }
+        text
+    }
+
+    /// Scrub an error and its sources, keeping the chain so adapters can still walk it
+    pub fn redact_error(&self, error: &(dyn Error + 'static)) -> RedactedError {
+        RedactedError {
+            message: self.redact_text(&error.to_string()).into_owned(),
+            source: error
+                .source()
+                .map(|source| Box::new(self.redact_error(source))),
+        }
+    }
+
+    /// Scrub the messages of an error report
+    pub fn redact_report(&self, mut report: ErrorReport) -> ErrorReport {
+        report.message = self.redact_text(&report.message).into_owned();
+        for cause in &mut report.causes {
+            *cause = self.redact_text(cause).into_owned();
+        }
+        report
+    }
+
+    // Byte ranges of the matches of a rule, once confirmed by its detector
+    fn find<'t>(
+        &'t self,
+        rule: &'t ValueRule,
+        text: &'t str,
+    ) -> impl Iterator<Item = (usize, usize)> + 't {
+        rule.regex
+            .find_iter(text)
+            .filter(|m| {
+                rule.detector
+                    .is_none_or(|detector| detector.confirms(text, *m))
+            })
+            .map(|m| (m.start(), m.end()))
+    }
+
+    fn hash(&self, value: &str) -> String {
+        let digest = Sha256::new()
+            .chain_update(self.salt.as_bytes())
+            .chain_update(value.as_bytes())
+            .finalize();
+        format!("sha256:{:x}", digest)
+    }
+}
+
+/// An error whose message, and those of its sources, went through a [`Redactor`]
+#[derive(Debug)]
+pub struct RedactedError {
+    message: String,
+    source: Option<Box<RedactedError>>,
+}
+
+impl Display for RedactedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for RedactedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
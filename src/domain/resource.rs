@@ -0,0 +1,219 @@
+use opentelemetry::KeyValue;
+use opentelemetry_resource_detectors::{
+    HostResourceDetector, OsResourceDetector, ProcessResourceDetector,
+};
+use opentelemetry_sdk::resource::{
+    EnvResourceDetector, ResourceDetector, SdkProvidedResourceDetector, TelemetryResourceDetector,
+};
+use opentelemetry_sdk::Resource;
+
+use super::detectors::{self, ContainerResourceDetector, KubernetesResourceDetector};
+use super::telemetry::{to_key_value, AttributeValue};
+
+/// The sources a resource can be populated from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Detector {
+    /// `host.name` and `host.arch`
+    Host,
+    /// `os.type`
+    Os,
+    /// `process.*` such as the pid and command line
+    Process,
+    /// The default `service.name`
+    Sdk,
+    /// `OTEL_RESOURCE_ATTRIBUTES`
+    Env,
+    /// `telemetry.sdk.*`
+    Telemetry,
+    /// `container.id` from the cgroup of the process
+    Container,
+    /// `k8s.*` from the downward API and the service account
+    Kubernetes,
+    /// `cloud.*` and `aws.ecs.*` from the ECS task metadata endpoint
+    #[cfg(feature = "aws")]
+    Ecs,
+    /// `cloud.*` and `host.*` from the EC2 instance metadata of EKS nodes
+    #[cfg(feature = "aws")]
+    Eks,
+    /// `cloud.*` and `faas.*` from the Lambda environment
+    #[cfg(feature = "aws")]
+    Lambda,
+    /// `cloud.*`, `host.*` and `faas.*` from the GCE metadata server
+    #[cfg(feature = "gcp")]
+    Gcp,
+    /// `cloud.*`, `host.*` and `azure.*` from the Azure instance metadata service
+    #[cfg(feature = "azure")]
+    Azure,
+}
+
+impl Detector {
+    /// The detectors used unless configured otherwise.
+    /// Cloud detectors are never among them, they have to be added explicitly.
+    pub const DEFAULTS: [Detector; 8] = [
+        Detector::Host,
+        Detector::Os,
+        Detector::Process,
+        Detector::Container,
+        Detector::Kubernetes,
+        Detector::Sdk,
+        Detector::Env,
+        Detector::Telemetry,
+    ];
+
+    fn detector(self) -> Box<dyn ResourceDetector> {
+        match self {
+            Detector::Host => Box::new(HostResourceDetector::default()),
+            Detector::Os => Box::new(OsResourceDetector),
+            Detector::Process => Box::new(ProcessResourceDetector),
+            Detector::Sdk => Box::new(SdkProvidedResourceDetector),
+            Detector::Env => Box::new(EnvResourceDetector::new()),
+            Detector::Telemetry => Box::new(TelemetryResourceDetector),
+            Detector::Container => Box::new(ContainerResourceDetector::default()),
+            Detector::Kubernetes => Box::new(KubernetesResourceDetector::default()),
+            #[cfg(feature = "aws")]
+            Detector::Ecs => Box::new(detectors::EcsResourceDetector::default()),
+            #[cfg(feature = "aws")]
+            Detector::Eks => Box::new(detectors::EksResourceDetector::default()),
+            #[cfg(feature = "aws")]
+            Detector::Lambda => Box::new(detectors::LambdaResourceDetector::default()),
+            #[cfg(feature = "gcp")]
+            Detector::Gcp => Box::new(detectors::GcpResourceDetector::default()),
+            #[cfg(feature = "azure")]
+            Detector::Azure => Box::new(detectors::AzureResourceDetector::default()),
+        }
+    }
+}
+
+/// Describes the entity producing telemetry, built into the `Resource` given to every adapter.
+///
+/// Values that are not set explicitly fall back to `OTEL_SERVICE_NAME`,
+/// `OTEL_SERVICE_VERSION` and `OTEL_DEPLOYMENT_ENVIRONMENT`. Explicit values
+/// always take precedence over what the detectors find.
+#[derive(Debug, Clone)]
+pub struct ResourceBuilder {
+    service_name: Option<String>,
+    service_version: Option<String>,
+    service_namespace: Option<String>,
+    service_instance_id: Option<String>,
+    environment: Option<String>,
+    attributes: Vec<(String, AttributeValue)>,
+    detectors: Vec<Detector>,
+}
+
+impl Default for ResourceBuilder {
+    fn default() -> Self {
+        Self {
+            service_name: None,
+            service_version: None,
+            service_namespace: None,
+            service_instance_id: None,
+            environment: None,
+            attributes: Vec::new(),
+            detectors: Detector::DEFAULTS.to_vec(),
+        }
+    }
+}
+
+impl ResourceBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_name = Some(name.into());
+        self
+    }
+
+    pub fn with_service_version(mut self, version: impl Into<String>) -> Self {
+        self.service_version = Some(version.into());
+        self
+    }
+
+    pub fn with_service_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.service_namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn with_service_instance_id(mut self, instance_id: impl Into<String>) -> Self {
+        self.service_instance_id = Some(instance_id.into());
+        self
+    }
+
+    pub fn with_environment(mut self, environment: impl Into<String>) -> Self {
+        self.environment = Some(environment.into());
+        self
+    }
+
+    pub fn with_attribute(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<AttributeValue>,
+    ) -> Self {
+        self.attributes.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Vec<(String, AttributeValue)>) -> Self {
+        self.attributes.extend(attributes);
+        self
+    }
+
+    /// Replace the detectors to run
+    pub fn with_detectors(mut self, detectors: impl IntoIterator<Item = Detector>) -> Self {
+        self.detectors = detectors.into_iter().collect();
+        self
+    }
+
+    /// Add a detector to the ones already configured
+    pub fn with_detector(mut self, detector: Detector) -> Self {
+        if !self.detectors.contains(&detector) {
+            self.detectors.push(detector);
+        }
+        self
+    }
+
+    /// Run the detectors and build the resource
+    pub fn build(&self) -> Resource {
+        let detectors: Vec<_> = self
+            .detectors
+            .iter()
+            .map(|detector| detector.detector())
+            .collect();
+
+        let mut attributes = vec![
+            KeyValue::new(
+                "service.version",
+                or_env(&self.service_version, "OTEL_SERVICE_VERSION"),
+            ),
+            KeyValue::new(
+                "deployment.environment",
+                or_env(&self.environment, "OTEL_DEPLOYMENT_ENVIRONMENT"),
+            ),
+        ];
+        if let Some(namespace) = &self.service_namespace {
+            attributes.push(KeyValue::new("service.namespace", namespace.clone()));
+        }
+        if let Some(instance_id) = &self.service_instance_id {
+            attributes.push(KeyValue::new("service.instance.id", instance_id.clone()));
+        }
+        attributes.extend(
+            self.attributes
+                .iter()
+                .map(|(key, value)| to_key_value(key.clone(), value)),
+        );
+
+        // Later attributes override earlier ones, so explicit values win over detected ones
+        Resource::builder_empty()
+            .with_detectors(&detectors)
+            .with_service_name(or_env(&self.service_name, "OTEL_SERVICE_NAME"))
+            .with_attributes(attributes)
+            .build()
+    }
+}
+
+fn or_env(value: &Option<String>, var: &str) -> String {
+    value
+        .clone()
+        .or_else(|| std::env::var(var).ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
@@ -0,0 +1,102 @@
+//! Attributes set once for a scope, e.g. a request's id and tenant, and added to the
+//! telemetry emitted within it.
+//!
+//! Scopes are stored in the OpenTelemetry context, so that they are kept across
+//! `spawn_with_context`. Unlike baggage, they are never propagated to other services.
+
+use std::collections::HashMap;
+
+use opentelemetry::Context;
+
+use crate::domain::baggage::BaggageAttributes;
+use crate::domain::telemetry::{AttributeValue, Signal};
+
+// The attributes of the current scope and those it is nested in, stored in the context
+struct Scope(Vec<(String, AttributeValue)>);
+
+/// The attributes of the current scope, including those of the scopes it is nested in
+pub fn scoped_attributes() -> Vec<(String, AttributeValue)> {
+    Context::map_current(|context| {
+        context
+            .get::<Scope>()
+            .map(|scope| scope.0.clone())
+            .unwrap_or_default()
+    })
+}
+
+/// The current context with a nested scope, whose attributes replace those of the same
+/// key in the outer scopes
+pub fn scoped_context<I, K, V>(attributes: I) -> Context
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<AttributeValue>,
+{
+    let mut scoped = scoped_attributes();
+    for (key, value) in attributes {
+        let (key, value) = (key.into(), value.into());
+        match scoped.iter_mut().find(|(existing, _)| *existing == key) {
+            Some(existing) => existing.1 = value,
+            None => scoped.push((key, value)),
+        }
+    }
+    Context::current().with_value(Scope(scoped))
+}
+
+/// The attributes the service adds from the current context to what it emits: those of
+/// the current scope, for the selected signals, and the selected baggage entries.
+/// Attributes set explicitly win over scoped ones, which win over baggage.
+#[derive(Debug, Clone)]
+pub(crate) struct ContextAttributes {
+    signals: Vec<Signal>,
+    baggage: BaggageAttributes,
+}
+
+impl Default for ContextAttributes {
+    fn default() -> Self {
+        Self::new(
+            vec![Signal::Traces, Signal::Metrics, Signal::Logs],
+            BaggageAttributes::default(),
+        )
+    }
+}
+
+impl ContextAttributes {
+    pub(crate) fn new(signals: Vec<Signal>, baggage: BaggageAttributes) -> Self {
+        Self { signals, baggage }
+    }
+
+    // The attributes of the current context to add to a signal
+    fn current(&self, signal: Signal) -> Vec<(String, AttributeValue)> {
+        let mut attributes = if self.signals.contains(&signal) {
+            scoped_attributes()
+        } else {
+            Vec::new()
+        };
+        if !self.baggage.is_empty() {
+            attributes.extend(self.baggage.from_context(&Context::current()));
+        }
+        attributes
+    }
+
+    /// Add the attributes of the current context that are not set already
+    pub(crate) fn extend(&self, signal: Signal, attributes: &mut Vec<(String, AttributeValue)>) {
+        for (key, value) in self.current(signal) {
+            if !attributes.iter().any(|(existing, _)| *existing == key) {
+                attributes.push((key, value));
+            }
+        }
+    }
+
+    /// Add the attributes of the current context to those of a log record that do not
+    /// set them already
+    pub(crate) fn extend_map(
+        &self,
+        signal: Signal,
+        attributes: &mut HashMap<String, AttributeValue>,
+    ) {
+        for (key, value) in self.current(signal) {
+            attributes.entry(key).or_insert(value);
+        }
+    }
+}
@@ -1,65 +1,356 @@
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{self, Display, Formatter};
-use std::sync::OnceLock;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use opentelemetry::logs::Severity;
 use opentelemetry::KeyValue;
-use opentelemetry_resource_detectors::{
-    HostResourceDetector, OsResourceDetector, ProcessResourceDetector,
-};
-use opentelemetry_sdk::resource::{
-    EnvResourceDetector, SdkProvidedResourceDetector, TelemetryResourceDetector,
-};
-use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::OTelSdkError;
 
 use super::metrics::MetricUnit;
 
+/// A boxed error that can be sent across threads, used as the source of telemetry errors
+pub type BoxError = Box<dyn Error + Send + Sync + 'static>;
+
 #[derive(Debug)]
 pub enum TelemetryError {
-    TracerInitError(String),
-    MetricsInitError(String),
-    LoggerInitError(String),
-    ShutdownError(String),
+    /// The global telemetry service was initialized more than once
+    AlreadyInitialized,
+    /// The global telemetry service was used before being initialized
+    NotInitialized,
+    /// The configuration is missing or invalid
+    Config {
+        message: String,
+        source: Option<BoxError>,
+    },
+    /// The exporter of a signal could not be built
+    ExporterBuild { signal: Signal, source: BoxError },
+    /// Exporting, flushing or shutting down the exporter of a signal failed
+    Export { signal: Signal, source: BoxError },
+    /// An operation on a signal did not complete in time
+    Timeout { signal: Signal, timeout: Duration },
+    /// One or more signals could not be flushed
+    Flush(Box<FlushReport>),
+    /// One or more signals did not shut down cleanly
+    Shutdown(ShutdownReport),
+}
+
+impl TelemetryError {
+    /// Create a configuration error without an underlying cause
+    pub fn config(message: impl Into<String>) -> Self {
+        TelemetryError::Config {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Create a configuration error caused by another error
+    pub fn config_with_source(message: impl Into<String>, source: impl Into<BoxError>) -> Self {
+        TelemetryError::Config {
+            message: message.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Create an exporter build error for a signal
+    pub fn exporter_build(signal: Signal, source: impl Into<BoxError>) -> Self {
+        TelemetryError::ExporterBuild {
+            signal,
+            source: source.into(),
+        }
+    }
+
+    /// Create an export error for a signal
+    pub fn export(signal: Signal, source: impl Into<BoxError>) -> Self {
+        TelemetryError::Export {
+            signal,
+            source: source.into(),
+        }
+    }
+
+    /// Map an error returned by an OpenTelemetry SDK provider, keeping timeouts distinct
+    pub fn from_sdk(signal: Signal, error: OTelSdkError) -> Self {
+        match error {
+            OTelSdkError::Timeout(timeout) => TelemetryError::Timeout { signal, timeout },
+            error => TelemetryError::export(signal, error),
+        }
+    }
+
+    /// The signal this error relates to, if it relates to a single one
+    pub fn signal(&self) -> Option<Signal> {
+        match self {
+            TelemetryError::ExporterBuild { signal, .. }
+            | TelemetryError::Export { signal, .. }
+            | TelemetryError::Timeout { signal, .. } => Some(*signal),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for TelemetryError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            TelemetryError::TracerInitError(msg) => {
-                write!(f, "Tracer initialization error: {}", msg)
+            TelemetryError::AlreadyInitialized => {
+                write!(f, "Telemetry service already initialized")
+            }
+            TelemetryError::NotInitialized => write!(f, "Telemetry service not initialized"),
+            TelemetryError::Config { message, .. } => {
+                write!(f, "Invalid telemetry configuration: {}", message)
+            }
+            TelemetryError::ExporterBuild { signal, source } => {
+                write!(f, "Failed to build {} exporter: {}", signal, source)
             }
-            TelemetryError::MetricsInitError(msg) => {
-                write!(f, "Metrics initialization error: {}", msg)
+            TelemetryError::Export { signal, source } => {
+                write!(f, "Failed to export {}: {}", signal, source)
+            }
+            TelemetryError::Timeout { signal, timeout } => {
+                write!(f, "Timed out after {:?} while handling {}", timeout, signal)
+            }
+            TelemetryError::Flush(report) => write!(f, "Flush incomplete: {}", report),
+            TelemetryError::Shutdown(report) => write!(f, "Shutdown incomplete: {}", report),
+        }
+    }
+}
+
+impl Error for TelemetryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TelemetryError::Config { source, .. } => source
+                .as_deref()
+                .map(|e| e as &(dyn Error + 'static)),
+            TelemetryError::ExporterBuild { source, .. } | TelemetryError::Export { source, .. } => {
+                Some(source.as_ref())
             }
-            TelemetryError::LoggerInitError(msg) => {
-                write!(f, "Logger initialization error: {}", msg)
+            TelemetryError::Flush(report) => report
+                .failures()
+                .next()
+                .map(|(_, e)| e as &(dyn Error + 'static)),
+            TelemetryError::Shutdown(report) => {
+                report.failures().find_map(|(_, failure)| match failure {
+                    SignalFailure::Failed(e) => Some(e.as_ref() as &(dyn Error + 'static)),
+                    SignalFailure::TimedOut => None,
+                })
             }
-            TelemetryError::ShutdownError(msg) => write!(f, "Shutdown error: {}", msg),
+            _ => None,
+        }
+    }
+}
+
+/// The telemetry signals handled by the service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signal {
+    Traces,
+    Metrics,
+    Logs,
+}
+
+impl Display for Signal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Signal::Traces => write!(f, "traces"),
+            Signal::Metrics => write!(f, "metrics"),
+            Signal::Logs => write!(f, "logs"),
+        }
+    }
+}
+
+/// Why a signal could not be shut down cleanly
+#[derive(Debug)]
+pub enum SignalFailure {
+    /// The signal's adapter returned an error
+    Failed(Box<TelemetryError>),
+    /// The shutdown deadline passed before the signal finished
+    TimedOut,
+}
+
+impl Display for SignalFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SignalFailure::Failed(e) => write!(f, "failed: {}", e),
+            SignalFailure::TimedOut => write!(f, "timed out"),
+        }
+    }
+}
+
+/// Signals that did not shut down cleanly, `None` meaning the signal shut down fine
+#[derive(Debug, Default)]
+pub struct ShutdownReport {
+    pub traces: Option<SignalFailure>,
+    pub metrics: Option<SignalFailure>,
+    pub logs: Option<SignalFailure>,
+}
+
+impl ShutdownReport {
+    /// Returns true if every signal shut down cleanly
+    pub fn is_ok(&self) -> bool {
+        self.traces.is_none() && self.metrics.is_none() && self.logs.is_none()
+    }
+
+    /// Get the failure for a single signal, if any
+    pub fn get(&self, signal: Signal) -> Option<&SignalFailure> {
+        match signal {
+            Signal::Traces => self.traces.as_ref(),
+            Signal::Metrics => self.metrics.as_ref(),
+            Signal::Logs => self.logs.as_ref(),
+        }
+    }
+
+    /// Record the failure of a single signal
+    pub fn set(&mut self, signal: Signal, failure: SignalFailure) {
+        match signal {
+            Signal::Traces => self.traces = Some(failure),
+            Signal::Metrics => self.metrics = Some(failure),
+            Signal::Logs => self.logs = Some(failure),
+        }
+    }
+
+    /// Iterate over the signals that failed or timed out
+    pub fn failures(&self) -> impl Iterator<Item = (Signal, &SignalFailure)> {
+        [
+            (Signal::Traces, self.traces.as_ref()),
+            (Signal::Metrics, self.metrics.as_ref()),
+            (Signal::Logs, self.logs.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(signal, failure)| failure.map(|f| (signal, f)))
+    }
+
+    /// Turn the report into an error if any signal failed
+    pub fn into_result(self) -> Result<(), TelemetryError> {
+        if self.is_ok() {
+            Ok(())
+        } else {
+            Err(TelemetryError::Shutdown(self))
+        }
+    }
+}
+
+impl Display for ShutdownReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let failures: Vec<String> = self
+            .failures()
+            .map(|(signal, failure)| format!("{} {}", signal, failure))
+            .collect();
+        write!(f, "{}", failures.join("; "))
+    }
+}
+
+/// Outcome of flushing every signal, one result per signal
+#[derive(Debug)]
+pub struct FlushReport {
+    pub traces: Result<(), TelemetryError>,
+    pub metrics: Result<(), TelemetryError>,
+    pub logs: Result<(), TelemetryError>,
+}
+
+impl FlushReport {
+    /// Returns true if every signal was flushed successfully
+    pub fn is_ok(&self) -> bool {
+        self.traces.is_ok() && self.metrics.is_ok() && self.logs.is_ok()
+    }
+
+    /// Get the result for a single signal
+    pub fn get(&self, signal: Signal) -> &Result<(), TelemetryError> {
+        match signal {
+            Signal::Traces => &self.traces,
+            Signal::Metrics => &self.metrics,
+            Signal::Logs => &self.logs,
+        }
+    }
+
+    /// Iterate over the signals that could not be flushed
+    pub fn failures(&self) -> impl Iterator<Item = (Signal, &TelemetryError)> {
+        [
+            (Signal::Traces, &self.traces),
+            (Signal::Metrics, &self.metrics),
+            (Signal::Logs, &self.logs),
+        ]
+        .into_iter()
+        .filter_map(|(signal, result)| result.as_ref().err().map(|e| (signal, e)))
+    }
+
+    /// Turn the report into an error if any signal could not be flushed
+    pub fn into_result(self) -> Result<(), TelemetryError> {
+        if self.is_ok() {
+            Ok(())
+        } else {
+            Err(TelemetryError::Flush(Box::new(self)))
         }
     }
 }
 
-impl Error for TelemetryError {}
+impl Display for FlushReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let failures: Vec<String> = self
+            .failures()
+            .map(|(signal, e)| format!("{}: {}", signal, e))
+            .collect();
+        write!(f, "{}", failures.join("; "))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct SpanContext {
     pub name: String,
+    pub kind: SpanKind,
     pub attributes: Vec<(String, AttributeValue)>,
+    /// Spans this one relates to without being their child, e.g. the messages of a batch
+    pub links: Vec<opentelemetry::trace::SpanContext>,
 }
 
 impl SpanContext {
     pub fn new(name: String) -> Self {
         Self {
             name,
+            kind: SpanKind::default(),
             attributes: Vec::new(),
+            links: Vec::new(),
         }
     }
 
+    pub fn with_kind(mut self, kind: SpanKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
     pub fn with_attributes(mut self, attributes: Vec<(String, AttributeValue)>) -> Self {
         self.attributes.extend(attributes);
         self
     }
+
+    pub fn with_links(mut self, links: Vec<opentelemetry::trace::SpanContext>) -> Self {
+        self.links.extend(links);
+        self
+    }
+}
+
+/// Role of a span in a trace, e.g. handling a request or sending one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpanKind {
+    /// An operation within the service
+    #[default]
+    Internal,
+    /// Handling a request from a client
+    Server,
+    /// A request to another service
+    Client,
+    /// Sending a message to be processed later
+    Producer,
+    /// Processing a message sent by a producer
+    Consumer,
+}
+
+impl From<SpanKind> for opentelemetry::trace::SpanKind {
+    fn from(kind: SpanKind) -> Self {
+        match kind {
+            SpanKind::Internal => Self::Internal,
+            SpanKind::Server => Self::Server,
+            SpanKind::Client => Self::Client,
+            SpanKind::Producer => Self::Producer,
+            SpanKind::Consumer => Self::Consumer,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -99,8 +390,12 @@ impl AttributeValue {
     }
 }
 
-/// Log level for log events
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Directives of the log filter used when none is configured.
+/// The exporters' own transports are silenced so that exporting logs does not produce more logs.
+pub const DEFAULT_LOG_FILTER: &str = "info,opentelemetry=info,hyper=off,tonic=off,h2=off,reqwest=off";
+
+/// Log level for log events, ordered from the most verbose to the most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LogLevel {
     /// Very detailed logs, potentially of all levels.
     Trace,
@@ -127,6 +422,56 @@ impl Default for LogLevel {
     }
 }
 
+impl LogLevel {
+    /// The OpenTelemetry severity of records at this level
+    pub fn severity_number(&self) -> Severity {
+        match self {
+            LogLevel::Trace => Severity::Trace,
+            LogLevel::Debug => Severity::Debug,
+            LogLevel::Info => Severity::Info,
+            LogLevel::Warn => Severity::Warn,
+            LogLevel::Error => Severity::Error,
+            LogLevel::Critical => Severity::Fatal,
+        }
+    }
+
+    /// The OpenTelemetry severity text of records at this level
+    pub fn severity_text(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+            LogLevel::Critical => "FATAL",
+        }
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.severity_text())
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = TelemetryError;
+
+    /// Parse a level name, ignoring case. `warning`, `err`, `fatal` and `crit` are
+    /// accepted as well, as used by other logging libraries.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "trace" => Ok(LogLevel::Trace),
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" | "err" => Ok(LogLevel::Error),
+            "critical" | "crit" | "fatal" => Ok(LogLevel::Critical),
+            _ => Err(TelemetryError::config(format!("Unknown log level {}", s))),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MetricContext {
     pub name: String,
@@ -277,34 +622,6 @@ impl From<bool> for AttributeValue {
     }
 }
 
-pub fn get_resource() -> Resource {
-    static RESOURCE: OnceLock<Resource> = OnceLock::new();
-    RESOURCE
-        .get_or_init(|| {
-            Resource::builder()
-                .with_detector(Box::new(HostResourceDetector::default()))
-                .with_detector(Box::new(OsResourceDetector))
-                .with_detector(Box::new(ProcessResourceDetector))
-                .with_detector(Box::new(SdkProvidedResourceDetector))
-                .with_detector(Box::new(EnvResourceDetector::new()))
-                .with_detector(Box::new(TelemetryResourceDetector))
-                .with_service_name(
-                    std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "unknown".to_string()),
-                )
-                .with_attribute(KeyValue::new(
-                    "service.version",
-                    std::env::var("OTEL_SERVICE_VERSION").unwrap_or_else(|_| "unknown".to_string()),
-                ))
-                .with_attribute(KeyValue::new(
-                    "deployment.environment",
-                    std::env::var("OTEL_DEPLOYMENT_ENVIRONMENT")
-                        .unwrap_or_else(|_| "unknown".to_string()),
-                ))
-                .build()
-        })
-        .clone()
-}
-
 // Convert AttributeValue to OpenTelemetry KeyValue
 pub fn to_key_value(key: String, value: &AttributeValue) -> KeyValue {
     match value {
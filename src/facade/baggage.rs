@@ -0,0 +1,79 @@
+//! Request-scoped entries, e.g. a tenant id or feature flag cohort, that travel with
+//! the context across `spawn_with_context` and, through the configured propagators,
+//! network hops.
+//!
+//! Entries are not recorded by themselves; select those to copy onto spans, log
+//! records and metric points with `TelemetryServiceBuilder::with_baggage_attributes`.
+
+use opentelemetry::baggage::{Baggage, BaggageExt};
+use opentelemetry::context::FutureExt;
+use opentelemetry::{Context, ContextGuard};
+
+/// Set an entry of the current baggage, until the returned guard is dropped.
+#[must_use = "the entry is unset once the guard is dropped"]
+pub fn set(key: impl Into<String>, value: impl Into<String>) -> ContextGuard {
+    context_with([(key, value)]).attach()
+}
+
+/// Get an entry of the current baggage.
+pub fn get(key: &str) -> Option<String> {
+    Context::current()
+        .baggage()
+        .get(key)
+        .map(|value| value.as_str().to_string())
+}
+
+/// Remove an entry of the current baggage, until the returned guard is dropped.
+#[must_use = "the entry is restored once the guard is dropped"]
+pub fn remove(key: &str) -> ContextGuard {
+    let context = Context::current();
+    let mut baggage = owned(context.baggage());
+    baggage.remove(key);
+    context.with_baggage(baggage).attach()
+}
+
+/// Execute a function with entries added to the current baggage.
+pub fn with_baggage<I, K, V, F, R>(entries: I, f: F) -> R
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+    F: FnOnce() -> R,
+{
+    let _guard = context_with(entries).attach();
+    f()
+}
+
+/// Execute a future with entries added to the current baggage.
+pub async fn with_async_baggage<I, K, V, F>(entries: I, fut: F) -> F::Output
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+    F: std::future::Future,
+{
+    fut.with_context(context_with(entries)).await
+}
+
+/// The current context with entries added to its baggage, e.g. to attach in a thread.
+pub fn context_with<I, K, V>(entries: I) -> Context
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+{
+    let context = Context::current();
+    let mut baggage = owned(context.baggage());
+    for (key, value) in entries {
+        baggage.insert(key.into(), value.into());
+    }
+    context.with_baggage(baggage)
+}
+
+// A copy of a context's baggage, to be changed and set in a new context
+fn owned(baggage: &Baggage) -> Baggage {
+    baggage
+        .iter()
+        .map(|(key, (value, metadata))| (key.clone(), (value.clone(), metadata.clone())))
+        .collect()
+}
@@ -4,7 +4,7 @@
 
 use std::collections::HashMap;
 
-use crate::{domain::telemetry::LogContext, AttributeValue, LogLevel};
+use crate::{domain::error_report::ErrorReport, domain::telemetry::{BoxError, LogContext}, AttributeValue, LogLevel};
 use super::service;
 
 /// Log a message.
@@ -12,15 +12,86 @@ pub fn log(context: LogContext) {
     service().log(context)
 }
 
-/// Log a message.
+/// Log an error, which may come from another task or thread.
 pub fn log_error(
-    error: Box<dyn std::error::Error>,
+    error: impl Into<BoxError>,
     target: Option<&str>,
     attributes: Vec<(String, AttributeValue)>
 ) {
     service().log_error(error, target, attributes)
 }
 
+/// Log an error, reporting the name of its type as `error.kind`
+pub fn report_error<E: std::error::Error + 'static>(
+    error: &E,
+    target: Option<&str>,
+    attributes: Vec<(String, AttributeValue)>
+) {
+    service().report_error(error, target, attributes)
+}
+
+// Lets `error_log!` report the type of errors that have a concrete one, while still
+// accepting boxed errors and plain messages. `(&error).error_dispatch()` resolves to
+// `TypedError` whenever the error implements `Error`, `ErasedError` otherwise.
+#[doc(hidden)]
+pub mod error_dispatch {
+    use std::error::Error;
+
+    use crate::domain::telemetry::BoxError;
+    use crate::AttributeValue;
+
+    pub struct Typed;
+
+    pub struct Erased;
+
+    pub trait TypedError {
+        fn error_dispatch(&self) -> Typed {
+            Typed
+        }
+    }
+
+    impl<E: Error + 'static> TypedError for E {}
+
+    pub trait ErasedError {
+        fn error_dispatch(&self) -> Erased {
+            Erased
+        }
+    }
+
+    impl<E: Into<BoxError>> ErasedError for &E {}
+
+    impl Typed {
+        pub fn log<E: Error + 'static>(
+            self,
+            error: E,
+            target: Option<&str>,
+            attributes: Vec<(String, AttributeValue)>,
+        ) {
+            super::report_error(&error, target, attributes)
+        }
+    }
+
+    impl Erased {
+        pub fn log(
+            self,
+            error: impl Into<BoxError>,
+            target: Option<&str>,
+            attributes: Vec<(String, AttributeValue)>,
+        ) {
+            super::log_error(error, target, attributes)
+        }
+    }
+}
+
+/// Log a described error, e.g. `ErrorReport::from_anyhow(&error)`
+pub fn log_report(
+    report: ErrorReport,
+    target: Option<&str>,
+    attributes: Vec<(String, AttributeValue)>
+) {
+    service().log_report(report, target, attributes)
+}
+
 /// Log a message at DEBUG level
 pub fn debug(
     message: &str,
@@ -0,0 +1,211 @@
+//! Bridge from the `log` crate into the telemetry service.
+//!
+//! Records logged through `log` macros, typically by dependencies, are converted to
+//! a [`LogContext`] and sent through the same logger as the rest of the application.
+
+use std::cell::Cell;
+
+use ::log::kv::{self, Key, Value, VisitSource, VisitValue};
+use ::log::{Level, LevelFilter, Log, Metadata, Record};
+use tracing_subscriber::filter::{LevelFilter as TracingLevelFilter, Targets};
+
+use super::service;
+use crate::domain::telemetry::{AttributeValue, LogContext, LogLevel, TelemetryError};
+
+thread_local! {
+    // Set while a record is being forwarded, so that anything the logger itself logs
+    // through `log` is not bridged back into it
+    static FORWARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A `log::Log` implementation forwarding records to the global telemetry service
+#[derive(Debug, Clone)]
+pub struct LogBridge {
+    filter: Targets,
+    max_level: LevelFilter,
+}
+
+impl LogBridge {
+    /// Forward the records enabled by the given `EnvFilter` directives, e.g. `info,hyper=off`.
+    ///
+    /// Directives filtering on spans or fields don't apply to `log` records and are ignored.
+    pub fn new(directives: &str) -> Self {
+        let mut filter = Targets::new();
+        let mut max_level = TracingLevelFilter::OFF;
+
+        for directive in directives.split(',').map(str::trim) {
+            if directive.is_empty() || directive.contains(['[', '{']) {
+                continue;
+            }
+
+            let parsed = match directive.split_once('=') {
+                Some((target, level)) => level.parse().ok().map(|level| (Some(target), level)),
+                None => match directive.parse() {
+                    Ok(level) => Some((None, level)),
+                    // A bare target enables everything it logs
+                    Err(_) => Some((Some(directive), TracingLevelFilter::TRACE)),
+                },
+            };
+            let Some((target, level)) = parsed else {
+                continue;
+            };
+
+            max_level = max_level.max(level);
+            filter = match target {
+                Some(target) => filter.with_target(target, level),
+                None => filter.with_default(level),
+            };
+        }
+
+        Self {
+            filter,
+            max_level: to_log_level_filter(max_level),
+        }
+    }
+
+    /// The most verbose level any target is enabled at
+    pub fn max_level(&self) -> LevelFilter {
+        self.max_level
+    }
+}
+
+impl Log for LogBridge {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter
+            .would_enable(metadata.target(), &to_tracing_level(metadata.level()))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) || FORWARDING.with(Cell::get) {
+            return;
+        }
+
+        FORWARDING.with(|forwarding| forwarding.set(true));
+        super::log(to_log_context(record));
+        FORWARDING.with(|forwarding| forwarding.set(false));
+    }
+
+    fn flush(&self) {}
+}
+
+/// Route the `log` crate into the global telemetry service.
+///
+/// Done by [`init`](super::init), unless a logger for the `log` crate was installed
+/// first. Records are filtered with the same directives as the `EnvFilter` the service
+/// was initialized with.
+pub fn init_log_bridge() -> Result<(), TelemetryError> {
+    if super::TELEMETRY_SERVICE.get().is_none() {
+        return Err(TelemetryError::NotInitialized);
+    }
+
+    let bridge = LogBridge::new(service().log_filter());
+    let max_level = bridge.max_level();
+    ::log::set_boxed_logger(Box::new(bridge)).map_err(|e| {
+        TelemetryError::config_with_source("A logger for the log crate is already installed", e)
+    })?;
+    ::log::set_max_level(max_level);
+
+    Ok(())
+}
+
+/// Convert a `log` record, its source location and key-values included
+pub fn to_log_context(record: &Record) -> LogContext {
+    let mut context = LogContext::new(record.args().to_string(), to_log_level(record.level()))
+        .with_target(record.target());
+
+    if let Some(module_path) = record.module_path() {
+        context = context.with_attribute("code.namespace", module_path.into());
+    }
+    if let Some(file) = record.file() {
+        context = context.with_attribute("code.filepath", file.into());
+    }
+    if let Some(line) = record.line() {
+        context = context.with_attribute("code.lineno", line.into());
+    }
+
+    let mut attributes = Attributes(Vec::new());
+    // Visiting only fails if the visitor does, which ours never does
+    let _ = record.key_values().visit(&mut attributes);
+    context.with_attributes(attributes.0)
+}
+
+// Collects the key-values of a record
+struct Attributes(Vec<(String, AttributeValue)>);
+
+impl<'kvs> VisitSource<'kvs> for Attributes {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        let mut attribute = Attribute(None);
+        value.visit(&mut attribute)?;
+        let value = attribute
+            .0
+            .unwrap_or_else(|| AttributeValue::String(value.to_string()));
+        self.0.push((key.as_str().to_string(), value));
+        Ok(())
+    }
+}
+
+// Keeps the type of primitive values, anything else is formatted
+struct Attribute(Option<AttributeValue>);
+
+impl<'v> VisitValue<'v> for Attribute {
+    fn visit_any(&mut self, value: Value) -> Result<(), kv::Error> {
+        self.0 = Some(AttributeValue::String(value.to_string()));
+        Ok(())
+    }
+
+    fn visit_bool(&mut self, value: bool) -> Result<(), kv::Error> {
+        self.0 = Some(AttributeValue::Bool(value));
+        Ok(())
+    }
+
+    fn visit_i64(&mut self, value: i64) -> Result<(), kv::Error> {
+        self.0 = Some(AttributeValue::Int(value));
+        Ok(())
+    }
+
+    fn visit_u64(&mut self, value: u64) -> Result<(), kv::Error> {
+        self.0 = Some(AttributeValue::from(value));
+        Ok(())
+    }
+
+    fn visit_f64(&mut self, value: f64) -> Result<(), kv::Error> {
+        self.0 = Some(AttributeValue::Float(value));
+        Ok(())
+    }
+
+    fn visit_str(&mut self, value: &str) -> Result<(), kv::Error> {
+        self.0 = Some(AttributeValue::String(value.to_string()));
+        Ok(())
+    }
+}
+
+fn to_log_level(level: Level) -> LogLevel {
+    match level {
+        Level::Error => LogLevel::Error,
+        Level::Warn => LogLevel::Warn,
+        Level::Info => LogLevel::Info,
+        Level::Debug => LogLevel::Debug,
+        Level::Trace => LogLevel::Trace,
+    }
+}
+
+fn to_tracing_level(level: Level) -> tracing::Level {
+    match level {
+        Level::Error => tracing::Level::ERROR,
+        Level::Warn => tracing::Level::WARN,
+        Level::Info => tracing::Level::INFO,
+        Level::Debug => tracing::Level::DEBUG,
+        Level::Trace => tracing::Level::TRACE,
+    }
+}
+
+fn to_log_level_filter(level: TracingLevelFilter) -> LevelFilter {
+    match level.into_level() {
+        None => LevelFilter::Off,
+        Some(tracing::Level::ERROR) => LevelFilter::Error,
+        Some(tracing::Level::WARN) => LevelFilter::Warn,
+        Some(tracing::Level::INFO) => LevelFilter::Info,
+        Some(tracing::Level::DEBUG) => LevelFilter::Debug,
+        Some(_) => LevelFilter::Trace,
+    }
+}
@@ -3,18 +3,26 @@
 //! This module provides a simple interface to the telemetry service
 //! with initialization, shutdown, and global service management.
 
+pub mod baggage;
 mod log;
+mod log_bridge;
 mod metrics;
+pub mod scope;
 mod trace;
 
 use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
-use crate::domain::telemetry::TelemetryError;
+use tokio::task::JoinHandle;
+
+use crate::domain::health::HealthSnapshot;
+use crate::domain::telemetry::{FlushReport, TelemetryError};
 use crate::services::telemetry::TelemetryService;
 pub use crate::domain::metrics::*;
 
 // Re-export all public functions from sub-modules
 pub use log::*;
+pub use log_bridge::*;
 pub use metrics::*;
 pub use trace::*;
 use tracing_subscriber::EnvFilter;
@@ -24,16 +32,23 @@ static TELEMETRY_SERVICE: OnceLock<Arc<TelemetryService>> = OnceLock::new();
 
 /// Initialize the global telemetry service.
 /// This must be called before any other telemetry functions.
+///
+/// Records of the `log` crate are routed to the service too, see [`init_log_bridge`].
 pub async fn init(service: TelemetryService, filter: Option<EnvFilter>) -> Result<(), TelemetryError> {
     let service_arc = Arc::new(service);
 
     if TELEMETRY_SERVICE.set(service_arc.clone()).is_err() {
-        return Err(TelemetryError::TracerInitError(
-            "Telemetry service already initialized".to_string(),
-        ));
+        return Err(TelemetryError::AlreadyInitialized);
+    }
+
+    service_arc.init(filter).await?;
+
+    // The application may have installed its own logger for the `log` crate
+    if let Err(e) = init_log_bridge() {
+        tracing::warn!("Records of the log crate are not captured: {}", e);
     }
 
-    service_arc.init(filter).await
+    Ok(())
 }
 
 /// Initialize a DataDog-based telemetry service.
@@ -44,6 +59,25 @@ pub async fn init_datadog(service_name: String, filter: Option<EnvFilter>) -> Re
     init(service, filter).await
 }
 
+/// Flush all pending telemetry of the global service without shutting it down.
+/// Each signal is given at most `timeout` to be exported.
+pub async fn force_flush(timeout: Duration) -> Result<FlushReport, TelemetryError> {
+    if let Some(service) = TELEMETRY_SERVICE.get() {
+        Ok(service.force_flush(timeout).await)
+    } else {
+        Err(TelemetryError::NotInitialized)
+    }
+}
+
+/// Get a snapshot of the export health of the global service.
+pub fn health() -> Result<HealthSnapshot, TelemetryError> {
+    if let Some(service) = TELEMETRY_SERVICE.get() {
+        Ok(service.health())
+    } else {
+        Err(TelemetryError::NotInitialized)
+    }
+}
+
 /// Shutdown the global telemetry service.
 pub async fn shutdown() -> Result<(), TelemetryError> {
     if let Some(service) = TELEMETRY_SERVICE.get() {
@@ -53,6 +87,67 @@ pub async fn shutdown() -> Result<(), TelemetryError> {
     }
 }
 
+/// Shutdown the global telemetry service, giving it at most `timeout` to flush and stop.
+pub async fn shutdown_with_timeout(timeout: Duration) -> Result<(), TelemetryError> {
+    if let Some(service) = TELEMETRY_SERVICE.get() {
+        service.shutdown_with_timeout(timeout).await
+    } else {
+        Ok(())
+    }
+}
+
+/// Install SIGTERM and SIGINT handlers that shut the global telemetry service down.
+///
+/// The returned task completes once a signal was received and telemetry was shut down
+/// with the given timeout. Await it before returning from `main` so that the runtime
+/// does not exit while telemetry is still being exported.
+///
+/// ```no_run
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let shutdown = otel_tracing::telemetry::shutdown_on_signal(std::time::Duration::from_secs(5));
+/// // ... run the application ...
+/// shutdown.await??;
+/// # Ok(())
+/// # }
+/// ```
+pub fn shutdown_on_signal(timeout: Duration) -> JoinHandle<Result<(), TelemetryError>> {
+    tokio::spawn(async move {
+        wait_for_signal().await;
+        shutdown_with_timeout(timeout).await
+    })
+}
+
+// Wait for either SIGINT (Ctrl+C) or, on unix, SIGTERM
+async fn wait_for_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::warn!("Failed to listen for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
 /// Get a reference to the global telemetry service.
 pub(crate) fn service() -> &'static TelemetryService {
     TELEMETRY_SERVICE
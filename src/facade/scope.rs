@@ -0,0 +1,62 @@
+//! Attributes set once for a scope, e.g. a request's id and tenant, and added to every
+//! span, log record and metric point emitted within it instead of to each call.
+//!
+//! Scopes nest, with inner attributes replacing outer ones of the same key, and are kept
+//! across `spawn_with_context`. Attributes passed to a call win over scoped ones. Select
+//! the signals they are added to with `TelemetryServiceBuilder::with_scoped_signals`.
+//!
+//! ```no_run
+//! # async fn handle() {}
+//! # async fn run() {
+//! use otel_tracing::telemetry::scope;
+//!
+//! scope::with_async_attributes([("request_id", "42"), ("tenant", "acme")], async {
+//!     handle().await
+//! })
+//! .await;
+//! # }
+//! ```
+
+use opentelemetry::context::FutureExt;
+use opentelemetry::Context;
+
+use crate::domain::scope::{scoped_attributes, scoped_context};
+use crate::domain::telemetry::AttributeValue;
+
+/// Execute a function within a scope adding these attributes.
+pub fn with_attributes<I, K, V, F, R>(attributes: I, f: F) -> R
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<AttributeValue>,
+    F: FnOnce() -> R,
+{
+    let _guard = scoped_context(attributes).attach();
+    f()
+}
+
+/// Execute a future within a scope adding these attributes.
+pub async fn with_async_attributes<I, K, V, F>(attributes: I, fut: F) -> F::Output
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<AttributeValue>,
+    F: std::future::Future,
+{
+    fut.with_context(scoped_context(attributes)).await
+}
+
+/// The current context with a scope adding these attributes, e.g. to attach in a thread.
+pub fn context_with<I, K, V>(attributes: I) -> Context
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<AttributeValue>,
+{
+    scoped_context(attributes)
+}
+
+/// The attributes of the current scope, including those of the scopes it is nested in.
+pub fn attributes() -> Vec<(String, AttributeValue)> {
+    scoped_attributes()
+}
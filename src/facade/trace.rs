@@ -8,6 +8,40 @@ pub fn create_span(context: SpanContext) -> Box<dyn Span> {
     service().create_span(context)
 }
 
+/// A tower layer tracing and measuring the requests of an HTTP server.
+#[cfg(feature = "tower")]
+pub fn http_server_layer() -> crate::adapters::http::HttpServerLayer {
+    service().http_server_layer()
+}
+
+/// A tower layer tracing and measuring the calls handled by a tonic gRPC server.
+#[cfg(feature = "grpc")]
+pub fn grpc_server_layer() -> crate::adapters::grpc::GrpcServerLayer {
+    service().grpc_server_layer()
+}
+
+/// A tower layer tracing and measuring the calls sent through a tonic gRPC channel.
+#[cfg(feature = "grpc")]
+pub fn grpc_client_layer() -> crate::adapters::grpc::GrpcClientLayer {
+    service().grpc_client_layer()
+}
+
+/// Wrap a reqwest client so that the requests sent through it are traced and measured.
+pub fn http_client(client: reqwest::Client) -> crate::adapters::http::TracedClient {
+    service().http_client(client)
+}
+
+/// Create the spans of message producers and consumers, propagating their context
+/// through message headers.
+pub fn messaging_tracer() -> crate::adapters::messaging::MessagingTracer {
+    service().messaging_tracer()
+}
+
+/// Create the spans of database operations and record their metrics.
+pub fn db_tracer() -> crate::adapters::db::DbTracer {
+    service().db_tracer()
+}
+
 /// Execute a function within a span scope, automatically ending the span when done.
 /// Properly maintains trace context for nested spans.
 pub fn with_span<F, R>(name: &str, attributes: Vec<(String, AttributeValue)>, f: F) -> R
@@ -15,10 +49,7 @@ where
     F: FnOnce() -> R,
 {
     // Create a span in the current context
-    let span = create_span(SpanContext {
-        name: name.to_string(),
-        attributes,
-    });
+    let span = create_span(SpanContext::new(name.to_string()).with_attributes(attributes));
     
     // Get the context containing this span
     let cx = span.get_context();
@@ -44,10 +75,7 @@ where
     F: std::future::Future<Output = R>,
 {
     // Create a span in the current context
-    let span = create_span(SpanContext {
-        name: name.to_string(),
-        attributes,
-    });
+    let span = create_span(SpanContext::new(name.to_string()).with_attributes(attributes));
     
     // Get the context containing this span
     let cx = span.get_context();
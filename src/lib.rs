@@ -4,10 +4,16 @@ pub mod domain;
 pub mod facade;
 pub mod ports;
 mod services;
+pub use domain::error_report::ErrorReport;
+pub use domain::health::{HealthSnapshot, SignalHealth};
+pub use domain::redaction::{PiiDetector, RedactionAction, Redactor};
+pub use domain::resource::{Detector, ResourceBuilder};
 pub use domain::telemetry::{
-    AttributeValue, LogContext, LogLevel, MetricContext, SpanContext, TelemetryError,
+    AttributeValue, FlushReport, LogContext, LogLevel, MetricContext, ShutdownReport, Signal,
+    SignalFailure, SpanContext, SpanKind, TelemetryError,
 };
 pub use facade as telemetry;
+pub use services::telemetry::{TelemetryService, TelemetryServiceBuilder};
 use opentelemetry::{context::FutureExt, Context};
 use std::collections::HashMap;
 
@@ -43,18 +49,13 @@ where
 #[macro_export]
 macro_rules! span {
     ($name:expr) => {
-        $crate::telemetry::create_span($crate::SpanContext {
-            name: $name.to_string(),
-            attributes: vec![],
-        })
+        $crate::telemetry::create_span($crate::SpanContext::new($name.to_string()))
     };
     ($name:expr, $($key:expr => $value:expr),+ $(,)?) => {
-        $crate::telemetry::create_span($crate::SpanContext {
-            name: $name.to_string(),
-            attributes: vec![
+        $crate::telemetry::create_span($crate::SpanContext::new($name.to_string())
+            .with_attributes(vec![
                 $(($key.to_string(), $value.into())),+
-            ],
-        })
+            ]))
     };
 }
 
@@ -446,22 +447,56 @@ macro_rules! warn_log {
     };
 }
 
-/// Log a message at ERROR level.
+/// Log a message or an error at ERROR level.
 ///
 /// # Examples
 ///
 /// ```
 /// // Simple error message
-/// error!("Failed to process request");
+/// error_log!("Failed to process request");
 ///
 /// // With target
-/// error!("Failed to process request", target: "app::process_request");
+/// error_log!("Failed to process request", target: "app::process_request");
 ///
 /// // With attributes
-/// error!("Failed to process request", "user_id" => "12345", "error_code" => "500");
+/// error_log!("Failed to process request", "user_id" => "12345", "error_code" => "500");
+///
+/// // An error, with its chain of sources, which may come from another task
+/// error_log!(error: err, target: "app::process_request", "user_id" => "12345");
 /// ```
 #[macro_export]
 macro_rules! error_log {
+    // The error forms come first, a message would match them as well
+    (error: $error:expr) => {
+        $crate::error_log!(@error $error, None, vec![])
+    };
+    (error: $error:expr, target: $target:expr) => {
+        $crate::error_log!(@error $error, Some($target), vec![])
+    };
+    (error: $error:expr, $($key:expr => $value:expr),+ $(,)?) => {
+        $crate::error_log!(
+            @error $error,
+            None,
+            vec![$(($key.to_string(), $value.into())),+]
+        )
+    };
+    (error: $error:expr, target: $target:expr, $($key:expr => $value:expr),+ $(,)?) => {
+        $crate::error_log!(
+            @error $error,
+            Some($target),
+            vec![$(($key.to_string(), $value.into())),+]
+        )
+    };
+    // Errors of a known type go through `report_error`, to keep their type as `error.kind`
+    (@error $error:expr, $target:expr, $attributes:expr) => {
+        match $error {
+            error => {
+                #[allow(unused_imports)]
+                use $crate::telemetry::error_dispatch::{ErasedError as _, TypedError as _};
+                (&error).error_dispatch().log(error, $target, $attributes)
+            }
+        }
+    };
     ($message:expr) => {
         $crate::telemetry::log($crate::LogContext::new($message.to_string(), $crate::LogLevel::Error))
     };
@@ -482,13 +517,6 @@ macro_rules! error_log {
                 $(($key.to_string(), $value.into())),+
             ]))
     };
-    ($error:expr, target: $target:expr, $($key:expr => $value:expr),+ $(,)?) => {
-        $crate::telemetry::log_error(
-            $error,
-            Some($target),
-            vec![$(($key.to_string(), $value.into())),+]
-        )
-    };
 }
 
 
@@ -497,7 +525,7 @@ macro_rules! error_log {
 mod tests {
     use std::time::Duration;
 
-    use crate::facade::{init_datadog, shutdown};
+    use crate::facade::{force_flush, init_datadog, shutdown};
 
     use super::*;
     use tracing::Level;
@@ -508,20 +536,19 @@ mod tests {
     // Initialize telemetry
     init_datadog("test_service".to_string(), None).await.unwrap();
 
-    let err: Box<dyn std::error::Error> = "An error occurred".into();
+    let err: Box<dyn std::error::Error + Send + Sync> = "An error occurred".into();
 
 
-    error_log!(err, target: "test",
+    error_log!(error: err, target: "test",
         "operation" => "process_data",
         "user_id" => 42,
         "error_code" => 500,
         );
     
+     // Make sure everything is exported before shutting down
+     force_flush(Duration::from_secs(5)).await.unwrap();
 
      shutdown().await.unwrap();
- 
-     // Allow time for traces to be sent
-     tokio::time::sleep(Duration::from_secs(2)).await;
     }
  
 
@@ -1,20 +1,46 @@
 use async_trait::async_trait;
+use opentelemetry_sdk::Resource;
 use tracing_subscriber::EnvFilter;
 
-use crate::{domain::telemetry::{LogContext, TelemetryError}, AttributeValue};
+use crate::{domain::error_report::ErrorReport, domain::health::SignalHealth, domain::telemetry::{LogContext, TelemetryError}, AttributeValue, LogLevel};
 
 #[async_trait]
 pub trait LoggerPort: Send + Sync {
-    async fn init(&self, filter: Option<EnvFilter>) -> Result<(), TelemetryError>;
+    async fn init(&self, resource: &Resource, filter: Option<EnvFilter>) -> Result<(), TelemetryError>;
     
     fn log(&self, context: LogContext);
 
     fn log_error(
         &self,
-        error: Box<dyn std::error::Error>,
+        error: &(dyn std::error::Error + 'static),
         target: Option<&str>,
         attributes: Vec<(String, AttributeValue)>,
     );
+
+    /// Log a described error, by default as an error record with its `error.*` attributes
+    fn log_report(
+        &self,
+        report: ErrorReport,
+        target: Option<&str>,
+        mut attributes: Vec<(String, AttributeValue)>,
+    ) {
+        attributes.extend(report.attributes());
+        let mut context = LogContext::new(report.message, LogLevel::Error).with_attributes(attributes);
+        if let Some(target) = target {
+            context = context.with_target(target);
+        }
+        self.log(context);
+    }
+
+    /// Export all buffered log records, without shutting down
+    async fn force_flush(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    /// Export health of this adapter, if it keeps track of it
+    fn health(&self) -> Option<SignalHealth> {
+        None
+    }
     
     async fn shutdown(&self) -> Result<(), TelemetryError>;
 }
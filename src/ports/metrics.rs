@@ -1,16 +1,32 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
 use async_trait::async_trait;
+use opentelemetry_sdk::Resource;
 
+use crate::domain::health::SignalHealth;
+use crate::domain::metrics::{MetricUnit, TimeUnit};
 use crate::domain::telemetry::{MetricContext, AttributeValue, TelemetryError};
 
 #[async_trait]
 pub trait MetricsPort: Send + Sync {
-    async fn init(&self) -> Result<(), TelemetryError>;
+    async fn init(&self, resource: &Resource) -> Result<(), TelemetryError>;
     
     fn create_counter(&self, context: MetricContext) -> Box<dyn Counter>;
     
     fn create_gauge(&self, context: MetricContext) -> Box<dyn Gauge>;
     
     fn create_histogram(&self, context: MetricContext) -> Box<dyn Histogram>;
+
+    /// Collect and export all pending metric points, without shutting down
+    async fn force_flush(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    /// Export health of this adapter, if it keeps track of it
+    fn health(&self) -> Option<SignalHealth> {
+        None
+    }
     
     async fn shutdown(&self) -> Result<(), TelemetryError>;
 }
@@ -25,4 +41,132 @@ pub trait Gauge: Send + Sync {
 
 pub trait Histogram: Send + Sync {
     fn record(&self, value: f64, attributes: Vec<(String, AttributeValue)>);
+
+    /// The unit the histogram was created with, if the adapter keeps it
+    fn unit(&self) -> Option<MetricUnit> {
+        None
+    }
+}
+
+/// Measures how long something takes and records it in a histogram once stopped or
+/// dropped, in the histogram's time unit or in seconds if it has none.
+///
+/// ```no_run
+/// # use otel_tracing::ports::metrics::{Histogram, HistogramExt};
+/// # fn charge() -> Result<(), std::io::Error> { Ok(()) }
+/// # fn run(histogram: &dyn Histogram) {
+/// let mut timer = histogram.start_timer();
+/// let outcome = if charge().is_ok() { "ok" } else { "error" };
+/// timer.set_attribute("outcome", outcome);
+/// # }
+/// ```
+#[must_use = "the duration is recorded when the timer is dropped"]
+pub struct Timer<'a> {
+    histogram: &'a dyn Histogram,
+    unit: TimeUnit,
+    started: Instant,
+    attributes: Vec<(String, AttributeValue)>,
+    stopped: bool,
+}
+
+impl<'a> Timer<'a> {
+    pub fn new(histogram: &'a dyn Histogram) -> Self {
+        let unit = match histogram.unit() {
+            Some(MetricUnit::Time(unit)) => unit,
+            _ => TimeUnit::Second,
+        };
+        Self {
+            histogram,
+            unit,
+            started: Instant::now(),
+            attributes: Vec::new(),
+            stopped: false,
+        }
+    }
+
+    /// Record the duration with these attributes
+    pub fn with_attributes(mut self, attributes: Vec<(String, AttributeValue)>) -> Self {
+        self.attributes.extend(attributes);
+        self
+    }
+
+    /// Set an attribute to record the duration with, e.g. the outcome once known
+    pub fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<AttributeValue>) {
+        let (key, value) = (key.into(), value.into());
+        match self.attributes.iter_mut().find(|(existing, _)| *existing == key) {
+            Some(existing) => existing.1 = value,
+            None => self.attributes.push((key, value)),
+        }
+    }
+
+    /// Time since the timer was started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Record the duration now rather than when the timer is dropped, and return it
+    pub fn stop(mut self) -> Duration {
+        self.record()
+    }
+
+    /// Drop the timer without recording anything, e.g. when the operation was cancelled
+    pub fn discard(mut self) {
+        self.stopped = true;
+    }
+
+    fn record(&mut self) -> Duration {
+        let elapsed = self.started.elapsed();
+        self.stopped = true;
+        self.histogram.record(
+            self.unit.convert(elapsed),
+            std::mem::take(&mut self.attributes),
+        );
+        elapsed
+    }
+}
+
+impl Drop for Timer<'_> {
+    fn drop(&mut self) {
+        if !self.stopped {
+            self.record();
+        }
+    }
+}
+
+/// Timing helpers available on every histogram
+pub trait HistogramExt: Histogram {
+    /// Start a timer recording in this histogram once stopped or dropped
+    fn start_timer(&self) -> Timer<'_>;
+
+    /// Execute a function and record how long it took
+    fn time<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let _timer = self.start_timer();
+        f()
+    }
+
+    /// Execute a future and record how long it took, from its first poll
+    fn time_async<F>(&self, fut: F) -> impl Future<Output = F::Output>
+    where
+        F: Future,
+    {
+        async move {
+            let _timer = self.start_timer();
+            fut.await
+        }
+    }
+}
+
+impl<H: Histogram> HistogramExt for H {
+    fn start_timer(&self) -> Timer<'_> {
+        Timer::new(self)
+    }
+}
+
+impl HistogramExt for dyn Histogram + '_ {
+    fn start_timer(&self) -> Timer<'_> {
+        Timer::new(self)
+    }
 }
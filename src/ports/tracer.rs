@@ -1,14 +1,27 @@
 use async_trait::async_trait;
 use opentelemetry::Context;
+use opentelemetry_sdk::Resource;
 
+use crate::domain::error_report::ErrorReport;
+use crate::domain::health::SignalHealth;
 use crate::domain::telemetry::{AttributeValue, SpanContext, TelemetryError};
 
 #[async_trait]
 pub trait TracerPort: Send + Sync {
-    async fn init(&self) -> Result<(), TelemetryError>;
+    async fn init(&self, resource: &Resource) -> Result<(), TelemetryError>;
 
     fn create_span(&self, context: SpanContext) -> Box<dyn Span>;
 
+    /// Export all spans that have ended but not yet been exported, without shutting down
+    async fn force_flush(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    /// Export health of this adapter, if it keeps track of it
+    fn health(&self) -> Option<SignalHealth> {
+        None
+    }
+
     async fn shutdown(&self) -> Result<(), TelemetryError>;
 }
 
@@ -19,6 +32,22 @@ pub trait Span: Send + Sync {
 
     fn end(&self);
 
+    /// Record an error as `error.*` attributes and an `exception` event
+    fn record_error(&self, report: &ErrorReport) {
+        for (key, value) in report.attributes() {
+            self.set_attribute(key, value);
+        }
+        self.add_event("exception", report.exception_attributes());
+    }
+
+    /// Mark the span as failed without an error to record, e.g. for a 5xx response
+    fn set_error_status(&self, _description: &str) {}
+
+    /// Record a type-erased error, see [`Span::record_error`]
+    fn record_exception(&self, error: &(dyn std::error::Error + 'static)) {
+        self.record_error(&ErrorReport::from_dyn(error));
+    }
+
     /// Get the OpenTelemetry context containing this span
     /// This is used for context propagation across async boundaries
     fn get_context(&self) -> Context;
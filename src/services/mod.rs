@@ -1 +1,4 @@
-pub mod telemetry;
\ No newline at end of file
+pub mod process_metrics;
+pub mod runtime_metrics;
+pub mod self_telemetry;
+pub mod telemetry;
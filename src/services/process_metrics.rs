@@ -0,0 +1,21 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::adapters::process::ProcessMetrics;
+use crate::ports::metrics::MetricsPort;
+
+/// Periodically record the resources used by this process and the CPU time of the host
+/// as `process.*` and `system.*` metrics
+pub(crate) fn spawn_collector(interval: Duration, metrics: Arc<dyn MetricsPort>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut process = ProcessMetrics::new(metrics.as_ref());
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+            process.record();
+        }
+    })
+}
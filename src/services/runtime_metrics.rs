@@ -0,0 +1,158 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::runtime::{Handle, RuntimeMetrics};
+use tokio::task::JoinHandle;
+
+use crate::domain::metrics::{MetricUnit, TimeUnit};
+use crate::domain::telemetry::MetricContext;
+use crate::ports::metrics::{Counter, Gauge, MetricsPort};
+use crate::AttributeValue;
+
+// Prefix of the names of the runtime's metrics
+const RUNTIME_NAMESPACE: &str = "tokio";
+
+// What a worker did since the runtime started. Busy time and parks are only counted on
+// targets with 64 bit atomics, polls when built with `--cfg tokio_unstable` as well.
+#[derive(Debug, Clone, Copy, Default)]
+struct WorkerTotals {
+    busy_micros: u64,
+    parks: u64,
+    #[cfg(tokio_unstable)]
+    polls: u64,
+}
+
+impl WorkerTotals {
+    fn sample(metrics: &RuntimeMetrics, worker: usize) -> Self {
+        Self {
+            #[cfg(target_has_atomic = "64")]
+            busy_micros: metrics.worker_total_busy_duration(worker).as_micros() as u64,
+            #[cfg(not(target_has_atomic = "64"))]
+            busy_micros: 0,
+            #[cfg(target_has_atomic = "64")]
+            parks: metrics.worker_park_count(worker),
+            #[cfg(not(target_has_atomic = "64"))]
+            parks: 0,
+            #[cfg(all(tokio_unstable, target_has_atomic = "64"))]
+            polls: metrics.worker_poll_count(worker),
+            #[cfg(all(tokio_unstable, not(target_has_atomic = "64")))]
+            polls: 0,
+        }
+    }
+}
+
+// The instruments describing the scheduling of a runtime, and the totals of every
+// worker at the previous sample, recorded as the counters' increments
+struct RuntimeInstruments {
+    workers: Box<dyn Gauge>,
+    alive_tasks: Box<dyn Gauge>,
+    global_queue_depth: Box<dyn Gauge>,
+    #[cfg(tokio_unstable)]
+    local_queue_depth: Box<dyn Gauge>,
+    busy_duration: Box<dyn Counter>,
+    parks: Box<dyn Counter>,
+    #[cfg(tokio_unstable)]
+    polls: Box<dyn Counter>,
+    previous: Vec<WorkerTotals>,
+}
+
+impl RuntimeInstruments {
+    fn new(metrics: &dyn MetricsPort) -> Self {
+        let context = |name: &str, description: &str, unit: MetricUnit| {
+            MetricContext::new(format!("{}.{}", RUNTIME_NAMESPACE, name))
+                .with_description(description)
+                .with_unit(unit)
+        };
+
+        Self {
+            workers: metrics.create_gauge(context(
+                "workers",
+                "Worker threads of the runtime",
+                MetricUnit::Count,
+            )),
+            alive_tasks: metrics.create_gauge(context(
+                "tasks.alive",
+                "Tasks spawned and not completed yet",
+                MetricUnit::Count,
+            )),
+            global_queue_depth: metrics.create_gauge(context(
+                "global_queue.depth",
+                "Tasks waiting in the queue shared by all workers",
+                MetricUnit::Count,
+            )),
+            #[cfg(tokio_unstable)]
+            local_queue_depth: metrics.create_gauge(context(
+                "worker.local_queue.depth",
+                "Tasks waiting in a worker's own queue",
+                MetricUnit::Count,
+            )),
+            busy_duration: metrics.create_counter(context(
+                "worker.busy_duration",
+                "Time a worker spent polling tasks",
+                MetricUnit::Time(TimeUnit::Microsecond),
+            )),
+            parks: metrics.create_counter(context(
+                "worker.parks",
+                "Times a worker parked for lack of tasks to poll",
+                MetricUnit::Count,
+            )),
+            #[cfg(tokio_unstable)]
+            polls: metrics.create_counter(context(
+                "worker.polls",
+                "Tasks polled by a worker",
+                MetricUnit::Count,
+            )),
+            previous: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, metrics: &RuntimeMetrics) {
+        let workers = metrics.num_workers();
+        self.workers.set(workers as f64, Vec::new());
+        self.alive_tasks
+            .set(metrics.num_alive_tasks() as f64, Vec::new());
+        self.global_queue_depth
+            .set(metrics.global_queue_depth() as f64, Vec::new());
+
+        self.previous.resize(workers, WorkerTotals::default());
+        for worker in 0..workers {
+            let attributes = || vec![("worker".to_string(), AttributeValue::from(worker))];
+
+            #[cfg(tokio_unstable)]
+            self.local_queue_depth.set(
+                metrics.worker_local_queue_depth(worker) as f64,
+                attributes(),
+            );
+
+            let totals = WorkerTotals::sample(metrics, worker);
+            let previous = std::mem::replace(&mut self.previous[worker], totals);
+            let add = |counter: &dyn Counter, total: u64, previous: u64| {
+                if total > previous {
+                    counter.add(total - previous, attributes());
+                }
+            };
+            add(
+                self.busy_duration.as_ref(),
+                totals.busy_micros,
+                previous.busy_micros,
+            );
+            add(self.parks.as_ref(), totals.parks, previous.parks);
+            #[cfg(tokio_unstable)]
+            add(self.polls.as_ref(), totals.polls, previous.polls);
+        }
+    }
+}
+
+/// Periodically record the scheduling metrics of the current runtime as `tokio.*`
+/// gauges and counters, the latter with a `worker` attribute
+pub(crate) fn spawn_collector(interval: Duration, metrics: Arc<dyn MetricsPort>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut instruments = RuntimeInstruments::new(metrics.as_ref());
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+            instruments.record(&Handle::current().metrics());
+        }
+    })
+}
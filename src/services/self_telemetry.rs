@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::task::JoinHandle;
+
+use crate::domain::health::{HealthSnapshot, SignalHealth, SELF_TELEMETRY_NAMESPACE};
+use crate::domain::metrics::{MetricUnit, TimeUnit};
+use crate::domain::telemetry::{MetricContext, Signal};
+use crate::ports::logger::LoggerPort;
+use crate::ports::metrics::{Counter, Gauge, MetricsPort};
+use crate::ports::tracer::TracerPort;
+use crate::AttributeValue;
+
+// The instruments describing the export pipeline of every signal. Exports, failures and
+// drops are totals, recorded as the counters' increments since the previous tick.
+struct ExporterInstruments {
+    exported: Box<dyn Counter>,
+    failed: Box<dyn Counter>,
+    dropped: Box<dyn Counter>,
+    queue_depth: Box<dyn Gauge>,
+    last_export_age: Box<dyn Gauge>,
+    previous: HashMap<Signal, SignalHealth>,
+}
+
+impl ExporterInstruments {
+    fn new(metrics: &dyn MetricsPort) -> Self {
+        let context = |name: &str, description: &str| {
+            MetricContext::new(format!("{}.exporter.{}", SELF_TELEMETRY_NAMESPACE, name))
+                .with_description(description)
+        };
+
+        Self {
+            exported: metrics.create_counter(
+                context("exported", "Records successfully exported").with_unit(MetricUnit::Count),
+            ),
+            failed: metrics.create_counter(
+                context("failed", "Export calls that failed").with_unit(MetricUnit::Count),
+            ),
+            dropped: metrics.create_counter(
+                context("dropped", "Records lost before being exported")
+                    .with_unit(MetricUnit::Count),
+            ),
+            queue_depth: metrics.create_gauge(
+                context("queue_depth", "Records waiting to be exported")
+                    .with_unit(MetricUnit::Count),
+            ),
+            last_export_age: metrics.create_gauge(
+                context("last_export_age", "Time since the last successful export")
+                    .with_unit(MetricUnit::Time(TimeUnit::Second)),
+            ),
+            previous: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, snapshot: &HealthSnapshot) {
+        for signal in [Signal::Traces, Signal::Metrics, Signal::Logs] {
+            let Some(health) = snapshot.get(signal) else {
+                continue;
+            };
+            let attributes = || {
+                vec![(
+                    "signal".to_string(),
+                    AttributeValue::String(signal.to_string()),
+                )]
+            };
+
+            let previous = self
+                .previous
+                .insert(signal, health.clone())
+                .unwrap_or_default();
+            for (counter, total, previous) in [
+                (&self.exported, health.exported, previous.exported),
+                (&self.failed, health.failed, previous.failed),
+                (&self.dropped, health.dropped, previous.dropped),
+            ] {
+                if total > previous {
+                    counter.add(total - previous, attributes());
+                }
+            }
+            if let Some(depth) = health.queue_depth {
+                self.queue_depth.set(depth as f64, attributes());
+            }
+            if let Some(age) = health
+                .last_export
+                .and_then(|at| SystemTime::now().duration_since(at).ok())
+            {
+                self.last_export_age.set(age.as_secs_f64(), attributes());
+            }
+        }
+    }
+}
+
+/// Periodically record the health of every component as `otel_tracing.exporter.*`
+/// counters and gauges
+pub(crate) fn spawn_reporter(
+    interval: Duration,
+    tracer: Arc<dyn TracerPort>,
+    metrics: Arc<dyn MetricsPort>,
+    logger: Arc<dyn LoggerPort>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut instruments = ExporterInstruments::new(metrics.as_ref());
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+            instruments.record(&HealthSnapshot {
+                traces: tracer.health(),
+                metrics: metrics.health(),
+                logs: logger.health(),
+            });
+        }
+    })
+}
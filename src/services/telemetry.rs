@@ -1,18 +1,60 @@
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 
+use async_trait::async_trait;
+use opentelemetry::Context;
+use opentelemetry_sdk::Resource;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::warn;
 use tracing_subscriber::EnvFilter;
 
-use crate::domain::telemetry::{LogContext, MetricContext, SpanContext, TelemetryError};
+use crate::adapters::db::DbTracer;
+#[cfg(feature = "grpc")]
+use crate::adapters::grpc::{GrpcClientLayer, GrpcServerLayer};
+#[cfg(feature = "tower")]
+use crate::adapters::http::HttpServerLayer;
+use crate::adapters::http::TracedClient;
+use crate::adapters::messaging::MessagingTracer;
+use crate::domain::baggage::BaggageAttributes;
+use crate::domain::error_report::ErrorReport;
+use crate::domain::health::HealthSnapshot;
+use crate::domain::metrics::MetricUnit;
+use crate::domain::redaction::Redactor;
+use crate::domain::resource::ResourceBuilder;
+use crate::domain::scope::ContextAttributes;
+use crate::domain::telemetry::{
+    BoxError, FlushReport, LogContext, MetricContext, ShutdownReport, Signal, SignalFailure,
+    SpanContext, TelemetryError, DEFAULT_LOG_FILTER,
+};
 use crate::ports::logger::LoggerPort;
 use crate::ports::metrics::{Counter, Gauge, Histogram, MetricsPort};
 use crate::ports::tracer::{Span, TracerPort};
+use crate::services::process_metrics;
+use crate::services::runtime_metrics;
+use crate::services::self_telemetry;
 use crate::AttributeValue;
 
+/// Time given to `TelemetryService::shutdown` to flush and stop every component
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Shortest period of the background collectors, whose tokio intervals panic on zero
+const MIN_COLLECTION_INTERVAL: Duration = Duration::from_millis(1);
+
 /// TelemetryService provides a unified interface for tracing, metrics, and logging
 pub struct TelemetryService {
     tracer: Arc<dyn TracerPort>,
     metrics: Arc<dyn MetricsPort>,
     logger: Arc<dyn LoggerPort>,
+    resource: ResourceBuilder,
+    self_telemetry_interval: Option<Duration>,
+    runtime_metrics_interval: Option<Duration>,
+    process_metrics_interval: Option<Duration>,
+    background_tasks: Mutex<Vec<JoinHandle<()>>>,
+    log_filter: OnceLock<String>,
+    redactor: Option<Arc<Redactor>>,
+    context_attributes: Arc<ContextAttributes>,
 }
 
 impl TelemetryService {
@@ -26,75 +68,468 @@ impl TelemetryService {
             tracer,
             metrics,
             logger,
+            resource: ResourceBuilder::default(),
+            self_telemetry_interval: None,
+            runtime_metrics_interval: None,
+            process_metrics_interval: None,
+            background_tasks: Mutex::new(Vec::new()),
+            log_filter: OnceLock::new(),
+            redactor: None,
+            context_attributes: Arc::default(),
         }
     }
 
-    /// Initialize all telemetry components
+    /// Initialize all telemetry components, describing them with the same resource
     pub async fn init(&self, filter: Option<EnvFilter>) -> Result<(), TelemetryError> {
+        // Detectors may query metadata services with blocking I/O, keep them off the
+        // async workers
+        let resource = self.resource.clone();
+        let resource = tokio::task::spawn_blocking(move || resource.build())
+            .await
+            .map_err(|e| TelemetryError::config_with_source("Resource detection failed", e))?;
+        let directives = filter
+            .as_ref()
+            .map_or_else(|| DEFAULT_LOG_FILTER.to_string(), ToString::to_string);
+        let _ = self.log_filter.set(directives);
+
         // Initialize logger first, so we can capture logs from other initializations
-        self.logger.init(filter).await?;
-        self.tracer.init().await?;
-        self.metrics.init().await?;
+        self.logger.init(&resource, filter).await?;
+        self.tracer.init(&resource).await?;
+        self.metrics.init(&resource).await?;
+
+        if let Some(interval) = self.self_telemetry_interval {
+            self.background_tasks
+                .lock()
+                .unwrap()
+                .push(self_telemetry::spawn_reporter(
+                    interval,
+                    self.tracer.clone(),
+                    self.metrics.clone(),
+                    self.logger.clone(),
+                ));
+        }
+        if let Some(interval) = self.runtime_metrics_interval {
+            self.background_tasks
+                .lock()
+                .unwrap()
+                .push(runtime_metrics::spawn_collector(
+                    interval,
+                    self.metrics.clone(),
+                ));
+        }
+        if let Some(interval) = self.process_metrics_interval {
+            self.background_tasks
+                .lock()
+                .unwrap()
+                .push(process_metrics::spawn_collector(
+                    interval,
+                    self.metrics.clone(),
+                ));
+        }
 
         Ok(())
     }
 
-    /// Shutdown all telemetry components
+    /// Directives of the log filter the service was initialized with
+    pub fn log_filter(&self) -> &str {
+        self.log_filter
+            .get()
+            .map_or(DEFAULT_LOG_FILTER, String::as_str)
+    }
+
+    /// Get a snapshot of how well every signal is being exported
+    pub fn health(&self) -> HealthSnapshot {
+        HealthSnapshot {
+            traces: self.tracer.health(),
+            metrics: self.metrics.health(),
+            logs: self.logger.health(),
+        }
+    }
+
+    /// Flush all telemetry components without shutting them down.
+    ///
+    /// The signals are flushed concurrently, and each one is given at most `timeout`
+    /// to complete. The returned report holds one result per signal.
+    pub async fn force_flush(&self, timeout: Duration) -> FlushReport {
+        let (traces, metrics, logs) = tokio::join!(
+            flush_with_timeout(Signal::Traces, timeout, self.tracer.force_flush()),
+            flush_with_timeout(Signal::Metrics, timeout, self.metrics.force_flush()),
+            flush_with_timeout(Signal::Logs, timeout, self.logger.force_flush()),
+        );
+
+        FlushReport {
+            traces,
+            metrics,
+            logs,
+        }
+    }
+
+    /// Shutdown all telemetry components with the default timeout
     pub async fn shutdown(&self) -> Result<(), TelemetryError> {
-        let mut errors = Vec::new();
+        self.shutdown_with_timeout(DEFAULT_SHUTDOWN_TIMEOUT).await
+    }
+
+    /// Shutdown all telemetry components, bounding the whole sequence by `timeout`.
+    ///
+    /// All signals are flushed first while every component is still running. The
+    /// components are then shut down in order: tracer, metrics and finally the
+    /// logger, so that anything logged while shutting down the others is still
+    /// exported. Signals that fail or do not finish before the deadline are
+    /// reported in [`TelemetryError::Shutdown`].
+    pub async fn shutdown_with_timeout(&self, timeout: Duration) -> Result<(), TelemetryError> {
+        let deadline = Instant::now() + timeout;
 
-        if let Err(e) = self.tracer.shutdown().await {
-            errors.push(format!("Tracer shutdown error: {}", e));
+        // Background tasks record into the components, stop them first
+        for task in self.background_tasks.lock().unwrap().drain(..) {
+            task.abort();
         }
 
-        if let Err(e) = self.metrics.shutdown().await {
-            errors.push(format!("Metrics shutdown error: {}", e));
+        // Flush everything before any component goes away
+        let (traces, metrics, logs) = tokio::join!(
+            tokio::time::timeout_at(deadline, self.tracer.force_flush()),
+            tokio::time::timeout_at(deadline, self.metrics.force_flush()),
+            tokio::time::timeout_at(deadline, self.logger.force_flush()),
+        );
+        for (signal, result) in [
+            (Signal::Traces, traces),
+            (Signal::Metrics, metrics),
+            (Signal::Logs, logs),
+        ] {
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => warn!("Failed to flush {} before shutdown: {}", signal, e),
+                Err(_) => warn!("Flushing {} before shutdown timed out", signal),
+            }
         }
 
-        if let Err(e) = self.logger.shutdown().await {
-            errors.push(format!("Logger shutdown error: {}", e));
+        let mut report = ShutdownReport::default();
+
+        if let Some(failure) = shutdown_before(deadline, self.tracer.shutdown()).await {
+            report.set(Signal::Traces, failure);
+        }
+
+        if let Some(failure) = shutdown_before(deadline, self.metrics.shutdown()).await {
+            report.set(Signal::Metrics, failure);
         }
 
-        if errors.is_empty() {
-            Ok(())
-        } else {
-            Err(TelemetryError::ShutdownError(errors.join("; ")))
+        if let Some(failure) = shutdown_before(deadline, self.logger.shutdown()).await {
+            report.set(Signal::Logs, failure);
         }
+
+        report.into_result()
     }
 
     /// Create a new span
-    pub fn create_span(&self, context: SpanContext) -> Box<dyn Span> {
-        self.tracer.create_span(context)
+    pub fn create_span(&self, mut context: SpanContext) -> Box<dyn Span> {
+        self.context_attributes
+            .extend(Signal::Traces, &mut context.attributes);
+        match &self.redactor {
+            Some(redactor) if redactor.applies_to(Signal::Traces) => Box::new(RedactedSpan {
+                span: self.tracer.create_span(redactor.redact_span(context)),
+                redactor: redactor.clone(),
+            }),
+            _ => self.tracer.create_span(context),
+        }
+    }
+
+    /// A tower layer tracing and measuring the requests of an HTTP server
+    #[cfg(feature = "tower")]
+    pub fn http_server_layer(&self) -> HttpServerLayer {
+        HttpServerLayer::new(self.span_tracer(), self.instruments().as_ref())
+    }
+
+    /// A tower layer tracing and measuring the calls handled by a tonic gRPC server
+    #[cfg(feature = "grpc")]
+    pub fn grpc_server_layer(&self) -> GrpcServerLayer {
+        GrpcServerLayer::new(self.span_tracer(), self.instruments().as_ref())
+    }
+
+    /// A tower layer tracing and measuring the calls sent through a tonic gRPC channel
+    #[cfg(feature = "grpc")]
+    pub fn grpc_client_layer(&self) -> GrpcClientLayer {
+        GrpcClientLayer::new(self.span_tracer(), self.instruments().as_ref())
+    }
+
+    /// Wrap a reqwest client so that the requests sent through it are traced and measured
+    pub fn http_client(&self, client: reqwest::Client) -> TracedClient {
+        TracedClient::new(client, self.span_tracer(), self.instruments().as_ref())
+    }
+
+    /// Create the spans of message producers and consumers, propagating their context
+    /// through message headers
+    pub fn messaging_tracer(&self) -> MessagingTracer {
+        MessagingTracer::new(self.span_tracer())
+    }
+
+    /// Create the spans of database operations and record their metrics
+    pub fn db_tracer(&self) -> DbTracer {
+        DbTracer::new(self.span_tracer(), self.instruments().as_ref())
+    }
+
+    // The tracer for spans created outside of `create_span`, applying the same redaction
+    // and adding the same attributes of the current context
+    fn span_tracer(&self) -> Arc<dyn TracerPort> {
+        let tracer: Arc<dyn TracerPort> = match &self.redactor {
+            Some(redactor) if redactor.applies_to(Signal::Traces) => Arc::new(RedactedTracer {
+                tracer: self.tracer.clone(),
+                redactor: redactor.clone(),
+            }),
+            _ => self.tracer.clone(),
+        };
+        Arc::new(WithContextAttributes {
+            inner: tracer,
+            attributes: self.context_attributes.clone(),
+        })
+    }
+
+    // The metrics for instruments created outside of the `create_*` methods, adding the
+    // same attributes of the current context
+    fn instruments(&self) -> Arc<dyn MetricsPort> {
+        Arc::new(WithContextAttributes {
+            inner: self.metrics.clone(),
+            attributes: self.context_attributes.clone(),
+        })
     }
 
     /// Create a new counter
     pub fn create_counter(&self, context: MetricContext) -> Box<dyn Counter> {
-        self.metrics.create_counter(context)
+        self.instruments().create_counter(context)
     }
 
     /// Create a new gauge
     pub fn create_gauge(&self, context: MetricContext) -> Box<dyn Gauge> {
-        self.metrics.create_gauge(context)
+        self.instruments().create_gauge(context)
     }
 
     /// Create a new histogram
     pub fn create_histogram(&self, context: MetricContext) -> Box<dyn Histogram> {
-        self.metrics.create_histogram(context)
+        self.instruments().create_histogram(context)
     }
 
     /// Log a message
-    pub fn log(&self, context: LogContext) {
-        self.logger.log(context)
+    pub fn log(&self, mut context: LogContext) {
+        self.context_attributes
+            .extend_map(Signal::Logs, &mut context.attributes);
+        match &self.redactor {
+            Some(redactor) => self.logger.log(redactor.redact_log(context)),
+            None => self.logger.log(context),
+        }
     }
 
-    /// Log an error message
+    /// Log an error message.
+    ///
+    /// The error is `Send + Sync`, so it can be produced in one task and logged from
+    /// another. Plain messages are accepted too, e.g. `"payment declined"`.
     pub fn log_error(
         &self,
-        error: Box<dyn std::error::Error>,
+        error: impl Into<BoxError>,
+        target: Option<&str>,
+        mut attributes: Vec<(String, AttributeValue)>,
+    ) {
+        let error: BoxError = error.into();
+        self.context_attributes.extend(Signal::Logs, &mut attributes);
+        match &self.redactor {
+            Some(redactor) if redactor.applies_to(Signal::Logs) => self.logger.log_error(
+                &redactor.redact_error(error.as_ref()),
+                target,
+                redactor.redact_attributes(attributes),
+            ),
+            _ => self.logger.log_error(error.as_ref(), target, attributes),
+        }
+    }
+
+    /// Log an error whose type is known, reporting its type name as `error.kind`
+    pub fn report_error<E: std::error::Error + 'static>(
+        &self,
+        error: &E,
         target: Option<&str>,
         attributes: Vec<(String, AttributeValue)>,
     ) {
-        self.logger.log_error(error, target, attributes)
+        self.log_report(ErrorReport::new(error), target, attributes)
+    }
+
+    /// Log a described error
+    pub fn log_report(
+        &self,
+        report: ErrorReport,
+        target: Option<&str>,
+        mut attributes: Vec<(String, AttributeValue)>,
+    ) {
+        self.context_attributes.extend(Signal::Logs, &mut attributes);
+        match &self.redactor {
+            Some(redactor) if redactor.applies_to(Signal::Logs) => self.logger.log_report(
+                redactor.redact_report(report),
+                target,
+                redactor.redact_attributes(attributes),
+            ),
+            _ => self.logger.log_report(report, target, attributes),
+        }
+    }
+}
+
+// Creates spans scrubbed by the redactor, leaving the tracer's lifecycle to the service
+struct RedactedTracer {
+    tracer: Arc<dyn TracerPort>,
+    redactor: Arc<Redactor>,
+}
+
+#[async_trait]
+impl TracerPort for RedactedTracer {
+    async fn init(&self, _resource: &Resource) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    fn create_span(&self, context: SpanContext) -> Box<dyn Span> {
+        Box::new(RedactedSpan {
+            span: self.tracer.create_span(self.redactor.redact_span(context)),
+            redactor: self.redactor.clone(),
+        })
+    }
+
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+}
+
+// Scrubs what is recorded on a span after it was created
+struct RedactedSpan {
+    span: Box<dyn Span>,
+    redactor: Arc<Redactor>,
+}
+
+impl Span for RedactedSpan {
+    fn set_attribute(&self, key: String, value: AttributeValue) {
+        if let Some(value) = self.redactor.redact_attribute(&key, value) {
+            self.span.set_attribute(key, value);
+        }
+    }
+
+    fn add_event(&self, name: &str, attributes: Vec<(String, AttributeValue)>) {
+        self.span
+            .add_event(name, self.redactor.redact_attributes(attributes));
+    }
+
+    fn end(&self) {
+        self.span.end()
+    }
+
+    fn record_error(&self, report: &ErrorReport) {
+        self.span
+            .record_error(&self.redactor.redact_report(report.clone()));
+    }
+
+    fn set_error_status(&self, description: &str) {
+        self.span
+            .set_error_status(&self.redactor.redact_text(description));
+    }
+
+    fn get_context(&self) -> Context {
+        self.span.get_context()
+    }
+}
+
+// Adds the attributes of the current context to what it creates or records, leaving the
+// lifecycle of the wrapped port to the service
+struct WithContextAttributes<T> {
+    inner: T,
+    attributes: Arc<ContextAttributes>,
+}
+
+#[async_trait]
+impl TracerPort for WithContextAttributes<Arc<dyn TracerPort>> {
+    async fn init(&self, _resource: &Resource) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    fn create_span(&self, mut context: SpanContext) -> Box<dyn Span> {
+        self.attributes
+            .extend(Signal::Traces, &mut context.attributes);
+        self.inner.create_span(context)
+    }
+
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MetricsPort for WithContextAttributes<Arc<dyn MetricsPort>> {
+    async fn init(&self, _resource: &Resource) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    fn create_counter(&self, context: MetricContext) -> Box<dyn Counter> {
+        Box::new(WithContextAttributes {
+            inner: self.inner.create_counter(context),
+            attributes: self.attributes.clone(),
+        })
+    }
+
+    fn create_gauge(&self, context: MetricContext) -> Box<dyn Gauge> {
+        Box::new(WithContextAttributes {
+            inner: self.inner.create_gauge(context),
+            attributes: self.attributes.clone(),
+        })
+    }
+
+    fn create_histogram(&self, context: MetricContext) -> Box<dyn Histogram> {
+        Box::new(WithContextAttributes {
+            inner: self.inner.create_histogram(context),
+            attributes: self.attributes.clone(),
+        })
+    }
+
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+}
+
+impl Counter for WithContextAttributes<Box<dyn Counter>> {
+    fn add(&self, value: u64, mut attributes: Vec<(String, AttributeValue)>) {
+        self.attributes.extend(Signal::Metrics, &mut attributes);
+        self.inner.add(value, attributes);
+    }
+}
+
+impl Gauge for WithContextAttributes<Box<dyn Gauge>> {
+    fn set(&self, value: f64, mut attributes: Vec<(String, AttributeValue)>) {
+        self.attributes.extend(Signal::Metrics, &mut attributes);
+        self.inner.set(value, attributes);
+    }
+}
+
+impl Histogram for WithContextAttributes<Box<dyn Histogram>> {
+    fn record(&self, value: f64, mut attributes: Vec<(String, AttributeValue)>) {
+        self.attributes.extend(Signal::Metrics, &mut attributes);
+        self.inner.record(value, attributes);
+    }
+
+    fn unit(&self) -> Option<MetricUnit> {
+        self.inner.unit()
+    }
+}
+
+// Bound a single signal's flush by the given timeout
+async fn flush_with_timeout(
+    signal: Signal,
+    timeout: Duration,
+    flush: impl Future<Output = Result<(), TelemetryError>>,
+) -> Result<(), TelemetryError> {
+    match tokio::time::timeout(timeout, flush).await {
+        Ok(result) => result,
+        Err(_) => Err(TelemetryError::Timeout { signal, timeout }),
+    }
+}
+
+// Run a single component's shutdown until the deadline
+async fn shutdown_before(
+    deadline: Instant,
+    shutdown: impl Future<Output = Result<(), TelemetryError>>,
+) -> Option<SignalFailure> {
+    match tokio::time::timeout_at(deadline, shutdown).await {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(SignalFailure::Failed(Box::new(e))),
+        Err(_) => Some(SignalFailure::TimedOut),
     }
 }
 
@@ -103,6 +538,13 @@ pub struct TelemetryServiceBuilder {
     tracer: Option<Arc<dyn TracerPort>>,
     metrics: Option<Arc<dyn MetricsPort>>,
     logger: Option<Arc<dyn LoggerPort>>,
+    resource: ResourceBuilder,
+    self_telemetry_interval: Option<Duration>,
+    runtime_metrics_interval: Option<Duration>,
+    process_metrics_interval: Option<Duration>,
+    redactor: Option<Redactor>,
+    baggage: BaggageAttributes,
+    scoped_signals: Vec<Signal>,
 }
 
 impl TelemetryServiceBuilder {
@@ -112,6 +554,13 @@ impl TelemetryServiceBuilder {
             tracer: None,
             metrics: None,
             logger: None,
+            resource: ResourceBuilder::default(),
+            self_telemetry_interval: None,
+            runtime_metrics_interval: None,
+            process_metrics_interval: None,
+            redactor: None,
+            baggage: BaggageAttributes::default(),
+            scoped_signals: vec![Signal::Traces, Signal::Metrics, Signal::Logs],
         }
     }
 
@@ -133,21 +582,89 @@ impl TelemetryServiceBuilder {
         self
     }
 
+    /// Set the resource describing this service
+    pub fn with_resource(mut self, resource: ResourceBuilder) -> Self {
+        self.resource = resource;
+        self
+    }
+
+    /// Periodically emit the health of every signal as `otel_tracing.exporter.*` metrics:
+    /// counters of the records exported and dropped and of the failed exports, and
+    /// gauges of the queue depth and of the time since the last export. A zero
+    /// `interval` is raised to a millisecond.
+    pub fn with_self_telemetry(mut self, interval: Duration) -> Self {
+        self.self_telemetry_interval = Some(interval.max(MIN_COLLECTION_INTERVAL));
+        self
+    }
+
+    /// Periodically sample the scheduling metrics of the tokio runtime the service is
+    /// initialized on as `tokio.*` gauges and counters, from worker counts and queue
+    /// depths to the time each worker spent busy. Poll counts and local queue depths
+    /// are only recorded when built with `--cfg tokio_unstable`. A zero `interval` is
+    /// raised to a millisecond.
+    pub fn with_runtime_metrics(mut self, interval: Duration) -> Self {
+        self.runtime_metrics_interval = Some(interval.max(MIN_COLLECTION_INTERVAL));
+        self
+    }
+
+    /// Periodically record the CPU time, memory, threads, file descriptors, disk and
+    /// network IO of this process and the CPU time of the host as `process.*` and
+    /// `system.*` metrics, read from the proc filesystem. Nothing is recorded on other
+    /// platforms than Linux. A zero `interval` is raised to a millisecond.
+    pub fn with_process_metrics(mut self, interval: Duration) -> Self {
+        self.process_metrics_interval = Some(interval.max(MIN_COLLECTION_INTERVAL));
+        self
+    }
+
+    /// Scrub logs and span attributes with the given rules before any adapter sees them
+    pub fn with_redaction(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// Copy the baggage entries with these keys onto every span, log record and metric
+    /// point created while they are set. Each key adds to the cardinality of metrics.
+    pub fn with_baggage_attributes<I, K>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        self.baggage = BaggageAttributes::new(keys);
+        self
+    }
+
+    /// Only add the attributes of `telemetry::scope` to these signals, by default all of
+    /// them. Leave out metrics when scopes hold per-request values such as ids, as each
+    /// distinct value starts a new time series.
+    pub fn with_scoped_signals(mut self, signals: impl IntoIterator<Item = Signal>) -> Self {
+        self.scoped_signals = signals.into_iter().collect();
+        self
+    }
+
     /// Build the TelemetryService
     pub fn build(self) -> Result<TelemetryService, TelemetryError> {
         let tracer = self
             .tracer
-            .ok_or_else(|| TelemetryError::TracerInitError("No tracer provided".to_string()))?;
+            .ok_or_else(|| TelemetryError::config("No tracer provided"))?;
 
-        let metrics = self.metrics.ok_or_else(|| {
-            TelemetryError::MetricsInitError("No metrics provider provided".to_string())
-        })?;
+        let metrics = self
+            .metrics
+            .ok_or_else(|| TelemetryError::config("No metrics provider provided"))?;
 
         let logger = self
             .logger
-            .ok_or_else(|| TelemetryError::LoggerInitError("No logger provided".to_string()))?;
+            .ok_or_else(|| TelemetryError::config("No logger provided"))?;
 
-        Ok(TelemetryService::new(tracer, metrics, logger))
+        let mut service = TelemetryService::new(tracer, metrics, logger);
+        service.resource = self.resource;
+        service.self_telemetry_interval = self.self_telemetry_interval;
+        service.runtime_metrics_interval = self.runtime_metrics_interval;
+        service.process_metrics_interval = self.process_metrics_interval;
+        service.redactor = self.redactor.map(Arc::new);
+        service.context_attributes =
+            Arc::new(ContextAttributes::new(self.scoped_signals, self.baggage));
+
+        Ok(service)
     }
 
     /// Build a DataDog-based TelemetryService with default configuration
@@ -156,8 +673,11 @@ impl TelemetryServiceBuilder {
 
         let tracer = Arc::new(DatadogTracer::new());
         let metrics = Arc::new(DatadogMetrics::new());
-        let logger = Arc::new(DatadogLogger::new(service_name));
+        let logger = Arc::new(DatadogLogger::new(&service_name));
+
+        let mut service = TelemetryService::new(tracer, metrics, logger);
+        service.resource = ResourceBuilder::default().with_service_name(service_name);
 
-        Ok(TelemetryService::new(tracer, metrics, logger))
+        Ok(service)
     }
 }
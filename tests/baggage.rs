@@ -0,0 +1,125 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use opentelemetry::baggage::BaggageExt;
+    use opentelemetry::global;
+    use opentelemetry::propagation::TextMapCompositePropagator;
+    use opentelemetry::Context;
+    use opentelemetry_sdk::propagation::{BaggagePropagator, TraceContextPropagator};
+
+    use otel_tracing::adapters::db::DbContext;
+    use otel_tracing::adapters::messaging::{extract_context, inject_context};
+    use otel_tracing::domain::telemetry::{LogContext, LogLevel, MetricContext, SpanContext};
+    use otel_tracing::telemetry::baggage;
+    use otel_tracing::{spawn_with_context, TelemetryService, TelemetryServiceBuilder};
+
+    use crate::common::Recorder;
+
+    fn service(recorder: &Recorder) -> TelemetryService {
+        TelemetryServiceBuilder::new()
+            .with_tracer(recorder.tracer.clone())
+            .with_metrics(recorder.metrics.clone())
+            .with_logger(recorder.logger.clone())
+            .with_baggage_attributes(["tenant.id", "cohort"])
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_entries_travel_with_the_context() {
+        assert_eq!(baggage::get("tenant.id"), None);
+        {
+            let _tenant = baggage::set("tenant.id", "acme");
+            let _cohort = baggage::set("cohort", "beta");
+            assert_eq!(baggage::get("tenant.id").as_deref(), Some("acme"));
+            {
+                let _removed = baggage::remove("cohort");
+                assert_eq!(baggage::get("cohort"), None);
+                assert_eq!(baggage::get("tenant.id").as_deref(), Some("acme"));
+            }
+            assert_eq!(baggage::get("cohort").as_deref(), Some("beta"));
+
+            let spawned = spawn_with_context(async { baggage::get("tenant.id") });
+            assert_eq!(spawned.await.unwrap().as_deref(), Some("acme"));
+        }
+        assert_eq!(baggage::get("tenant.id"), None);
+
+        let tenant = baggage::with_baggage([("tenant.id", "globex")], || baggage::get("tenant.id"));
+        assert_eq!(tenant.as_deref(), Some("globex"));
+        let tenant = baggage::with_async_baggage([("tenant.id", "initech")], async {
+            tokio::task::yield_now().await;
+            baggage::get("tenant.id")
+        })
+        .await;
+        assert_eq!(tenant.as_deref(), Some("initech"));
+    }
+
+    #[test]
+    fn test_selected_entries_are_copied_onto_telemetry() {
+        let recorder = Recorder::default();
+        let service = service(&recorder);
+
+        baggage::with_baggage(
+            [("tenant.id", "acme"), ("user.email", "bob@acme.test")],
+            || {
+                service.create_span(SpanContext::new("checkout".to_string()));
+                service.create_span(
+                    SpanContext::new("refund".to_string())
+                        .with_attributes(vec![("tenant.id".to_string(), "globex".into())]),
+                );
+                service
+                    .create_counter(MetricContext::new("orders".to_string()))
+                    .add(1, Vec::new());
+                service.log(LogContext::new("order placed".to_string(), LogLevel::Info));
+                service.log_error("payment declined", None, Vec::new());
+                drop(service.db_tracer().start(DbContext::new("postgresql")));
+            },
+        );
+
+        for name in [
+            "checkout",
+            "orders",
+            "order placed",
+            "payment declined",
+            "postgresql",
+            "db.client.operation.duration",
+        ] {
+            let attributes = recorder.attributes(name);
+            assert_eq!(attributes["tenant.id"], "acme", "{}", name);
+            assert!(!attributes.contains_key("cohort"), "{}", name);
+            assert!(!attributes.contains_key("user.email"), "{}", name);
+        }
+        // Attributes set explicitly win over baggage
+        assert_eq!(recorder.attributes("refund")["tenant.id"], "globex");
+
+        // Nothing is copied without baggage
+        service.create_span(SpanContext::new("cleanup".to_string()));
+        assert!(recorder.attributes("cleanup").is_empty());
+    }
+
+    #[test]
+    fn test_entries_are_propagated() {
+        global::set_text_map_propagator(TextMapCompositePropagator::new(vec![
+            Box::new(TraceContextPropagator::new()),
+            Box::new(BaggagePropagator::new()),
+        ]));
+
+        let mut headers: HashMap<String, Vec<u8>> = HashMap::new();
+        let _tenant = baggage::set("tenant.id", "acme");
+        inject_context(&Context::current(), &mut headers);
+        assert_eq!(headers["baggage"], b"tenant.id=acme");
+
+        drop(_tenant);
+        let context = extract_context(&headers);
+        assert_eq!(
+            context
+                .baggage()
+                .get("tenant.id")
+                .map(|value| value.as_str()),
+            Some("acme")
+        );
+    }
+}
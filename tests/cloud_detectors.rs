@@ -0,0 +1,426 @@
+#[cfg(test)]
+#[cfg(any(feature = "aws", feature = "gcp", feature = "azure"))]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{SocketAddr, TcpListener};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use opentelemetry::{Key, KeyValue};
+    use opentelemetry_sdk::resource::ResourceDetector;
+    use opentelemetry_sdk::Resource;
+
+    // A request received by the stand-in, with header names in lowercase
+    #[derive(Debug, Clone)]
+    struct Received {
+        method: String,
+        path: String,
+        headers: HashMap<String, String>,
+    }
+
+    // A metadata service answering canned bodies by path, 404 for anything else
+    struct StandIn {
+        addr: SocketAddr,
+        received: Arc<Mutex<Vec<Received>>>,
+    }
+
+    impl StandIn {
+        fn serve(routes: &[(&str, &str)]) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            let routes: HashMap<String, String> = routes
+                .iter()
+                .map(|(path, body)| (path.to_string(), body.to_string()))
+                .collect();
+            let received = Arc::new(Mutex::new(Vec::new()));
+
+            let log = received.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(mut stream) = stream else { break };
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    let mut parts = request_line.split_whitespace();
+                    let method = parts.next().unwrap_or_default().to_string();
+                    let path = parts.next().unwrap_or_default().to_string();
+
+                    let mut headers = HashMap::new();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        match line.trim_end().split_once(':') {
+                            Some((name, value)) => {
+                                headers.insert(name.to_lowercase(), value.trim().to_string());
+                            }
+                            None => break,
+                        }
+                    }
+
+                    let response = match routes.get(&path) {
+                        Some(body) => format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                            body.len(),
+                            body
+                        ),
+                        None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_string(),
+                    };
+                    // Logged before answering, for the client to find it once answered
+                    log.lock().unwrap().push(Received {
+                        method,
+                        path,
+                        headers,
+                    });
+                    stream.write_all(response.as_bytes()).unwrap();
+                }
+            });
+
+            Self { addr, received }
+        }
+
+        fn endpoint(&self) -> String {
+            format!("http://{}", self.addr)
+        }
+
+        fn received(&self) -> Vec<Received> {
+            self.received.lock().unwrap().clone()
+        }
+    }
+
+    fn attribute(resource: &Resource, key: &'static str) -> Option<String> {
+        resource
+            .get(&Key::from_static_str(key))
+            .map(|value| value.to_string())
+    }
+
+    #[cfg(feature = "aws")]
+    mod aws {
+        use super::*;
+        use otel_tracing::domain::detectors::{
+            EcsResourceDetector, EksResourceDetector, LambdaResourceDetector,
+        };
+
+        const ECS_CONTAINER: &str = r#"{
+            "DockerId": "cd189a933e5849daa93386466019ab50-2495160603",
+            "Name": "checkout",
+            "ContainerARN": "arn:aws:ecs:eu-west-1:111122223333:container/default/cd189a93/1f4b"
+        }"#;
+
+        const ECS_TASK: &str = r#"{
+            "Cluster": "payments",
+            "TaskARN": "arn:aws:ecs:eu-west-1:111122223333:task/payments/cd189a933e5849daa93386466019ab50",
+            "Family": "checkout",
+            "Revision": "26",
+            "AvailabilityZone": "eu-west-1b",
+            "LaunchType": "EC2"
+        }"#;
+
+        #[test]
+        fn test_ecs_attributes_from_task_metadata() {
+            let stand_in =
+                StandIn::serve(&[("/v4/abc", ECS_CONTAINER), ("/v4/abc/task", ECS_TASK)]);
+            let detector = EcsResourceDetector::default().with_env([(
+                "ECS_CONTAINER_METADATA_URI_V4",
+                format!("{}/v4/abc", stand_in.endpoint()),
+            )]);
+
+            let resource = detector.detect();
+
+            assert_eq!(
+                attribute(&resource, "cloud.provider").as_deref(),
+                Some("aws")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.platform").as_deref(),
+                Some("aws_ecs")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.region").as_deref(),
+                Some("eu-west-1")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.account.id").as_deref(),
+                Some("111122223333")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.availability_zone").as_deref(),
+                Some("eu-west-1b")
+            );
+            // The cluster name is expanded to an ARN
+            assert_eq!(
+                attribute(&resource, "aws.ecs.cluster.arn").as_deref(),
+                Some("arn:aws:ecs:eu-west-1:111122223333:cluster/payments")
+            );
+            assert_eq!(
+                attribute(&resource, "aws.ecs.task.revision").as_deref(),
+                Some("26")
+            );
+            assert_eq!(
+                attribute(&resource, "aws.ecs.launchtype").as_deref(),
+                Some("ec2")
+            );
+            assert_eq!(
+                attribute(&resource, "container.id").as_deref(),
+                Some("cd189a933e5849daa93386466019ab50-2495160603")
+            );
+        }
+
+        #[test]
+        fn test_lambda_attributes_from_environment() {
+            let detector = LambdaResourceDetector::default().with_env([
+                ("AWS_LAMBDA_FUNCTION_NAME", "resize-images"),
+                ("AWS_LAMBDA_FUNCTION_VERSION", "$LATEST"),
+                ("AWS_LAMBDA_FUNCTION_MEMORY_SIZE", "128"),
+                ("AWS_LAMBDA_LOG_STREAM_NAME", "2024/01/01/[$LATEST]8f1e"),
+                ("AWS_REGION", "us-east-1"),
+            ]);
+
+            let resource = detector.detect();
+
+            assert_eq!(
+                attribute(&resource, "cloud.platform").as_deref(),
+                Some("aws_lambda")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.region").as_deref(),
+                Some("us-east-1")
+            );
+            assert_eq!(
+                attribute(&resource, "faas.name").as_deref(),
+                Some("resize-images")
+            );
+            assert_eq!(
+                attribute(&resource, "faas.version").as_deref(),
+                Some("$LATEST")
+            );
+            assert_eq!(
+                attribute(&resource, "faas.max_memory").as_deref(),
+                Some("134217728")
+            );
+
+            let outside = LambdaResourceDetector::default().with_env([("AWS_REGION", "us-east-1")]);
+            assert!(outside.attributes().is_empty());
+        }
+
+        #[test]
+        fn test_eks_attributes_from_imdsv2() {
+            let stand_in = StandIn::serve(&[
+                ("/latest/api/token", "AQAEAFEfoo=="),
+                (
+                    "/latest/dynamic/instance-identity/document",
+                    r#"{"accountId":"111122223333","region":"eu-west-1","availabilityZone":"eu-west-1a","instanceId":"i-0abc","instanceType":"m5.large"}"#,
+                ),
+            ]);
+            let detector = EksResourceDetector::default()
+                .with_endpoint(stand_in.endpoint())
+                .with_env([("KUBERNETES_SERVICE_HOST", "10.100.0.1")]);
+
+            let resource = detector.detect();
+
+            assert_eq!(
+                attribute(&resource, "cloud.platform").as_deref(),
+                Some("aws_eks")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.account.id").as_deref(),
+                Some("111122223333")
+            );
+            assert_eq!(attribute(&resource, "host.id").as_deref(), Some("i-0abc"));
+
+            // The document is requested with the session token
+            let received = stand_in.received();
+            assert_eq!(received[0].method, "PUT");
+            assert_eq!(
+                received[1].path,
+                "/latest/dynamic/instance-identity/document"
+            );
+            assert_eq!(
+                received[1]
+                    .headers
+                    .get("x-aws-ec2-metadata-token")
+                    .map(String::as_str),
+                Some("AQAEAFEfoo==")
+            );
+        }
+    }
+
+    #[cfg(feature = "gcp")]
+    mod gcp {
+        use super::*;
+        use otel_tracing::domain::detectors::GcpResourceDetector;
+
+        #[test]
+        fn test_gce_attributes_from_metadata_server() {
+            let stand_in = StandIn::serve(&[
+                ("/computeMetadata/v1/project/project-id", "shop-prod"),
+                ("/computeMetadata/v1/instance/id", "4520031799277581759"),
+                ("/computeMetadata/v1/instance/name", "checkout-vm"),
+                (
+                    "/computeMetadata/v1/instance/zone",
+                    "projects/123456789/zones/europe-west1-b",
+                ),
+                (
+                    "/computeMetadata/v1/instance/machine-type",
+                    "projects/123456789/machineTypes/e2-medium",
+                ),
+            ]);
+            let detector = GcpResourceDetector::default()
+                .with_endpoint(stand_in.endpoint())
+                .with_env(Vec::<(String, String)>::new());
+
+            let resource = detector.detect();
+
+            assert_eq!(
+                attribute(&resource, "cloud.provider").as_deref(),
+                Some("gcp")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.platform").as_deref(),
+                Some("gcp_compute_engine")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.account.id").as_deref(),
+                Some("shop-prod")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.availability_zone").as_deref(),
+                Some("europe-west1-b")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.region").as_deref(),
+                Some("europe-west1")
+            );
+            assert_eq!(
+                attribute(&resource, "host.type").as_deref(),
+                Some("e2-medium")
+            );
+
+            assert!(stand_in.received().iter().all(|request| request
+                .headers
+                .get("metadata-flavor")
+                .map(String::as_str)
+                == Some("Google")));
+        }
+
+        #[test]
+        fn test_cloud_run_attributes() {
+            let stand_in = StandIn::serve(&[
+                ("/computeMetadata/v1/project/project-id", "shop-prod"),
+                ("/computeMetadata/v1/instance/id", "00bf4bf0"),
+                (
+                    "/computeMetadata/v1/instance/region",
+                    "projects/123456789/regions/us-central1",
+                ),
+            ]);
+            let detector = GcpResourceDetector::default()
+                .with_endpoint(stand_in.endpoint())
+                .with_env([
+                    ("K_SERVICE", "checkout"),
+                    ("K_REVISION", "checkout-00042-xiz"),
+                ]);
+
+            let resource = detector.detect();
+
+            assert_eq!(
+                attribute(&resource, "cloud.platform").as_deref(),
+                Some("gcp_cloud_run")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.region").as_deref(),
+                Some("us-central1")
+            );
+            assert_eq!(
+                attribute(&resource, "faas.name").as_deref(),
+                Some("checkout")
+            );
+            assert_eq!(
+                attribute(&resource, "faas.version").as_deref(),
+                Some("checkout-00042-xiz")
+            );
+            assert_eq!(
+                attribute(&resource, "faas.instance").as_deref(),
+                Some("00bf4bf0")
+            );
+        }
+    }
+
+    #[cfg(feature = "azure")]
+    mod azure {
+        use super::*;
+        use otel_tracing::domain::detectors::AzureResourceDetector;
+
+        #[test]
+        fn test_azure_attributes_from_imds() {
+            let stand_in = StandIn::serve(&[(
+                "/metadata/instance/compute?api-version=2021-12-13&format=json",
+                r#"{
+                    "location": "westeurope",
+                    "name": "checkout-vm",
+                    "subscriptionId": "8d10da13-8125-4ba9-a717-bf7490507b3d",
+                    "resourceGroupName": "payments",
+                    "vmId": "02aab8a4-74ef-476e-8182-f6d2ba4166a6",
+                    "vmSize": "Standard_D2s_v3",
+                    "vmScaleSetName": "",
+                    "zone": "1"
+                }"#,
+            )]);
+            let detector = AzureResourceDetector::default().with_endpoint(stand_in.endpoint());
+
+            let resource = detector.detect();
+
+            assert_eq!(
+                attribute(&resource, "cloud.provider").as_deref(),
+                Some("azure")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.region").as_deref(),
+                Some("westeurope")
+            );
+            assert_eq!(
+                attribute(&resource, "cloud.account.id").as_deref(),
+                Some("8d10da13-8125-4ba9-a717-bf7490507b3d")
+            );
+            assert_eq!(
+                attribute(&resource, "azure.resourcegroup.name").as_deref(),
+                Some("payments")
+            );
+            // Empty values are left out
+            assert_eq!(attribute(&resource, "azure.vm.scaleset.name"), None);
+
+            let received = stand_in.received();
+            assert_eq!(
+                received[0].headers.get("metadata").map(String::as_str),
+                Some("true")
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "azure")]
+    fn test_unreachable_metadata_service_detects_nothing() {
+        use std::time::{Duration, Instant};
+
+        use otel_tracing::domain::detectors::AzureResourceDetector;
+
+        // Nothing listens on a port that was just released
+        let closed = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let detector = AzureResourceDetector::default().with_endpoint(format!("http://{}", closed));
+        assert!(detector.detect().is_empty());
+
+        // A service that accepts but never answers is given up on after the timeout
+        let silent = TcpListener::bind("127.0.0.1:0").unwrap();
+        let detector = AzureResourceDetector::default()
+            .with_endpoint(format!("http://{}", silent.local_addr().unwrap()))
+            .with_timeout(Duration::from_millis(100));
+
+        let started = Instant::now();
+        let attributes: Vec<KeyValue> = detector.attributes();
+        assert!(attributes.is_empty());
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
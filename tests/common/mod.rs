@@ -0,0 +1,443 @@
+//! Recording doubles of the ports, shared by the integration tests.
+//!
+//! Every test crate only uses some of them.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use opentelemetry::trace::{
+    SpanContext as OtelSpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+};
+use opentelemetry::Context;
+use opentelemetry_sdk::Resource;
+use tracing_subscriber::EnvFilter;
+
+use otel_tracing::domain::metrics::MetricUnit;
+use otel_tracing::domain::telemetry::{
+    AttributeValue, LogContext, MetricContext, SpanContext, SpanKind, TelemetryError,
+};
+use otel_tracing::ports::logger::LoggerPort;
+use otel_tracing::ports::metrics::{Counter, Gauge, Histogram, MetricsPort};
+use otel_tracing::ports::tracer::{Span, TracerPort};
+use otel_tracing::ErrorReport;
+
+pub type Records<T> = Arc<Mutex<Vec<T>>>;
+
+pub type Attributes = HashMap<String, String>;
+
+/// The trace of the spans created by [`RecordingTracer::with_span_ids`]
+pub const TRACE_ID: &str = "2de7888d8f42abc9c7ba048b78f7a9fb";
+
+fn to_strings(attributes: impl IntoIterator<Item = (String, AttributeValue)>) -> Attributes {
+    attributes
+        .into_iter()
+        .map(|(key, value)| (key, value.to_string()))
+        .collect()
+}
+
+/// A sampled span of the [`TRACE_ID`] trace
+pub fn span_context(span_id: u64) -> OtelSpanContext {
+    OtelSpanContext::new(
+        TraceId::from_hex(TRACE_ID).unwrap(),
+        SpanId::from(span_id),
+        TraceFlags::SAMPLED,
+        false,
+        TraceState::default(),
+    )
+}
+
+/// What a span was created with and what happened to it
+#[derive(Debug, Clone, Default)]
+pub struct SpanRecord {
+    pub name: String,
+    pub kind: SpanKind,
+    pub parent_trace_id: Option<TraceId>,
+    pub parent_span_id: Option<SpanId>,
+    pub links: Vec<SpanId>,
+    pub attributes: Attributes,
+    pub events: Vec<(String, Attributes)>,
+    pub error_status: Option<String>,
+    pub ended: bool,
+}
+
+impl SpanRecord {
+    pub fn event_names(&self) -> Vec<&str> {
+        self.events.iter().map(|(name, _)| name.as_str()).collect()
+    }
+}
+
+/// Keeps every span it creates
+#[derive(Default, Clone)]
+pub struct RecordingTracer {
+    spans: Records<SpanRecord>,
+    span_ids: bool,
+}
+
+impl RecordingTracer {
+    /// Spans get ids of their own, counting from 1 in the [`TRACE_ID`] trace, so they
+    /// can be found in what is propagated. Otherwise they carry the current context.
+    pub fn with_span_ids() -> Self {
+        Self {
+            span_ids: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn spans(&self) -> Vec<SpanRecord> {
+        self.spans.lock().unwrap().clone()
+    }
+
+    pub fn spans_of_kind(&self, kind: SpanKind) -> Vec<SpanRecord> {
+        let spans = self.spans.lock().unwrap();
+        spans
+            .iter()
+            .filter(|span| span.kind == kind)
+            .cloned()
+            .collect()
+    }
+
+    pub fn span(&self, name: &str) -> Option<SpanRecord> {
+        let spans = self.spans.lock().unwrap();
+        spans.iter().find(|span| span.name == name).cloned()
+    }
+}
+
+#[async_trait]
+impl TracerPort for RecordingTracer {
+    async fn init(&self, _resource: &Resource) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    fn create_span(&self, context: SpanContext) -> Box<dyn Span> {
+        let parent = Context::current();
+        let parent = parent.span().span_context().clone();
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(SpanRecord {
+            name: context.name,
+            kind: context.kind,
+            parent_trace_id: parent.is_valid().then(|| parent.trace_id()),
+            parent_span_id: parent.is_valid().then(|| parent.span_id()),
+            links: context.links.iter().map(|link| link.span_id()).collect(),
+            attributes: to_strings(context.attributes),
+            ..SpanRecord::default()
+        });
+        Box::new(RecordingSpan {
+            records: self.spans.clone(),
+            index: spans.len() - 1,
+            span_ids: self.span_ids,
+        })
+    }
+
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+}
+
+struct RecordingSpan {
+    records: Records<SpanRecord>,
+    index: usize,
+    span_ids: bool,
+}
+
+impl RecordingSpan {
+    fn update(&self, f: impl FnOnce(&mut SpanRecord)) {
+        f(&mut self.records.lock().unwrap()[self.index]);
+    }
+}
+
+impl Span for RecordingSpan {
+    fn set_attribute(&self, key: String, value: AttributeValue) {
+        self.update(|record| {
+            record.attributes.insert(key, value.to_string());
+        });
+    }
+
+    fn add_event(&self, name: &str, attributes: Vec<(String, AttributeValue)>) {
+        self.update(|record| {
+            record
+                .events
+                .push((name.to_string(), to_strings(attributes)))
+        });
+    }
+
+    fn end(&self) {
+        self.update(|record| record.ended = true);
+    }
+
+    fn set_error_status(&self, description: &str) {
+        self.update(|record| record.error_status = Some(description.to_string()));
+    }
+
+    fn get_context(&self) -> Context {
+        if self.span_ids {
+            Context::current().with_remote_span_context(span_context(self.index as u64 + 1))
+        } else {
+            Context::current()
+        }
+    }
+}
+
+/// A value recorded by an instrument, with the instrument's name
+pub type Recorded = (String, f64, Attributes);
+
+/// Keeps the instruments it creates and every value recorded in them
+#[derive(Default, Clone)]
+pub struct RecordingMetrics {
+    instruments: Records<(String, Option<MetricUnit>)>,
+    values: Records<Recorded>,
+}
+
+impl RecordingMetrics {
+    /// The name and unit of every instrument created, in order
+    pub fn instruments(&self) -> Vec<(String, Option<MetricUnit>)> {
+        self.instruments.lock().unwrap().clone()
+    }
+
+    /// Every value recorded, in order
+    pub fn values(&self) -> Vec<Recorded> {
+        self.values.lock().unwrap().clone()
+    }
+
+    /// The values recorded by the instruments called `name`
+    pub fn recorded(&self, name: &str) -> Vec<(f64, Attributes)> {
+        let values = self.values.lock().unwrap();
+        values
+            .iter()
+            .filter(|(instrument, _, _)| instrument == name)
+            .map(|(_, value, attributes)| (*value, attributes.clone()))
+            .collect()
+    }
+
+    fn instrument(&self, context: MetricContext) -> Box<RecordingInstrument> {
+        self.instruments
+            .lock()
+            .unwrap()
+            .push((context.name.clone(), context.unit.clone()));
+        Box::new(RecordingInstrument {
+            name: context.name,
+            unit: context.unit,
+            values: self.values.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl MetricsPort for RecordingMetrics {
+    async fn init(&self, _resource: &Resource) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    fn create_counter(&self, context: MetricContext) -> Box<dyn Counter> {
+        self.instrument(context)
+    }
+
+    fn create_gauge(&self, context: MetricContext) -> Box<dyn Gauge> {
+        self.instrument(context)
+    }
+
+    fn create_histogram(&self, context: MetricContext) -> Box<dyn Histogram> {
+        self.instrument(context)
+    }
+
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+}
+
+struct RecordingInstrument {
+    name: String,
+    unit: Option<MetricUnit>,
+    values: Records<Recorded>,
+}
+
+impl RecordingInstrument {
+    fn push(&self, value: f64, attributes: Vec<(String, AttributeValue)>) {
+        self.values
+            .lock()
+            .unwrap()
+            .push((self.name.clone(), value, to_strings(attributes)));
+    }
+}
+
+impl Counter for RecordingInstrument {
+    fn add(&self, value: u64, attributes: Vec<(String, AttributeValue)>) {
+        self.push(value as f64, attributes);
+    }
+}
+
+impl Gauge for RecordingInstrument {
+    fn set(&self, value: f64, attributes: Vec<(String, AttributeValue)>) {
+        self.push(value, attributes);
+    }
+}
+
+impl Histogram for RecordingInstrument {
+    fn record(&self, value: f64, attributes: Vec<(String, AttributeValue)>) {
+        self.push(value, attributes);
+    }
+
+    fn unit(&self) -> Option<MetricUnit> {
+        self.unit.clone()
+    }
+}
+
+/// Keeps every record it is given, errors being logged through the default `log_report`
+#[derive(Default, Clone)]
+pub struct RecordingLogger {
+    records: Records<LogContext>,
+}
+
+impl RecordingLogger {
+    pub fn records(&self) -> Vec<LogContext> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// The messages logged since the last call
+    pub fn take_messages(&self) -> Vec<String> {
+        let records = std::mem::take(&mut *self.records.lock().unwrap());
+        records.into_iter().map(|record| record.message).collect()
+    }
+}
+
+#[async_trait]
+impl LoggerPort for RecordingLogger {
+    async fn init(
+        &self,
+        _resource: &Resource,
+        _filter: Option<EnvFilter>,
+    ) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    fn log(&self, context: LogContext) {
+        self.records.lock().unwrap().push(context);
+    }
+
+    fn log_error(
+        &self,
+        error: &(dyn std::error::Error + 'static),
+        target: Option<&str>,
+        attributes: Vec<(String, AttributeValue)>,
+    ) {
+        self.log_report(ErrorReport::from_dyn(error), target, attributes);
+    }
+
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+}
+
+/// One recorder per signal, to find what any of them was given by name
+#[derive(Default, Clone)]
+pub struct Recorder {
+    pub tracer: RecordingTracer,
+    pub metrics: RecordingMetrics,
+    pub logger: RecordingLogger,
+}
+
+impl Recorder {
+    /// The attributes of the span, metric value or log record called `name`
+    pub fn attributes(&self, name: &str) -> Attributes {
+        if let Some(span) = self.tracer.span(name) {
+            return span.attributes;
+        }
+        if let Some((_, attributes)) = self.metrics.recorded(name).into_iter().next() {
+            return attributes;
+        }
+        let records = self.logger.records();
+        let record = records.into_iter().find(|record| record.message == name);
+        to_strings(record.expect(name).attributes)
+    }
+}
+
+/// Does nothing, whatever the port
+pub struct Noop;
+
+impl Span for Noop {
+    fn set_attribute(&self, _key: String, _value: AttributeValue) {}
+
+    fn add_event(&self, _name: &str, _attributes: Vec<(String, AttributeValue)>) {}
+
+    fn end(&self) {}
+
+    fn get_context(&self) -> Context {
+        Context::current()
+    }
+}
+
+impl Counter for Noop {
+    fn add(&self, _value: u64, _attributes: Vec<(String, AttributeValue)>) {}
+}
+
+impl Gauge for Noop {
+    fn set(&self, _value: f64, _attributes: Vec<(String, AttributeValue)>) {}
+}
+
+impl Histogram for Noop {
+    fn record(&self, _value: f64, _attributes: Vec<(String, AttributeValue)>) {}
+}
+
+#[async_trait]
+impl TracerPort for Noop {
+    async fn init(&self, _resource: &Resource) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    fn create_span(&self, _context: SpanContext) -> Box<dyn Span> {
+        Box::new(Noop)
+    }
+
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MetricsPort for Noop {
+    async fn init(&self, _resource: &Resource) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    fn create_counter(&self, _context: MetricContext) -> Box<dyn Counter> {
+        Box::new(Noop)
+    }
+
+    fn create_gauge(&self, _context: MetricContext) -> Box<dyn Gauge> {
+        Box::new(Noop)
+    }
+
+    fn create_histogram(&self, _context: MetricContext) -> Box<dyn Histogram> {
+        Box::new(Noop)
+    }
+
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LoggerPort for Noop {
+    async fn init(
+        &self,
+        _resource: &Resource,
+        _filter: Option<EnvFilter>,
+    ) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+
+    fn log(&self, _context: LogContext) {}
+
+    fn log_error(
+        &self,
+        _error: &(dyn std::error::Error + 'static),
+        _target: Option<&str>,
+        _attributes: Vec<(String, AttributeValue)>,
+    ) {
+    }
+
+    async fn shutdown(&self) -> Result<(), TelemetryError> {
+        Ok(())
+    }
+}
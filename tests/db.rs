@@ -0,0 +1,157 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use otel_tracing::adapters::db::{sanitize_query, DbContext, DbTracer};
+    use otel_tracing::domain::metrics::{DatabaseUnit, MetricUnit, TimeUnit};
+    use otel_tracing::domain::telemetry::SpanKind;
+
+    use crate::common::{RecordingMetrics, RecordingTracer};
+
+    #[test]
+    fn test_literals_are_stripped_from_queries() {
+        for (query, sanitized) in [
+            (
+                "SELECT * FROM users WHERE name = 'o''brien' AND age > 42",
+                "SELECT * FROM users WHERE name = ? AND age > ?",
+            ),
+            (
+                "INSERT INTO t2 (a, b) VALUES (-1.5e3, 0xFF), ('it\\'s', $1)",
+                "INSERT INTO t2 (a, b) VALUES (-?, ?), (?, $1)",
+            ),
+            (
+                "UPDATE \"table 1\" SET body = $tag$ 'secret' $tag$ WHERE id = ?",
+                "UPDATE \"table 1\" SET body = ? WHERE id = ?",
+            ),
+            (
+                "SELECT/* user 42 */id FROM t -- card 4242\nLIMIT 10",
+                "SELECT id FROM t \nLIMIT ?",
+            ),
+            ("SELECT $$ unterminated", "SELECT ?"),
+        ] {
+            assert_eq!(sanitize_query(query), sanitized);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_operations_are_traced_and_measured() {
+        let (tracer, metrics) = (RecordingTracer::default(), RecordingMetrics::default());
+        let db = DbTracer::new(Arc::new(tracer.clone()), &metrics);
+        assert_eq!(
+            metrics.instruments(),
+            vec![
+                (
+                    "db.client.operation.duration".to_string(),
+                    Some(MetricUnit::Time(TimeUnit::Second))
+                ),
+                (
+                    "db.client.response.returned_rows".to_string(),
+                    Some(MetricUnit::Database(DatabaseUnit::Row))
+                ),
+            ]
+        );
+
+        let context = DbContext::new("postgresql")
+            .with_namespace("shop")
+            .with_collection("orders");
+        let operation = db.start(
+            context
+                .clone()
+                .with_query("select id from orders where total > 100"),
+        );
+        operation.returned_rows(3);
+        drop(operation);
+
+        let error = std::io::Error::other("connection reset");
+        let result: Result<(), _> = db
+            .run(context.with_operation("DELETE"), async move { Err(error) })
+            .await;
+        assert!(result.is_err());
+
+        let spans = tracer.spans();
+        assert_eq!(spans[0].name, "SELECT orders");
+        assert_eq!(spans[0].kind, SpanKind::Client);
+        for (key, value) in [
+            ("db.system", "postgresql"),
+            ("db.namespace", "shop"),
+            ("db.collection.name", "orders"),
+            ("db.operation.name", "SELECT"),
+            ("db.query.text", "select id from orders where total > ?"),
+            ("db.response.returned_rows", "3"),
+        ] {
+            assert_eq!(spans[0].attributes[key], value, "{}", key);
+        }
+        assert!(spans[0].ended);
+        assert_eq!(spans[0].error_status, None);
+
+        assert_eq!(spans[1].name, "DELETE orders");
+        assert_eq!(spans[1].attributes["error.type"], "std::io::error::Error");
+        assert_eq!(spans[1].error_status.as_deref(), Some("connection reset"));
+        assert_eq!(spans[1].event_names(), vec!["exception"]);
+        assert!(spans[1].ended);
+
+        let rows = metrics.recorded("db.client.response.returned_rows");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].0, 3.0);
+        let durations = metrics.recorded("db.client.operation.duration");
+        assert_eq!(durations.len(), 2);
+        assert!(!durations[0].1.contains_key("db.query.text"));
+        assert_eq!(durations[1].1["error.type"], "std::io::error::Error");
+    }
+
+    #[cfg(feature = "sqlx")]
+    #[tokio::test]
+    async fn test_sqlx_queries_are_traced() {
+        use otel_tracing::adapters::db::TracedPool;
+        use sqlx::sqlite::SqlitePoolOptions;
+
+        let (tracer, metrics) = (RecordingTracer::default(), RecordingMetrics::default());
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let pool = TracedPool::new(pool, DbTracer::new(Arc::new(tracer.clone()), &metrics))
+            .with_namespace("main");
+
+        pool.execute(sqlx::query(
+            "CREATE TABLE orders (id INTEGER PRIMARY KEY, item TEXT)",
+        ))
+        .await
+        .unwrap();
+        pool.execute(sqlx::query(
+            "INSERT INTO orders VALUES (1, 'book'), (2, 'pen')",
+        ))
+        .await
+        .unwrap();
+        let rows = pool
+            .fetch_all(sqlx::query("SELECT * FROM orders WHERE id > ?").bind(0))
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        let error = pool
+            .execute(sqlx::query("INSERT INTO orders VALUES (1, 'mug')"))
+            .await
+            .unwrap_err();
+        assert!(error.as_database_error().is_some());
+
+        let spans = tracer.spans();
+        assert_eq!(spans.len(), 4);
+        assert_eq!(spans[1].name, "INSERT main");
+        assert_eq!(spans[1].attributes["db.system"], "sqlite");
+        assert_eq!(
+            spans[1].attributes["db.query.text"],
+            "INSERT INTO orders VALUES (?, ?), (?, ?)"
+        );
+        assert_eq!(spans[2].name, "SELECT main");
+        assert_eq!(spans[2].attributes["db.response.returned_rows"], "2");
+
+        // Primary key violations have SQLite's extended result code
+        assert_eq!(spans[3].attributes["db.response.status_code"], "1555");
+        assert_eq!(spans[3].attributes["error.type"], "1555");
+        assert!(spans[3].error_status.is_some());
+        assert!(spans.iter().all(|span| span.ended));
+    }
+}
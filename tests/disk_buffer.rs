@@ -0,0 +1,275 @@
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime};
+
+    use opentelemetry::trace::{
+        SpanContext, SpanId, SpanKind, Status, TraceFlags, TraceId, TraceState,
+    };
+    use opentelemetry::InstrumentationScope;
+    use opentelemetry_proto::tonic::collector::trace::v1::trace_service_server::{
+        TraceService, TraceServiceServer,
+    };
+    use opentelemetry_proto::tonic::collector::trace::v1::{
+        ExportTraceServiceRequest, ExportTraceServiceResponse,
+    };
+    use opentelemetry_sdk::trace::{SpanData, SpanEvents, SpanExporter, SpanLinks};
+    use tokio::net::TcpSocket;
+    use tokio::sync::oneshot;
+    use tokio::task::JoinHandle;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::metadata::MetadataMap;
+    use tonic::{Request, Response};
+
+    use otel_tracing::adapters::disk_buffer::{BufferedSpanExporter, DiskBufferConfig, DiskQueue};
+    use otel_tracing::domain::health::ExportStats;
+
+    // An OTLP collector recording the name of every span it receives, and the
+    // credentials of every request
+    #[derive(Clone, Default)]
+    struct StandIn {
+        received: Arc<Mutex<Vec<String>>>,
+        authorizations: Arc<Mutex<Vec<Option<String>>>>,
+    }
+
+    #[tonic::async_trait]
+    impl TraceService for StandIn {
+        async fn export(
+            &self,
+            request: Request<ExportTraceServiceRequest>,
+        ) -> Result<Response<ExportTraceServiceResponse>, tonic::Status> {
+            let authorization = request
+                .metadata()
+                .get("authorization")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            self.authorizations.lock().unwrap().push(authorization);
+
+            let names = request
+                .into_inner()
+                .resource_spans
+                .into_iter()
+                .flat_map(|resource| resource.scope_spans)
+                .flat_map(|scope| scope.spans)
+                .map(|span| span.name);
+            self.received.lock().unwrap().extend(names);
+
+            Ok(Response::new(ExportTraceServiceResponse {
+                partial_success: None,
+            }))
+        }
+    }
+
+    // The stand-in served on a fixed address, so it can be stopped and started again
+    struct Collector {
+        addr: SocketAddr,
+        stand_in: StandIn,
+        server: Option<(oneshot::Sender<()>, JoinHandle<()>)>,
+    }
+
+    impl Collector {
+        async fn start() -> Self {
+            let mut collector = Self {
+                addr: "127.0.0.1:0".parse().unwrap(),
+                stand_in: StandIn::default(),
+                server: None,
+            };
+            collector.restart().await;
+            collector
+        }
+
+        async fn restart(&mut self) {
+            let socket = TcpSocket::new_v4().unwrap();
+            socket.set_reuseaddr(true).unwrap();
+            socket.bind(self.addr).unwrap();
+            let listener = socket.listen(16).unwrap();
+            self.addr = listener.local_addr().unwrap();
+
+            let (stop, stopped) = oneshot::channel::<()>();
+            let service = TraceServiceServer::new(self.stand_in.clone());
+            let task = tokio::spawn(async move {
+                tonic::transport::Server::builder()
+                    .add_service(service)
+                    .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async {
+                        stopped.await.ok();
+                    })
+                    .await
+                    .unwrap();
+            });
+            self.server = Some((stop, task));
+        }
+
+        async fn stop(&mut self) {
+            if let Some((stop, task)) = self.server.take() {
+                stop.send(()).unwrap();
+                task.await.unwrap();
+            }
+        }
+
+        fn endpoint(&self) -> String {
+            format!("http://{}", self.addr)
+        }
+
+        fn received(&self) -> Vec<String> {
+            self.stand_in.received.lock().unwrap().clone()
+        }
+    }
+
+    fn span(name: &'static str) -> SpanData {
+        SpanData {
+            span_context: SpanContext::new(
+                TraceId::from(1),
+                SpanId::from(1),
+                TraceFlags::SAMPLED,
+                false,
+                TraceState::default(),
+            ),
+            parent_span_id: SpanId::INVALID,
+            span_kind: SpanKind::Internal,
+            name: Cow::Borrowed(name),
+            start_time: SystemTime::now(),
+            end_time: SystemTime::now(),
+            attributes: Vec::new(),
+            dropped_attributes_count: 0,
+            events: SpanEvents::default(),
+            links: SpanLinks::default(),
+            status: Status::Unset,
+            instrumentation_scope: InstrumentationScope::builder("disk-buffer-test").build(),
+        }
+    }
+
+    #[test]
+    fn test_queue_evicts_oldest_batches_over_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = DiskQueue::open(dir.path(), 10, Duration::from_secs(60)).unwrap();
+
+        // Evictions are reported in records, here those of the oldest batch
+        assert_eq!(queue.push(b"aaaa", 3).unwrap(), 0);
+        assert_eq!(queue.push(b"bbbb", 2).unwrap(), 0);
+        assert_eq!(queue.push(b"cccc", 1).unwrap(), 3);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.size_bytes(), 8);
+
+        // A batch larger than the whole queue is dropped on its own
+        assert_eq!(queue.push(&[0; 11], 4).unwrap(), 4);
+
+        let (_, bytes) = queue.front().unwrap().unwrap();
+        assert_eq!(bytes, b"bbbb");
+    }
+
+    #[test]
+    fn test_queue_is_replayed_in_order_after_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let queue = DiskQueue::open(dir.path(), 1024, Duration::from_secs(60)).unwrap();
+            for (records, batch) in [&b"first"[..], b"second", b"third"].into_iter().enumerate() {
+                queue.push(batch, records as u64 + 1).unwrap();
+            }
+        }
+
+        let queue = DiskQueue::open(dir.path(), 1024, Duration::from_secs(60)).unwrap();
+        let mut replayed = Vec::new();
+        while let Some((batch, bytes)) = queue.front().unwrap() {
+            replayed.push((bytes, batch.records()));
+            queue.remove(&batch);
+        }
+
+        assert_eq!(
+            replayed,
+            vec![
+                (b"first".to_vec(), 1),
+                (b"second".to_vec(), 2),
+                (b"third".to_vec(), 3)
+            ]
+        );
+        assert!(queue.is_empty());
+
+        // New batches continue after the ones picked up from disk
+        queue.push(b"fourth", 1).unwrap();
+        assert_eq!(queue.front().unwrap().unwrap().1, b"fourth");
+    }
+
+    #[test]
+    fn test_queue_drops_expired_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = DiskQueue::open(dir.path(), 1024, Duration::from_millis(20)).unwrap();
+        queue.push(b"stale", 5).unwrap();
+
+        std::thread::sleep(Duration::from_millis(50));
+
+        assert!(queue.front().unwrap().is_none());
+        assert!(queue.is_empty());
+        assert_eq!(queue.take_discarded(), 5);
+        assert_eq!(queue.take_discarded(), 0);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_spans_are_spooled_and_replayed_in_order_with_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut collector = Collector::start().await;
+
+        let mut metadata = MetadataMap::new();
+        metadata.insert("authorization", "Bearer buffered".parse().unwrap());
+        let config = DiskBufferConfig::new(dir.path())
+            .with_endpoint(collector.endpoint())
+            .with_metadata(metadata)
+            .with_backoff(Duration::from_millis(50), Duration::from_millis(200));
+        let inner = config
+            .configure_exporter(opentelemetry_otlp::SpanExporter::builder().with_tonic())
+            .build()
+            .unwrap();
+        let stats = Arc::new(ExportStats::with_queue_tracking());
+        let exporter = BufferedSpanExporter::new(inner, &config, stats.clone()).unwrap();
+        let export = |name| {
+            stats.record_produced(1);
+            exporter.export(vec![span(name)])
+        };
+
+        export("online").await.unwrap();
+        assert_eq!(collector.received(), vec!["online"]);
+
+        // While the collector is down, batches are kept on disk
+        collector.stop().await;
+        export("offline-1").await.unwrap();
+        export("offline-2").await.unwrap();
+        let spooled = std::fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(spooled, 2);
+        let health = stats.snapshot();
+        assert_eq!((health.exported, health.dropped), (1, 0));
+        assert_eq!(health.queue_depth, Some(2));
+        assert!(!health.is_healthy());
+
+        // Once it is back, the backlog is delivered before anything newer
+        collector.restart().await;
+        export("reconnected").await.unwrap();
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while (collector.received().len() < 4 || stats.snapshot().exported < 4)
+            && tokio::time::Instant::now() < deadline
+        {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        assert_eq!(
+            collector.received(),
+            vec!["online", "offline-1", "offline-2", "reconnected"]
+        );
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        // Replays are authenticated like the exports of the wrapped exporter
+        let authorizations = collector.stand_in.authorizations.lock().unwrap().clone();
+        assert!(authorizations.len() >= 3);
+        assert!(authorizations
+            .iter()
+            .all(|authorization| authorization.as_deref() == Some("Bearer buffered")));
+
+        // Replayed batches are counted as exported once the collector took them
+        let health = stats.snapshot();
+        assert_eq!((health.exported, health.dropped), (4, 0));
+        assert_eq!(health.queue_depth, Some(0));
+        assert!(health.is_healthy());
+    }
+}
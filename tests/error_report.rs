@@ -0,0 +1,204 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::fmt;
+    use std::io;
+    use std::sync::Arc;
+
+    use otel_tracing::domain::error_report::UNKNOWN_ERROR_KIND;
+    use otel_tracing::domain::telemetry::{AttributeValue, BoxError, LogLevel, SpanContext};
+    use otel_tracing::ports::tracer::TracerPort;
+    use otel_tracing::{ErrorReport, TelemetryService};
+
+    use crate::common::{Noop, RecordingLogger, RecordingTracer};
+
+    #[derive(Debug)]
+    struct ConfigError {
+        source: io::Error,
+    }
+
+    impl fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "could not load the configuration")
+        }
+    }
+
+    impl Error for ConfigError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.source)
+        }
+    }
+
+    fn config_error() -> ConfigError {
+        ConfigError {
+            source: io::Error::new(io::ErrorKind::NotFound, "config.toml not found"),
+        }
+    }
+
+    fn attribute(attributes: &[(String, AttributeValue)], key: &str) -> String {
+        attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.to_string())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_report_keeps_the_type_name_and_chain() {
+        let error = config_error();
+        let report = ErrorReport::new(&error);
+
+        assert!(report.kind.ends_with("error_report::tests::ConfigError"));
+        assert_eq!(report.message, "could not load the configuration");
+        assert_eq!(report.causes, vec!["config.toml not found"]);
+
+        // Once boxed, the type is no longer known
+        let boxed: Box<dyn Error> = Box::new(config_error());
+        let report = ErrorReport::from_dyn(boxed.as_ref());
+        assert_eq!(report.kind, UNKNOWN_ERROR_KIND);
+        assert_eq!(report.causes.len(), 1);
+    }
+
+    #[test]
+    fn test_stack_joins_the_chain_and_backtrace() {
+        let mut report = ErrorReport::new(&config_error());
+        report.backtrace = None;
+        assert_eq!(
+            report.stack(),
+            "could not load the configuration\n    caused by: config.toml not found"
+        );
+
+        report.backtrace = Some("   0: checkout::config::load".to_string());
+        let attributes = report.attributes();
+        assert_eq!(
+            attribute(&attributes, "error.stack"),
+            "could not load the configuration\n    caused by: config.toml not found\
+             \n\nstack backtrace:\n   0: checkout::config::load"
+        );
+        assert_eq!(
+            attribute(&attributes, "error.message"),
+            "could not load the configuration"
+        );
+        assert!(attribute(&attributes, "error.kind").ends_with("ConfigError"));
+    }
+
+    #[cfg(feature = "anyhow")]
+    #[test]
+    fn test_anyhow_chains_are_reported() {
+        use anyhow::Context as _;
+
+        let error = Err::<(), _>(config_error())
+            .context("starting the checkout service")
+            .unwrap_err();
+        let report = ErrorReport::from(&error);
+
+        assert_eq!(report.kind, "anyhow::Error");
+        assert_eq!(report.message, "starting the checkout service");
+        assert_eq!(
+            report.causes,
+            vec!["could not load the configuration", "config.toml not found"]
+        );
+    }
+
+    #[cfg(feature = "eyre")]
+    #[test]
+    fn test_eyre_chains_are_reported() {
+        use eyre::WrapErr as _;
+
+        let report = Err::<(), _>(config_error())
+            .wrap_err("starting the checkout service")
+            .unwrap_err();
+        let report = ErrorReport::from(&report);
+
+        assert_eq!(report.kind, "eyre::Report");
+        assert_eq!(report.message, "starting the checkout service");
+        assert_eq!(report.causes.len(), 2);
+    }
+
+    #[test]
+    fn test_service_logs_and_records_reports() {
+        let logger = RecordingLogger::default();
+        let service =
+            TelemetryService::new(Arc::new(Noop), Arc::new(Noop), Arc::new(logger.clone()));
+
+        service.report_error(
+            &config_error(),
+            Some("checkout::config"),
+            vec![("attempt".to_string(), AttributeValue::Int(3))],
+        );
+
+        let records = logger.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level, LogLevel::Error);
+        assert_eq!(records[0].target.as_deref(), Some("checkout::config"));
+        assert_eq!(records[0].message, "could not load the configuration");
+        assert!(records[0].attributes["error.kind"]
+            .to_string()
+            .ends_with("ConfigError"));
+        assert!(records[0].attributes.contains_key("error.stack"));
+        assert!(records[0].attributes.contains_key("attempt"));
+
+        let tracer = RecordingTracer::default();
+        tracer
+            .create_span(SpanContext::new("load_config".to_string()))
+            .record_error(&ErrorReport::new(&config_error()));
+        let span = &tracer.spans()[0];
+        assert_eq!(
+            span.attributes["error.message"],
+            "could not load the configuration"
+        );
+        assert_eq!(span.event_names(), vec!["exception"]);
+        assert!(span.events[0].1["exception.type"].ends_with("ConfigError"));
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_logged_from_another_task() {
+        let logger = RecordingLogger::default();
+        let service =
+            TelemetryService::new(Arc::new(Noop), Arc::new(Noop), Arc::new(logger.clone()));
+
+        // The error crosses a task boundary and is held across an await
+        let error = tokio::spawn(async { config_error() }).await.unwrap();
+        tokio::task::yield_now().await;
+        service.log_error(error, Some("checkout::config"), vec![]);
+        service.log_error("payment declined", None, vec![]);
+
+        let records = logger.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].message, "could not load the configuration");
+        assert!(records[0].attributes["error.stack"]
+            .to_string()
+            .starts_with("could not load the configuration\n    caused by: config.toml not found"));
+        assert_eq!(records[1].message, "payment declined");
+    }
+
+    #[tokio::test]
+    async fn test_error_log_macro_reports_the_error_type() {
+        let logger = RecordingLogger::default();
+        let service =
+            TelemetryService::new(Arc::new(Noop), Arc::new(Noop), Arc::new(logger.clone()));
+        otel_tracing::telemetry::init(service, None).await.unwrap();
+
+        otel_tracing::error_log!(error: config_error(), target: "checkout::config", "attempt" => 3);
+        let boxed: BoxError = config_error().into();
+        otel_tracing::error_log!(error: boxed);
+        otel_tracing::error_log!(error: "payment declined");
+
+        let records = logger.records();
+        assert_eq!(records.len(), 3);
+        assert!(records[0].attributes["error.kind"]
+            .to_string()
+            .ends_with("error_report::tests::ConfigError"));
+        assert_eq!(records[0].target.as_deref(), Some("checkout::config"));
+        assert!(records[0].attributes.contains_key("attempt"));
+        // Once boxed, the type is no longer known
+        assert_eq!(
+            records[1].attributes["error.kind"].to_string(),
+            UNKNOWN_ERROR_KIND
+        );
+        assert_eq!(records[1].message, "could not load the configuration");
+        assert_eq!(records[2].message, "payment declined");
+    }
+}
@@ -0,0 +1,150 @@
+mod common;
+
+#[cfg(all(test, feature = "grpc"))]
+mod tests {
+    use std::sync::Arc;
+
+    use opentelemetry::global;
+    use opentelemetry::trace::TraceId;
+    use opentelemetry_proto::tonic::collector::trace::v1::trace_service_client::TraceServiceClient;
+    use opentelemetry_proto::tonic::collector::trace::v1::trace_service_server::{
+        TraceService, TraceServiceServer,
+    };
+    use opentelemetry_proto::tonic::collector::trace::v1::{
+        ExportTraceServiceRequest, ExportTraceServiceResponse,
+    };
+    use opentelemetry_proto::tonic::trace::v1::ResourceSpans;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::transport::{Channel, Server};
+    use tonic::{Code, Request, Response};
+    use tower::Layer;
+
+    use otel_tracing::adapters::grpc::{GrpcClientLayer, GrpcServerLayer};
+    use otel_tracing::domain::telemetry::SpanKind;
+
+    use crate::common::{RecordingMetrics, RecordingTracer, TRACE_ID};
+
+    // A collector accepting a single batch of spans at a time
+    struct StandIn;
+
+    #[tonic::async_trait]
+    impl TraceService for StandIn {
+        async fn export(
+            &self,
+            request: Request<ExportTraceServiceRequest>,
+        ) -> Result<Response<ExportTraceServiceResponse>, tonic::Status> {
+            match request.into_inner().resource_spans.len() {
+                0 => Err(tonic::Status::invalid_argument("no spans")),
+                1 => Ok(Response::new(ExportTraceServiceResponse {
+                    partial_success: None,
+                })),
+                _ => Err(tonic::Status::unavailable("collector overloaded")),
+            }
+        }
+    }
+
+    fn batches(count: usize) -> ExportTraceServiceRequest {
+        ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans::default(); count],
+        }
+    }
+
+    // Serve the stand-in and connect a client to it, both traced by the same tracer
+    async fn connect(
+        tracer: &RecordingTracer,
+        metrics: &RecordingMetrics,
+    ) -> TraceServiceClient<otel_tracing::adapters::grpc::TracedGrpcService<Channel>> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = Server::builder()
+            .layer(GrpcServerLayer::new(Arc::new(tracer.clone()), metrics))
+            .add_service(TraceServiceServer::new(StandIn));
+        tokio::spawn(async move {
+            server
+                .serve_with_incoming(TcpListenerStream::new(listener))
+                .await
+                .unwrap()
+        });
+
+        let channel = Channel::from_shared(format!("http://{}", address))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        let layer = GrpcClientLayer::new(Arc::new(tracer.clone()), metrics);
+        TraceServiceClient::new(layer.layer(channel))
+    }
+
+    #[tokio::test]
+    async fn test_calls_are_traced_on_both_sides() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let (tracer, metrics) = (
+            RecordingTracer::with_span_ids(),
+            RecordingMetrics::default(),
+        );
+        let mut client = connect(&tracer, &metrics).await;
+
+        client.export(batches(1)).await.unwrap();
+
+        let name = "opentelemetry.proto.collector.trace.v1.TraceService/Export";
+        let client_spans = tracer.spans_of_kind(SpanKind::Client);
+        let server_spans = tracer.spans_of_kind(SpanKind::Server);
+        assert_eq!((client_spans.len(), server_spans.len()), (1, 1));
+        for span in [&client_spans[0], &server_spans[0]] {
+            assert_eq!(span.name, name);
+            assert_eq!(span.attributes["rpc.system"], "grpc");
+            assert_eq!(
+                span.attributes["rpc.service"],
+                "opentelemetry.proto.collector.trace.v1.TraceService"
+            );
+            assert_eq!(span.attributes["rpc.method"], "Export");
+            assert_eq!(span.attributes["rpc.grpc.status_code"], "0");
+            assert_eq!(span.error_status, None);
+            assert!(span.ended);
+        }
+        // The server continues the client's trace
+        assert_eq!(client_spans[0].parent_trace_id, None);
+        assert_eq!(
+            server_spans[0].parent_trace_id,
+            Some(TraceId::from_hex(TRACE_ID).unwrap())
+        );
+
+        for name in ["rpc.client.call.duration", "rpc.server.call.duration"] {
+            let durations = metrics.recorded(name);
+            assert_eq!(durations.len(), 1, "{}", name);
+            assert_eq!(durations[0].1["rpc.grpc.status_code"], "0", "{}", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_calls_are_errors_of_the_side_at_fault() {
+        let (tracer, metrics) = (
+            RecordingTracer::with_span_ids(),
+            RecordingMetrics::default(),
+        );
+        let mut client = connect(&tracer, &metrics).await;
+
+        let status = client.export(batches(0)).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        let status = client.export(batches(2)).await.unwrap_err();
+        assert_eq!(status.code(), Code::Unavailable);
+
+        // Invalid arguments are the client's fault only
+        let client_spans = tracer.spans_of_kind(SpanKind::Client);
+        let server_spans = tracer.spans_of_kind(SpanKind::Server);
+        assert_eq!(client_spans[0].attributes["rpc.grpc.status_code"], "3");
+        assert_eq!(client_spans[0].attributes["error.type"], "INVALID_ARGUMENT");
+        assert_eq!(client_spans[0].error_status.as_deref(), Some("no spans"));
+        assert_eq!(server_spans[0].attributes["rpc.grpc.status_code"], "3");
+        assert_eq!(server_spans[0].error_status, None);
+
+        for span in [&client_spans[1], &server_spans[1]] {
+            assert_eq!(span.attributes["rpc.grpc.status_code"], "14");
+            assert_eq!(span.attributes["error.type"], "UNAVAILABLE");
+            assert_eq!(span.error_status.as_deref(), Some("collector overloaded"));
+            assert!(span.ended);
+        }
+    }
+}
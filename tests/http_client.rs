@@ -0,0 +1,149 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use opentelemetry::global;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use tokio::net::TcpListener;
+
+    use otel_tracing::adapters::http::TracedClient;
+    use otel_tracing::domain::metrics::{MetricUnit, TimeUnit};
+    use otel_tracing::domain::telemetry::SpanKind;
+
+    use crate::common::{RecordingMetrics, RecordingTracer, TRACE_ID};
+
+    // A server echoing the trace context it was sent
+    async fn serve() -> SocketAddr {
+        let app = Router::new()
+            .route(
+                "/orders",
+                get(|headers: HeaderMap| async move {
+                    headers
+                        .get("traceparent")
+                        .map(|value| value.to_str().unwrap().to_string())
+                        .unwrap_or_default()
+                }),
+            )
+            .route(
+                "/payments",
+                get(|| async { (StatusCode::BAD_GATEWAY, "payments are down") }),
+            );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        address
+    }
+
+    fn client(tracer: &RecordingTracer, metrics: &RecordingMetrics) -> TracedClient {
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        TracedClient::new(client, Arc::new(tracer.clone()), metrics)
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_traced_and_propagated() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let address = serve().await;
+        let (tracer, metrics) = (
+            RecordingTracer::with_span_ids(),
+            RecordingMetrics::default(),
+        );
+        let client = client(&tracer, &metrics);
+
+        let response = client
+            .send(
+                client
+                    .get(format!(
+                        "http://user:secret@{}/orders?page=2&api_key=secret",
+                        address
+                    ))
+                    .header("accept", "text/plain"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.text().await.unwrap(),
+            format!("00-{}-0000000000000001-01", TRACE_ID)
+        );
+
+        let spans = tracer.spans();
+        assert_eq!(spans.len(), 1);
+        let span = &spans[0];
+        assert_eq!(span.name, "GET");
+        assert_eq!(span.kind, SpanKind::Client);
+        for (key, value) in [
+            ("http.request.method", "GET".to_string()),
+            ("server.address", "127.0.0.1".to_string()),
+            ("server.port", address.port().to_string()),
+            ("url.scheme", "http".to_string()),
+            (
+                "url.full",
+                format!("http://{}/orders?page=REDACTED&api_key=REDACTED", address),
+            ),
+            ("http.response.status_code", "200".to_string()),
+        ] {
+            assert_eq!(span.attributes[key], value, "{}", key);
+        }
+        assert!(span.ended);
+        assert_eq!(span.error_status, None);
+
+        assert_eq!(
+            metrics.instruments(),
+            vec![(
+                "http.client.request.duration".to_string(),
+                Some(MetricUnit::Time(TimeUnit::Second))
+            )]
+        );
+        let values = metrics.recorded("http.client.request.duration");
+        assert_eq!(values[0].1["http.response.status_code"], "200");
+        assert!(!values[0].1.contains_key("url.full"));
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_marked() {
+        let address = serve().await;
+        let (tracer, metrics) = (RecordingTracer::default(), RecordingMetrics::default());
+        let client = client(&tracer, &metrics);
+
+        let response = client
+            .send(client.get(format!("http://{}/payments", address)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+
+        // Nothing listens on a port that was just released
+        let closed = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let request = client
+            .inner()
+            .get(format!("http://{}/payments", closed))
+            .build()
+            .unwrap();
+        assert!(client.execute(request).await.is_err());
+
+        let spans = tracer.spans();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].attributes["error.type"], "502");
+        assert_eq!(spans[0].error_status.as_deref(), Some("Bad Gateway"));
+
+        assert_eq!(spans[1].attributes["error.type"], "connect");
+        assert!(spans[1].error_status.is_some());
+        assert_eq!(spans[1].event_names(), vec!["exception"]);
+        assert!(!spans[1]
+            .attributes
+            .contains_key("http.response.status_code"));
+        assert!(spans[1].ended);
+
+        let values = metrics.recorded("http.client.request.duration");
+        assert_eq!(values[1].1["error.type"], "connect");
+    }
+}
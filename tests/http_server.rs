@@ -0,0 +1,127 @@
+mod common;
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+
+    use axum::body::Body;
+    use axum::extract::ConnectInfo;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use opentelemetry::global;
+    use opentelemetry::trace::TraceId;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use tower::ServiceExt;
+
+    use otel_tracing::adapters::http::HttpServerLayer;
+    use otel_tracing::domain::metrics::{MetricUnit, TimeUnit};
+    use otel_tracing::domain::telemetry::SpanKind;
+
+    use crate::common::{RecordingMetrics, RecordingTracer};
+
+    fn app(tracer: &RecordingTracer, metrics: &RecordingMetrics) -> Router {
+        Router::new()
+            .route("/users/{id}", get(|| async { "alice" }))
+            .route(
+                "/checkout",
+                get(|| async { (StatusCode::SERVICE_UNAVAILABLE, "payments are down") }),
+            )
+            .layer(HttpServerLayer::new(Arc::new(tracer.clone()), metrics))
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_traced_with_their_route() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let (tracer, metrics) = (RecordingTracer::default(), RecordingMetrics::default());
+
+        let mut request = Request::get("/users/42?expand=orders")
+            .header(
+                "traceparent",
+                "00-2de7888d8f42abc9c7ba048b78f7a9fb-58406520a0066491-01",
+            )
+            .header("user-agent", "curl/8.5.0")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 7], 51234))));
+        let response = app(&tracer, &metrics).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let spans = tracer.spans();
+        assert_eq!(spans.len(), 1);
+        let span = &spans[0];
+        assert_eq!(span.name, "GET /users/{id}");
+        assert_eq!(span.kind, SpanKind::Server);
+        assert_eq!(
+            span.parent_trace_id,
+            Some(TraceId::from_hex("2de7888d8f42abc9c7ba048b78f7a9fb").unwrap())
+        );
+        for (key, value) in [
+            ("http.request.method", "GET"),
+            ("http.route", "/users/{id}"),
+            ("url.path", "/users/42"),
+            ("url.scheme", "http"),
+            ("client.address", "10.0.0.7"),
+            ("user_agent.original", "curl/8.5.0"),
+            ("http.response.status_code", "200"),
+        ] {
+            assert_eq!(span.attributes[key], value, "{}", key);
+        }
+        assert!(span.ended);
+        assert_eq!(span.error_status, None);
+
+        assert_eq!(
+            metrics.instruments(),
+            vec![(
+                "http.server.request.duration".to_string(),
+                Some(MetricUnit::Time(TimeUnit::Second))
+            )]
+        );
+        let values = metrics.recorded("http.server.request.duration");
+        assert_eq!(values.len(), 1);
+        assert!(values[0].0 >= 0.0);
+        assert_eq!(values[0].1["http.route"], "/users/{id}");
+        assert_eq!(values[0].1["http.response.status_code"], "200");
+        // Unbounded values are kept off the metric
+        assert!(!values[0].1.contains_key("url.path"));
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_set_error_status() {
+        let (tracer, metrics) = (RecordingTracer::default(), RecordingMetrics::default());
+        let app = app(&tracer, &metrics);
+
+        let request = Request::get("/checkout")
+            .header("x-forwarded-for", "203.0.113.9, 10.0.0.1")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // Client errors are not errors of the server
+        let request = Request::get("/missing").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let spans = tracer.spans();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].name, "GET /checkout");
+        assert_eq!(spans[0].attributes["client.address"], "203.0.113.9");
+        assert_eq!(spans[0].attributes["error.type"], "503");
+        assert_eq!(
+            spans[0].error_status.as_deref(),
+            Some("Service Unavailable")
+        );
+
+        assert_eq!(spans[1].name, "GET");
+        assert_eq!(spans[1].attributes["http.response.status_code"], "404");
+        assert_eq!(spans[1].error_status, None);
+
+        let values = metrics.recorded("http.server.request.duration");
+        assert_eq!(values[0].1["error.type"], "503");
+        assert!(!values[1].1.contains_key("error.type"));
+    }
+}
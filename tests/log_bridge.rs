@@ -0,0 +1,119 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use log::kv::Value;
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+    use tracing_subscriber::EnvFilter;
+
+    use otel_tracing::domain::telemetry::{AttributeValue, LogLevel, TelemetryError};
+    use otel_tracing::telemetry::{self, to_log_context, LogBridge};
+    use otel_tracing::TelemetryService;
+
+    use crate::common::{Noop, RecordingLogger};
+
+    fn enabled(bridge: &LogBridge, target: &str, level: Level) -> bool {
+        bridge.enabled(&Metadata::builder().target(target).level(level).build())
+    }
+
+    #[test]
+    fn test_bridge_filters_like_env_filter_directives() {
+        let bridge = LogBridge::new("warn,checkout=debug,checkout::db[query]=trace,hyper=off");
+
+        assert!(enabled(&bridge, "app", Level::Warn));
+        assert!(!enabled(&bridge, "app", Level::Info));
+        // Targets match by module prefix
+        assert!(enabled(&bridge, "checkout::payments", Level::Debug));
+        assert!(!enabled(&bridge, "checkout::payments", Level::Trace));
+        assert!(!enabled(&bridge, "hyper::proto", Level::Error));
+        // Span directives don't apply to records, so they don't raise the level
+        assert_eq!(bridge.max_level(), LevelFilter::Debug);
+    }
+
+    #[test]
+    fn test_record_is_converted_with_location_and_key_values() {
+        let key_values = [
+            ("user_id", Value::from(42i64)),
+            ("cached", Value::from(true)),
+            ("region", Value::from("eu-west-1")),
+        ];
+
+        let context = to_log_context(
+            &Record::builder()
+                .args(format_args!("fetched {} rows", 3))
+                .level(Level::Warn)
+                .target("checkout::db")
+                .module_path(Some("checkout::db::pool"))
+                .file(Some("src/db/pool.rs"))
+                .line(Some(87))
+                .key_values(&key_values)
+                .build(),
+        );
+
+        assert_eq!(context.message, "fetched 3 rows");
+        assert_eq!(context.level, LogLevel::Warn);
+        assert_eq!(context.target.as_deref(), Some("checkout::db"));
+
+        let attribute = |key: &str| context.attributes.get(key).cloned();
+        assert!(matches!(
+            attribute("code.namespace"),
+            Some(AttributeValue::String(s)) if s == "checkout::db::pool"
+        ));
+        assert!(matches!(
+            attribute("code.filepath"),
+            Some(AttributeValue::String(s)) if s == "src/db/pool.rs"
+        ));
+        assert!(matches!(
+            attribute("code.lineno"),
+            Some(AttributeValue::Int(87))
+        ));
+        assert!(matches!(
+            attribute("user_id"),
+            Some(AttributeValue::Int(42))
+        ));
+        assert!(matches!(
+            attribute("cached"),
+            Some(AttributeValue::Bool(true))
+        ));
+        assert!(matches!(
+            attribute("region"),
+            Some(AttributeValue::String(s)) if s == "eu-west-1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_log_crate_records_reach_the_logger_port() {
+        let logger = RecordingLogger::default();
+        let service =
+            TelemetryService::new(Arc::new(Noop), Arc::new(Noop), Arc::new(logger.clone()));
+
+        // Installing before the service is initialized is refused
+        assert!(matches!(
+            telemetry::init_log_bridge(),
+            Err(TelemetryError::NotInitialized)
+        ));
+
+        // Initializing installs the bridge
+        telemetry::init(service, Some(EnvFilter::new("info,chatty=warn")))
+            .await
+            .unwrap();
+
+        log::info!(target: "checkout", order_id = 1234; "order placed");
+        log::info!(target: "chatty", "filtered out by its target");
+        log::debug!(target: "checkout", "filtered out by the default level");
+
+        let records = logger.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message, "order placed");
+        assert_eq!(records[0].target.as_deref(), Some("checkout"));
+        assert!(matches!(
+            records[0].attributes.get("order_id"),
+            Some(AttributeValue::Int(1234))
+        ));
+
+        // Only one logger can be installed for the log crate
+        assert!(telemetry::init_log_bridge().is_err());
+    }
+}
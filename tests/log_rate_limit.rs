@@ -0,0 +1,185 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
+    use opentelemetry::Context;
+    use opentelemetry_sdk::Resource;
+
+    use otel_tracing::adapters::rate_limited::{LogRateLimit, RateLimitedLogger};
+    use otel_tracing::domain::telemetry::{LogContext, LogLevel};
+    use otel_tracing::ports::logger::LoggerPort;
+
+    use crate::common::RecordingLogger;
+
+    fn record(message: String, level: LogLevel) -> LogContext {
+        LogContext::new(message, level).with_target("checkout")
+    }
+
+    fn trace_context(trace_id: &str, flags: TraceFlags) -> Context {
+        Context::current().with_remote_span_context(SpanContext::new(
+            TraceId::from_hex(trace_id).unwrap(),
+            SpanId::from_hex("58406520a0066491").unwrap(),
+            flags,
+            true,
+            TraceState::default(),
+        ))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_call_sites_are_limited_by_token_buckets() {
+        let recorded = RecordingLogger::default();
+        let logger =
+            RateLimitedLogger::new(recorded.clone(), LogRateLimit::new().with_rate(1.0, 3));
+
+        // The same template, whatever the numbers in it
+        for order in 0..10 {
+            logger.log(record(format!("order {} failed", order), LogLevel::Warn));
+        }
+        logger.log(record("cache miss".to_string(), LogLevel::Warn));
+        assert_eq!(
+            recorded.take_messages(),
+            vec![
+                "order 0 failed",
+                "order 1 failed",
+                "order 2 failed",
+                "cache miss"
+            ]
+        );
+
+        tokio::time::advance(Duration::from_secs(2)).await;
+        for order in 10..13 {
+            logger.log(record(format!("order {} failed", order), LogLevel::Warn));
+        }
+        assert_eq!(
+            recorded.take_messages(),
+            vec!["order 10 failed", "order 11 failed"]
+        );
+
+        logger.force_flush().await.unwrap();
+        assert_eq!(
+            recorded.take_messages(),
+            vec!["Suppressed 8 log records: order # failed"]
+        );
+        // Reported suppressions are not reported again
+        logger.force_flush().await.unwrap();
+        assert!(recorded.take_messages().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_verbose_records_are_sampled() {
+        let recorded = RecordingLogger::default();
+        let logger = RateLimitedLogger::new(
+            recorded.clone(),
+            LogRateLimit::new()
+                .with_rate(1000.0, 1000)
+                .with_sample_ratio(0.25),
+        );
+
+        for i in 0..100 {
+            logger.log(record(format!("debug {}", i), LogLevel::Debug));
+            logger.log(record(format!("warn {}", i), LogLevel::Warn));
+        }
+        let messages = recorded.take_messages();
+        assert_eq!(
+            messages.iter().filter(|m| m.starts_with("debug")).count(),
+            25
+        );
+        assert_eq!(
+            messages.iter().filter(|m| m.starts_with("warn")).count(),
+            100
+        );
+
+        // A whole trace is kept or dropped
+        let _guard =
+            trace_context("2de7888d8f42abc90000000000000001", TraceFlags::SAMPLED).attach();
+        for i in 0..10 {
+            logger.log(record(format!("debug {}", i), LogLevel::Debug));
+        }
+        assert_eq!(recorded.take_messages().len(), 10);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_errors_of_sampled_traces_are_always_kept() {
+        let recorded = RecordingLogger::default();
+        let logger =
+            RateLimitedLogger::new(recorded.clone(), LogRateLimit::new().with_rate(0.0, 1));
+
+        let error = std::io::Error::other("connection reset");
+        logger.log_error(&error, Some("checkout"), vec![]);
+        logger.log_error(&error, Some("checkout"), vec![]);
+        assert_eq!(recorded.take_messages().len(), 1);
+
+        {
+            let _guard =
+                trace_context("2de7888d8f42abc9c7ba048b78f7a9fb", TraceFlags::SAMPLED).attach();
+            logger.log_error(&error, Some("checkout"), vec![]);
+            logger.log(record("payment failed".to_string(), LogLevel::Critical));
+            logger.log(record("payment failed".to_string(), LogLevel::Critical));
+            assert_eq!(recorded.take_messages().len(), 3);
+        }
+
+        let _guard =
+            trace_context("2de7888d8f42abc9c7ba048b78f7a9fb", TraceFlags::default()).attach();
+        logger.log_error(&error, Some("checkout"), vec![]);
+        assert!(recorded.take_messages().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_summaries_are_logged_periodically() {
+        let recorded = RecordingLogger::default();
+        let logger = RateLimitedLogger::new(
+            recorded.clone(),
+            LogRateLimit::new()
+                .with_rate(0.0, 1)
+                .with_summary_interval(Duration::from_secs(10)),
+        );
+        logger
+            .init(&Resource::builder_empty().build(), None)
+            .await
+            .unwrap();
+
+        for _ in 0..5 {
+            logger.log(record("retrying".to_string(), LogLevel::Info));
+        }
+        assert_eq!(recorded.take_messages(), vec!["retrying"]);
+
+        tokio::time::sleep(Duration::from_secs(11)).await;
+        assert_eq!(
+            recorded.take_messages(),
+            vec!["Suppressed 4 log records: retrying"]
+        );
+
+        logger.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_zero_summary_interval_is_raised() {
+        let recorded = RecordingLogger::default();
+        let logger = RateLimitedLogger::new(
+            recorded.clone(),
+            LogRateLimit::new()
+                .with_rate(0.0, 1)
+                .with_summary_interval(Duration::ZERO),
+        );
+        logger
+            .init(&Resource::builder_empty().build(), None)
+            .await
+            .unwrap();
+
+        for _ in 0..3 {
+            logger.log(record("retrying".to_string(), LogLevel::Info));
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(
+            recorded.take_messages(),
+            vec!["retrying", "Suppressed 2 log records: retrying"]
+        );
+
+        logger.shutdown().await.unwrap();
+    }
+}
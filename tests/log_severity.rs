@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use opentelemetry::logs::Severity;
+    use opentelemetry::InstrumentationScope;
+    use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+    use opentelemetry_sdk::error::OTelSdkResult;
+    use opentelemetry_sdk::logs::{LogProcessor, SdkLogRecord, SdkLoggerProvider};
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::Registry;
+
+    use otel_tracing::adapters::datadog::{DatadogLogger, SeverityProcessor};
+    use otel_tracing::domain::telemetry::{LogContext, LogLevel, TelemetryError};
+    use otel_tracing::ports::logger::LoggerPort;
+
+    type Severities = Arc<Mutex<Vec<(Option<Severity>, Option<&'static str>)>>>;
+
+    // Keeps the severity of every record it sees
+    #[derive(Debug, Clone, Default)]
+    struct RecordingProcessor {
+        severities: Severities,
+    }
+
+    impl LogProcessor for RecordingProcessor {
+        fn emit(&self, record: &mut SdkLogRecord, _instrumentation: &InstrumentationScope) {
+            self.severities
+                .lock()
+                .unwrap()
+                .push((record.severity_number(), record.severity_text()));
+        }
+
+        fn force_flush(&self) -> OTelSdkResult {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> OTelSdkResult {
+            Ok(())
+        }
+    }
+
+    // Send what the logger logs through a provider ending with the recording processor
+    fn record_with(logger: &DatadogLogger, contexts: Vec<LogContext>) -> RecordingProcessor {
+        let recording = RecordingProcessor::default();
+        let provider = SdkLoggerProvider::builder()
+            .with_log_processor(SeverityProcessor)
+            .with_log_processor(recording.clone())
+            .build();
+        let subscriber = Registry::default().with(OpenTelemetryTracingBridge::new(&provider));
+
+        tracing::subscriber::with_default(subscriber, || {
+            for context in contexts {
+                logger.log(context);
+            }
+        });
+        recording
+    }
+
+    #[test]
+    fn test_levels_parse_and_map_to_severities() {
+        assert_eq!("WARNING".parse::<LogLevel>().unwrap(), LogLevel::Warn);
+        assert_eq!(" fatal ".parse::<LogLevel>().unwrap(), LogLevel::Critical);
+        assert_eq!("Critical".parse::<LogLevel>().unwrap(), LogLevel::Critical);
+        assert!(matches!(
+            "verbose".parse::<LogLevel>(),
+            Err(TelemetryError::Config { .. })
+        ));
+
+        assert_eq!(LogLevel::Critical.severity_number(), Severity::Fatal);
+        assert_eq!(LogLevel::Critical.to_string(), "FATAL");
+        assert_eq!(LogLevel::Trace.severity_number(), Severity::Trace);
+        // Display and parsing round-trip
+        assert_eq!(
+            LogLevel::Warn.to_string().parse::<LogLevel>().unwrap(),
+            LogLevel::Warn
+        );
+        assert!(LogLevel::Critical > LogLevel::Error && LogLevel::Debug < LogLevel::Info);
+    }
+
+    #[test]
+    fn test_critical_records_are_exported_as_fatal() {
+        let logger = DatadogLogger::new("checkout");
+        let recording = record_with(
+            &logger,
+            vec![
+                LogContext::new("disk full".to_string(), LogLevel::Critical),
+                LogContext::new("retrying".to_string(), LogLevel::Error),
+                LogContext::new("started".to_string(), LogLevel::Info),
+            ],
+        );
+
+        assert_eq!(
+            *recording.severities.lock().unwrap(),
+            vec![
+                (Some(Severity::Fatal), Some("FATAL")),
+                (Some(Severity::Error), Some("ERROR")),
+                (Some(Severity::Info), Some("INFO")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_min_level_per_target() {
+        let logger = DatadogLogger::new("checkout")
+            .with_min_level("", LogLevel::Warn)
+            .with_min_level("checkout", LogLevel::Debug)
+            .with_min_level("checkout::cache", LogLevel::Error);
+
+        let record = |target: &str, level| {
+            LogContext::new(format!("{} {}", target, level), level).with_target(target)
+        };
+        let recording = record_with(
+            &logger,
+            vec![
+                record("hyper", LogLevel::Info),
+                record("hyper", LogLevel::Warn),
+                record("checkout::db", LogLevel::Debug),
+                record("checkout::db", LogLevel::Trace),
+                record("checkout::cache", LogLevel::Warn),
+                record("checkout::cache", LogLevel::Critical),
+                // Only whole path segments match
+                record("checkoutservice", LogLevel::Info),
+            ],
+        );
+
+        assert_eq!(
+            recording
+                .severities
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(_, text)| text.unwrap())
+                .collect::<Vec<_>>(),
+            vec!["WARN", "DEBUG", "FATAL"]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
+    use opentelemetry::Context;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::Registry;
+
+    use otel_tracing::adapters::datadog::{ConsoleFormat, DatadogJsonFormat, DatadogLogger};
+    use otel_tracing::domain::telemetry::TelemetryError;
+    use otel_tracing::ports::logger::LoggerPort;
+    use otel_tracing::ResourceBuilder;
+
+    // Console output kept in memory
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn lines(&self) -> Vec<serde_json::Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_datadog_json_format_correlates_with_the_active_span() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = Registry::default().with(
+            tracing_subscriber::fmt::layer()
+                .event_format(DatadogJsonFormat::new("checkout"))
+                .with_writer(move || writer.clone()),
+        );
+
+        let span_context = SpanContext::new(
+            TraceId::from_hex("2de7888d8f42abc9c7ba048b78f7a9fb").unwrap(),
+            SpanId::from_hex("58406520a0066491").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "checkout::payments", order_id = 42, "card declined");
+
+            let _guard = Context::current()
+                .with_remote_span_context(span_context)
+                .attach();
+            tracing::info!("retrying");
+        });
+
+        let lines = captured.lines();
+        assert_eq!(lines.len(), 2);
+
+        assert_eq!(lines[0]["status"], "warn");
+        assert_eq!(lines[0]["service"], "checkout");
+        assert_eq!(lines[0]["logger.name"], "checkout::payments");
+        assert_eq!(lines[0]["message"], "card declined");
+        assert_eq!(lines[0]["order_id"], 42);
+        assert!(lines[0].get("dd.trace_id").is_none());
+
+        // Ids are the lower 64 bits, in decimal
+        assert_eq!(lines[1]["dd.trace_id"], 14391820556292303355u64);
+        assert_eq!(lines[1]["dd.span_id"], 6359193864645272721u64);
+    }
+
+    #[tokio::test]
+    async fn test_layers_compose_with_the_application_subscriber() {
+        let resource = ResourceBuilder::new().with_service_name("checkout").build();
+        let logger = DatadogLogger::new("checkout")
+            .with_console_format(ConsoleFormat::Compact)
+            .with_global_subscriber(false);
+
+        // Without the layers there is nothing to capture events with
+        assert!(matches!(
+            logger.init(&resource, None).await,
+            Err(TelemetryError::Config { .. })
+        ));
+
+        let layers = logger.layers::<Registry>(&resource, None).unwrap();
+        assert_eq!(layers.len(), 2);
+        let _subscriber = Registry::default().with(layers);
+
+        // The service initializing the logger afterwards keeps the composed layers
+        assert!(logger.init(&resource, None).await.is_ok());
+        assert!(matches!(
+            logger.layers::<Registry>(&resource, None),
+            Err(TelemetryError::AlreadyInitialized)
+        ));
+
+        let console_less = DatadogLogger::new("checkout").without_console();
+        assert_eq!(
+            console_less
+                .layers::<Registry>(&resource, None)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_try_init_fails_when_a_subscriber_is_installed() {
+        tracing::subscriber::set_global_default(Registry::default()).unwrap();
+        let resource = ResourceBuilder::new().with_service_name("checkout").build();
+        let logger = DatadogLogger::new("checkout");
+
+        assert!(matches!(
+            logger.try_init(&resource, None),
+            Err(TelemetryError::Config { .. })
+        ));
+        assert!(logger.init(&resource, None).await.is_err());
+
+        // Nothing was kept, the layers can still be composed by hand
+        assert!(logger.layers::<Registry>(&resource, None).is_ok());
+    }
+}
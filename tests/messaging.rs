@@ -0,0 +1,114 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use opentelemetry::global;
+    use opentelemetry::trace::{SpanId, TraceContextExt};
+    use opentelemetry::Context;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+
+    use otel_tracing::adapters::messaging::{
+        extract_context, MessageCarrier, MessagingContext, MessagingTracer,
+    };
+    use otel_tracing::domain::telemetry::SpanKind;
+
+    use crate::common::{span_context, RecordingTracer, TRACE_ID};
+
+    #[test]
+    fn test_consumers_continue_the_trace_of_producers() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = RecordingTracer::with_span_ids();
+        let messaging = MessagingTracer::new(Arc::new(tracer.clone()));
+
+        let mut headers: HashMap<String, Vec<u8>> = HashMap::new();
+        let span = messaging.send(
+            MessagingContext::new("kafka", "orders").with_message_id("42"),
+            &mut headers,
+        );
+        span.end();
+        assert_eq!(
+            headers["traceparent"],
+            format!("00-{}-0000000000000001-01", TRACE_ID).into_bytes()
+        );
+        assert_eq!(
+            extract_context(&headers).span().span_context().span_id(),
+            SpanId::from(1)
+        );
+
+        let span = messaging.process(
+            MessagingContext::new("kafka", "orders").with_attributes(vec![(
+                "messaging.consumer.group.name".to_string(),
+                "billing".into(),
+            )]),
+            &headers,
+        );
+        span.end();
+
+        let spans = tracer.spans();
+        assert_eq!(spans[0].name, "send orders");
+        assert_eq!(spans[0].kind, SpanKind::Producer);
+        assert_eq!(spans[0].parent_span_id, None);
+        for (key, value) in [
+            ("messaging.system", "kafka"),
+            ("messaging.destination.name", "orders"),
+            ("messaging.operation.type", "send"),
+            ("messaging.message.id", "42"),
+        ] {
+            assert_eq!(spans[0].attributes[key], value, "{}", key);
+        }
+
+        assert_eq!(spans[1].name, "process orders");
+        assert_eq!(spans[1].kind, SpanKind::Consumer);
+        assert_eq!(spans[1].parent_span_id, Some(SpanId::from(1)));
+        assert_eq!(spans[1].attributes["messaging.operation.type"], "process");
+        assert_eq!(
+            spans[1].attributes["messaging.consumer.group.name"],
+            "billing"
+        );
+        assert!(!spans[1].attributes.contains_key("messaging.message.id"));
+    }
+
+    #[test]
+    fn test_batches_are_linked_to_each_producer() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = RecordingTracer::with_span_ids();
+        let messaging = MessagingTracer::new(Arc::new(tracer.clone()));
+
+        // Ordered headers, as received from Kafka, with a stale context to replace
+        let mut batch = vec![
+            vec![("traceparent".to_string(), b"stale".to_vec())],
+            vec![("content-type".to_string(), b"application/json".to_vec())],
+            Vec::new(),
+        ];
+        for headers in &mut batch[..2] {
+            messaging
+                .send(MessagingContext::new("nats", "orders.created"), headers)
+                .end();
+        }
+        assert_eq!(
+            batch[0].get("traceparent"),
+            Some(format!("00-{}-0000000000000001-01", TRACE_ID).as_bytes())
+        );
+        assert_eq!(batch[0].keys().len(), batch[1].keys().len() - 1);
+        assert_eq!(batch[1].keys()[0], "content-type");
+
+        // Messages without a context are not linked to the current span
+        let _guard = Context::current()
+            .with_remote_span_context(span_context(99))
+            .attach();
+        messaging
+            .process_batch(MessagingContext::new("nats", "orders.created"), &batch)
+            .end();
+
+        let spans = tracer.spans();
+        let span = &spans[2];
+        assert_eq!(span.name, "process orders.created");
+        assert_eq!(span.kind, SpanKind::Consumer);
+        assert_eq!(span.parent_span_id, Some(SpanId::from(99)));
+        assert_eq!(span.links, vec![SpanId::from(1), SpanId::from(2)]);
+        assert_eq!(span.attributes["messaging.batch.message_count"], "3");
+    }
+}
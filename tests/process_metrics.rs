@@ -0,0 +1,147 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use otel_tracing::adapters::process::ProcessMetrics;
+
+    use crate::common::RecordingMetrics;
+
+    fn fixture() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc")
+    }
+
+    // The values recorded for a metric, by the value of one of their attributes
+    fn by(metrics: &RecordingMetrics, name: &str, attribute: &str) -> HashMap<String, Vec<f64>> {
+        let mut values: HashMap<String, Vec<f64>> = HashMap::new();
+        for (value, attributes) in metrics.recorded(name) {
+            values
+                .entry(attributes[attribute].clone())
+                .or_default()
+                .push(value);
+        }
+        values
+    }
+
+    #[test]
+    fn test_proc_files_are_recorded() {
+        let metrics = RecordingMetrics::default();
+        ProcessMetrics::new(&metrics)
+            .with_proc_root(fixture())
+            .record();
+
+        let single = |name: &str| {
+            let recorded = metrics.recorded(name);
+            assert_eq!(recorded.len(), 1, "{}", name);
+            recorded[0].0
+        };
+        assert_eq!(single("process.memory.usage"), 51200.0 * 1024.0);
+        assert_eq!(single("process.memory.virtual"), 204800.0 * 1024.0);
+        assert_eq!(single("process.thread.count"), 9.0);
+        assert_eq!(single("process.unix.file_descriptor.count"), 5.0);
+        assert_eq!(single("system.cpu.logical.count"), 2.0);
+        // Utilization needs a previous sample
+        assert!(metrics.recorded("process.cpu.utilization").is_empty());
+
+        let cpu_time = by(&metrics, "process.cpu.time", "cpu.mode");
+        assert_eq!(cpu_time["user"], vec![2500.0]);
+        assert_eq!(cpu_time["system"], vec![750.0]);
+        let faults = by(
+            &metrics,
+            "process.paging.faults",
+            "process.paging.fault_type",
+        );
+        assert_eq!(faults["minor"], vec![1500.0]);
+        assert_eq!(faults["major"], vec![12.0]);
+        let disk_io = by(&metrics, "process.disk.io", "disk.io.direction");
+        assert_eq!(disk_io["read"], vec![8192.0]);
+        assert_eq!(disk_io["write"], vec![4096.0]);
+
+        let mut network_io: Vec<(String, String, f64)> = metrics
+            .recorded("system.network.io")
+            .into_iter()
+            .map(|(value, attributes)| {
+                (
+                    attributes["network.interface.name"].clone(),
+                    attributes["network.io.direction"].clone(),
+                    value,
+                )
+            })
+            .collect();
+        network_io.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        assert_eq!(
+            network_io,
+            vec![
+                ("eth0".to_string(), "receive".to_string(), 3145728.0),
+                ("eth0".to_string(), "transmit".to_string(), 524288.0),
+                ("lo".to_string(), "receive".to_string(), 20480.0),
+                ("lo".to_string(), "transmit".to_string(), 20480.0),
+            ]
+        );
+
+        let system_cpu_time = by(&metrics, "system.cpu.time", "cpu.mode");
+        for (mode, millis) in [
+            ("user", 10000.0),
+            ("nice", 100.0),
+            ("system", 2000.0),
+            ("idle", 50000.0),
+            ("iowait", 300.0),
+            ("interrupt", 100.0),
+            ("steal", 80.0),
+        ] {
+            assert_eq!(system_cpu_time[mode], vec![millis], "{}", mode);
+        }
+    }
+
+    #[test]
+    fn test_counters_record_increments() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("self")).unwrap();
+        let stat = fs::read_to_string(fixture().join("self/stat")).unwrap();
+        fs::write(root.path().join("self/stat"), &stat).unwrap();
+
+        let metrics = RecordingMetrics::default();
+        let mut process = ProcessMetrics::new(&metrics).with_proc_root(root.path());
+        process.record();
+        // 50 more ticks of user time and 100 more minor faults
+        let stat = stat.replace(" 1500 0 12 0 250 75 ", " 1600 0 12 0 300 75 ");
+        fs::write(root.path().join("self/stat"), stat).unwrap();
+        process.record();
+
+        let cpu_time = by(&metrics, "process.cpu.time", "cpu.mode");
+        assert_eq!(cpu_time["user"], vec![2500.0, 500.0]);
+        assert_eq!(cpu_time["system"], vec![750.0]);
+        let faults = by(
+            &metrics,
+            "process.paging.faults",
+            "process.paging.fault_type",
+        );
+        assert_eq!(faults["minor"], vec![1500.0, 100.0]);
+        assert_eq!(faults["major"], vec![12.0]);
+        assert_eq!(metrics.recorded("process.cpu.utilization").len(), 1);
+
+        // Files that are missing are skipped
+        assert!(metrics.recorded("process.memory.usage").is_empty());
+        assert!(metrics.recorded("system.cpu.time").is_empty());
+        assert!(metrics.recorded("system.network.io").is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_this_process_is_recorded() {
+        let metrics = RecordingMetrics::default();
+        let mut process = ProcessMetrics::new(&metrics);
+        process.record();
+        process.record();
+
+        let threads = metrics.recorded("process.thread.count");
+        assert!(threads.iter().all(|(count, _)| *count >= 1.0));
+        assert!(metrics.recorded("process.memory.usage")[0].0 > 0.0);
+        assert!(metrics.recorded("process.unix.file_descriptor.count")[0].0 >= 3.0);
+        assert!(!metrics.recorded("system.network.io").is_empty());
+        assert_eq!(metrics.recorded("process.cpu.utilization").len(), 1);
+    }
+}
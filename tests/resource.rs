@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use opentelemetry::Key;
+    use opentelemetry_sdk::Resource;
+
+    use otel_tracing::{AttributeValue, Detector, ResourceBuilder};
+
+    fn attribute(resource: &Resource, key: &'static str) -> Option<String> {
+        resource
+            .get(&Key::from_static_str(key))
+            .map(|value| value.to_string())
+    }
+
+    #[test]
+    fn test_explicit_service_attributes() {
+        let resource = ResourceBuilder::new()
+            .with_service_name("checkout")
+            .with_service_version("1.4.2")
+            .with_service_namespace("shop")
+            .with_service_instance_id("checkout-7d9f")
+            .with_environment("staging")
+            .with_attribute("team", "payments")
+            .with_attributes(vec![("replicas".to_string(), AttributeValue::Int(3))])
+            .build();
+
+        assert_eq!(
+            attribute(&resource, "service.name").as_deref(),
+            Some("checkout")
+        );
+        assert_eq!(
+            attribute(&resource, "service.version").as_deref(),
+            Some("1.4.2")
+        );
+        assert_eq!(
+            attribute(&resource, "service.namespace").as_deref(),
+            Some("shop")
+        );
+        assert_eq!(
+            attribute(&resource, "service.instance.id").as_deref(),
+            Some("checkout-7d9f")
+        );
+        assert_eq!(
+            attribute(&resource, "deployment.environment").as_deref(),
+            Some("staging")
+        );
+        assert_eq!(attribute(&resource, "team").as_deref(), Some("payments"));
+        assert_eq!(attribute(&resource, "replicas").as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn test_detectors_can_be_chosen() {
+        let resource = ResourceBuilder::new()
+            .with_detectors([])
+            .with_service_name("checkout")
+            .build();
+        assert_eq!(attribute(&resource, "telemetry.sdk.name"), None);
+        assert_eq!(attribute(&resource, "process.pid"), None);
+
+        let resource = ResourceBuilder::new()
+            .with_detectors([])
+            .with_detector(Detector::Telemetry)
+            .build();
+        assert_eq!(
+            attribute(&resource, "telemetry.sdk.name").as_deref(),
+            Some("opentelemetry")
+        );
+    }
+
+    #[test]
+    fn test_explicit_values_override_detected_ones() {
+        std::env::set_var(
+            "OTEL_RESOURCE_ATTRIBUTES",
+            "service.version=0.0.1,region=eu-west-1",
+        );
+
+        let resource = ResourceBuilder::new()
+            .with_detectors([Detector::Env])
+            .with_service_version("2.0.0")
+            .build();
+
+        std::env::remove_var("OTEL_RESOURCE_ATTRIBUTES");
+
+        assert_eq!(
+            attribute(&resource, "service.version").as_deref(),
+            Some("2.0.0")
+        );
+        assert_eq!(attribute(&resource, "region").as_deref(), Some("eu-west-1"));
+    }
+}
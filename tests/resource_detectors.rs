@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use opentelemetry::{Key, KeyValue};
+    use opentelemetry_sdk::resource::ResourceDetector;
+    use opentelemetry_sdk::Resource;
+
+    use otel_tracing::adapters::datadog::with_datadog_tags;
+    use otel_tracing::domain::detectors::{ContainerResourceDetector, KubernetesResourceDetector};
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/resource")
+            .join(name)
+    }
+
+    fn attribute(resource: &Resource, key: &'static str) -> Option<String> {
+        resource
+            .get(&Key::from_static_str(key))
+            .map(|value| value.to_string())
+    }
+
+    fn container_id(cgroup: &str, mountinfo: &str) -> Option<String> {
+        ContainerResourceDetector::with_paths(fixture(cgroup), fixture(mountinfo)).container_id()
+    }
+
+    #[test]
+    fn test_container_id_from_cgroup_v1() {
+        assert_eq!(
+            container_id("cgroup_v1_docker", "mountinfo_host").as_deref(),
+            Some("3f4b8c2d9e1a7f6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b")
+        );
+        assert_eq!(
+            container_id("cgroup_v1_kubepods_systemd", "mountinfo_host").as_deref(),
+            Some("8e1f2a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f7")
+        );
+        assert_eq!(
+            container_id("cgroup_v1_ecs", "mountinfo_host").as_deref(),
+            Some("5a0d5ceddf6c44a1928d8ef5b5f4e3c2-2527074092")
+        );
+    }
+
+    #[test]
+    fn test_container_id_from_mountinfo_with_cgroup_v2() {
+        assert_eq!(
+            container_id("cgroup_v2", "mountinfo_v2_docker").as_deref(),
+            Some("c1e5e9d0b2a84f7e96d3a1b0c9f8e7d6a5b4c3d2e1f0a9b8c7d6e5f4a3b2c1d0")
+        );
+    }
+
+    #[test]
+    fn test_no_container_id_on_host() {
+        assert_eq!(container_id("cgroup_v2", "mountinfo_host"), None);
+        assert_eq!(container_id("missing", "missing"), None);
+
+        let resource =
+            ContainerResourceDetector::with_paths(fixture("cgroup_v2"), fixture("mountinfo_host"))
+                .detect();
+        assert!(resource.is_empty());
+    }
+
+    #[test]
+    fn test_kubernetes_attributes_from_downward_api() {
+        let detector = KubernetesResourceDetector::default()
+            .with_namespace_path(fixture("serviceaccount_namespace"))
+            .with_env([
+                ("KUBERNETES_SERVICE_HOST", "10.96.0.1"),
+                ("HOSTNAME", "checkout-7d9f8b-x2k4p"),
+                ("K8S_POD_NAME", "checkout-7d9f8b-x2k4p"),
+                ("K8S_POD_UID", "0a6d4b6e-5c7f-4b9a-8d1e-2f3a4b5c6d7e"),
+                ("K8S_NODE_NAME", "ip-10-0-1-23"),
+                ("K8S_CONTAINER_NAME", "checkout"),
+            ]);
+
+        let resource = detector.detect();
+
+        // The namespace comes from the service account when not in the environment
+        assert_eq!(
+            attribute(&resource, "k8s.namespace.name").as_deref(),
+            Some("payments")
+        );
+        assert_eq!(
+            attribute(&resource, "k8s.pod.name").as_deref(),
+            Some("checkout-7d9f8b-x2k4p")
+        );
+        assert_eq!(
+            attribute(&resource, "k8s.pod.uid").as_deref(),
+            Some("0a6d4b6e-5c7f-4b9a-8d1e-2f3a4b5c6d7e")
+        );
+        assert_eq!(
+            attribute(&resource, "k8s.node.name").as_deref(),
+            Some("ip-10-0-1-23")
+        );
+        assert_eq!(
+            attribute(&resource, "k8s.container.name").as_deref(),
+            Some("checkout")
+        );
+    }
+
+    #[test]
+    fn test_kubernetes_pod_name_falls_back_to_hostname() {
+        let detector = KubernetesResourceDetector::default()
+            .with_namespace_path(fixture("missing"))
+            .with_env([
+                ("KUBERNETES_SERVICE_HOST", "10.96.0.1"),
+                ("HOSTNAME", "checkout-7d9f8b-x2k4p"),
+                ("K8S_NAMESPACE_NAME", "staging"),
+            ]);
+
+        let resource = detector.detect();
+
+        assert_eq!(
+            attribute(&resource, "k8s.namespace.name").as_deref(),
+            Some("staging")
+        );
+        assert_eq!(
+            attribute(&resource, "k8s.pod.name").as_deref(),
+            Some("checkout-7d9f8b-x2k4p")
+        );
+    }
+
+    #[test]
+    fn test_nothing_detected_outside_kubernetes() {
+        let detector = KubernetesResourceDetector::default()
+            .with_namespace_path(fixture("missing"))
+            .with_env([("HOSTNAME", "laptop"), ("K8S_POD_NAME", "leftover")]);
+
+        assert!(detector.attributes().is_empty());
+    }
+
+    #[test]
+    fn test_attributes_are_mapped_to_datadog_tags() {
+        let resource = Resource::builder_empty()
+            .with_attributes([
+                KeyValue::new("service.name", "checkout"),
+                KeyValue::new("container.id", "3f4b8c2d"),
+                KeyValue::new("k8s.namespace.name", "payments"),
+                KeyValue::new("k8s.pod.name", "checkout-7d9f8b-x2k4p"),
+            ])
+            .build();
+
+        let tagged = with_datadog_tags(&resource);
+
+        assert_eq!(
+            attribute(&tagged, "service.name").as_deref(),
+            Some("checkout")
+        );
+        assert_eq!(
+            attribute(&tagged, "k8s.pod.name").as_deref(),
+            Some("checkout-7d9f8b-x2k4p")
+        );
+        assert_eq!(
+            attribute(&tagged, "container_id").as_deref(),
+            Some("3f4b8c2d")
+        );
+        assert_eq!(
+            attribute(&tagged, "kube_namespace").as_deref(),
+            Some("payments")
+        );
+        assert_eq!(
+            attribute(&tagged, "pod_name").as_deref(),
+            Some("checkout-7d9f8b-x2k4p")
+        );
+        assert_eq!(attribute(&tagged, "kube_node"), None);
+    }
+}
@@ -0,0 +1,82 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use otel_tracing::{TelemetryService, TelemetryServiceBuilder};
+
+    use crate::common::{Noop, RecordingMetrics};
+
+    fn service(metrics: &RecordingMetrics, interval: Option<Duration>) -> TelemetryService {
+        let builder = TelemetryServiceBuilder::new()
+            .with_tracer(Noop)
+            .with_metrics(metrics.clone())
+            .with_logger(Noop);
+        let builder = match interval {
+            Some(interval) => builder.with_runtime_metrics(interval),
+            None => builder,
+        };
+        builder.build().unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_runtime_is_sampled_until_shutdown() {
+        let metrics = RecordingMetrics::default();
+        let service = service(&metrics, Some(Duration::from_millis(10)));
+        service.init(None).await.unwrap();
+
+        // Keep the workers busy for a while, then let them park
+        let tasks: Vec<_> = (0..4)
+            .map(|_| tokio::spawn(async { std::thread::sleep(Duration::from_millis(20)) }))
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let workers = metrics.recorded("tokio.workers");
+        assert!(workers.len() >= 2);
+        assert!(workers
+            .iter()
+            .all(|(value, attributes)| *value == 2.0 && attributes.is_empty()));
+        assert!(!metrics.recorded("tokio.tasks.alive").is_empty());
+        assert!(!metrics.recorded("tokio.global_queue.depth").is_empty());
+
+        let busy = metrics.recorded("tokio.worker.busy_duration");
+        let busy_total: f64 = busy.iter().map(|(value, _)| value).sum();
+        // The tasks above kept workers busy for at least 80ms, in microseconds
+        assert!(busy_total >= 80_000.0, "{}", busy_total);
+        assert!(busy
+            .iter()
+            .all(|(_, attributes)| ["0", "1"].contains(&attributes["worker"].as_str())));
+        assert!(!metrics.recorded("tokio.worker.parks").is_empty());
+
+        service.shutdown().await.unwrap();
+        let sampled = metrics.values().len();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(metrics.values().len(), sampled);
+    }
+
+    #[tokio::test]
+    async fn test_runtime_is_not_sampled_by_default() {
+        let metrics = RecordingMetrics::default();
+        let service = service(&metrics, None);
+        service.init(None).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(metrics.values().is_empty());
+        service.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zero_interval_is_raised() {
+        let metrics = RecordingMetrics::default();
+        let service = service(&metrics, Some(Duration::ZERO));
+        service.init(None).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!metrics.recorded("tokio.workers").is_empty());
+        service.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,117 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use otel_tracing::adapters::db::DbContext;
+    use otel_tracing::domain::telemetry::{
+        LogContext, LogLevel, MetricContext, Signal, SpanContext,
+    };
+    use otel_tracing::telemetry::{baggage, scope};
+    use otel_tracing::{spawn_with_context, TelemetryServiceBuilder};
+
+    use crate::common::Recorder;
+
+    fn scoped() -> HashMap<String, String> {
+        scope::attributes()
+            .into_iter()
+            .map(|(key, value)| (key, value.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_scopes_nest_and_travel_with_the_context() {
+        assert!(scope::attributes().is_empty());
+        scope::with_attributes([("request_id", "42"), ("tenant", "acme")], || {
+            scope::with_attributes([("tenant", "globex"), ("user_id", "7")], || {
+                let attributes = scoped();
+                assert_eq!(attributes.len(), 3);
+                assert_eq!(attributes["request_id"], "42");
+                assert_eq!(attributes["tenant"], "globex");
+                assert_eq!(attributes["user_id"], "7");
+            });
+            assert_eq!(scoped()["tenant"], "acme");
+            assert!(!scoped().contains_key("user_id"));
+        });
+        assert!(scope::attributes().is_empty());
+
+        let spawned = scope::with_async_attributes([("request_id", "43")], async {
+            tokio::task::yield_now().await;
+            spawn_with_context(async { scoped() }).await.unwrap()
+        })
+        .await;
+        assert_eq!(spawned["request_id"], "43");
+        assert!(scope::attributes().is_empty());
+    }
+
+    #[test]
+    fn test_scoped_attributes_are_added_to_telemetry() {
+        let recorder = Recorder::default();
+        let service = TelemetryServiceBuilder::new()
+            .with_tracer(recorder.tracer.clone())
+            .with_metrics(recorder.metrics.clone())
+            .with_logger(recorder.logger.clone())
+            .with_baggage_attributes(["tenant"])
+            .build()
+            .unwrap();
+
+        baggage::with_baggage([("tenant", "from-baggage")], || {
+            scope::with_attributes([("request_id", "42"), ("tenant", "acme")], || {
+                service.create_span(SpanContext::new("checkout".to_string()));
+                service.create_span(
+                    SpanContext::new("refund".to_string())
+                        .with_attributes(vec![("request_id".to_string(), "41".into())]),
+                );
+                service
+                    .create_counter(MetricContext::new("orders".to_string()))
+                    .add(1, Vec::new());
+                service.log(LogContext::new("order placed".to_string(), LogLevel::Info));
+                service.log_error("payment declined", None, Vec::new());
+                drop(service.db_tracer().start(DbContext::new("postgresql")));
+            })
+        });
+
+        for name in [
+            "checkout",
+            "orders",
+            "order placed",
+            "payment declined",
+            "postgresql",
+            "db.client.operation.duration",
+        ] {
+            let attributes = recorder.attributes(name);
+            assert_eq!(attributes["request_id"], "42", "{}", name);
+            // Scoped attributes win over baggage
+            assert_eq!(attributes["tenant"], "acme", "{}", name);
+        }
+        // Attributes set explicitly win over scoped ones
+        assert_eq!(recorder.attributes("refund")["request_id"], "41");
+    }
+
+    #[test]
+    fn test_scoped_attributes_are_left_out_of_unselected_signals() {
+        let recorder = Recorder::default();
+        let service = TelemetryServiceBuilder::new()
+            .with_tracer(recorder.tracer.clone())
+            .with_metrics(recorder.metrics.clone())
+            .with_logger(recorder.logger.clone())
+            .with_scoped_signals([Signal::Traces, Signal::Logs])
+            .build()
+            .unwrap();
+
+        scope::with_attributes([("request_id", "42")], || {
+            service.create_span(SpanContext::new("checkout".to_string()));
+            service
+                .create_histogram(MetricContext::new("latency".to_string()))
+                .record(0.5, vec![("route".to_string(), "/orders".into())]);
+            service.log(LogContext::new("order placed".to_string(), LogLevel::Info));
+        });
+
+        assert_eq!(recorder.attributes("checkout")["request_id"], "42");
+        assert_eq!(recorder.attributes("order placed")["request_id"], "42");
+        let latency = recorder.attributes("latency");
+        assert_eq!(latency.len(), 1);
+        assert_eq!(latency["route"], "/orders");
+    }
+}
@@ -0,0 +1,79 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use opentelemetry_sdk::Resource;
+
+    use otel_tracing::domain::health::ExportStats;
+    use otel_tracing::domain::telemetry::{SpanContext, TelemetryError};
+    use otel_tracing::ports::tracer::{Span, TracerPort};
+    use otel_tracing::{SignalHealth, TelemetryServiceBuilder};
+
+    use crate::common::{Noop, RecordingMetrics};
+
+    // A tracer reporting the health of the given stats
+    struct ReportingTracer {
+        stats: Arc<ExportStats>,
+    }
+
+    #[async_trait]
+    impl TracerPort for ReportingTracer {
+        async fn init(&self, _resource: &Resource) -> Result<(), TelemetryError> {
+            Ok(())
+        }
+
+        fn create_span(&self, _context: SpanContext) -> Box<dyn Span> {
+            Box::new(Noop)
+        }
+
+        fn health(&self) -> Option<SignalHealth> {
+            Some(self.stats.snapshot())
+        }
+
+        async fn shutdown(&self) -> Result<(), TelemetryError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_totals_are_counted_once() {
+        let stats = Arc::new(ExportStats::with_queue_tracking());
+        let metrics = RecordingMetrics::default();
+        let service = TelemetryServiceBuilder::new()
+            .with_tracer(ReportingTracer {
+                stats: stats.clone(),
+            })
+            .with_metrics(metrics.clone())
+            .with_logger(Noop)
+            .with_self_telemetry(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        service.init(None).await.unwrap();
+
+        stats.record_produced(7);
+        stats.record_exported(3);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        stats.record_exported(2);
+        stats.record_failed(1, "connection refused");
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        service.shutdown().await.unwrap();
+
+        let total = |name: &str| -> f64 {
+            let recorded = metrics.recorded(&format!("otel_tracing.exporter.{}", name));
+            assert!(recorded
+                .iter()
+                .all(|(_, attributes)| attributes["signal"] == "traces"));
+            recorded.iter().map(|(value, _)| value).sum()
+        };
+        assert_eq!(total("exported"), 5.0);
+        assert_eq!(total("failed"), 1.0);
+        assert_eq!(total("dropped"), 1.0);
+
+        let depth = metrics.recorded("otel_tracing.exporter.queue_depth");
+        assert_eq!(depth.last().unwrap().0, 1.0);
+    }
+}
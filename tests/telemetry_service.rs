@@ -0,0 +1,449 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use opentelemetry_proto::tonic::collector::trace::v1::trace_service_server::{
+        TraceService, TraceServiceServer,
+    };
+    use opentelemetry_proto::tonic::collector::trace::v1::{
+        ExportTraceServiceRequest, ExportTraceServiceResponse,
+    };
+    use opentelemetry_proto::tonic::common::v1::any_value::Value;
+    use opentelemetry_sdk::Resource;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tracing_subscriber::EnvFilter;
+
+    use otel_tracing::adapters::datadog::DatadogTracer;
+    use otel_tracing::domain::health::SignalHealth;
+    use otel_tracing::domain::telemetry::{
+        AttributeValue, LogContext, MetricContext, Signal, SignalFailure, SpanContext,
+        TelemetryError,
+    };
+    use otel_tracing::ports::logger::LoggerPort;
+    use otel_tracing::ports::metrics::{Counter, Gauge, Histogram, MetricsPort};
+    use otel_tracing::ports::tracer::{Span, TracerPort};
+    use opentelemetry::Key;
+    use otel_tracing::{ResourceBuilder, TelemetryServiceBuilder};
+
+    use crate::common::Noop;
+
+    // Records the order in which components are shut down
+    type ShutdownLog = Arc<Mutex<Vec<Signal>>>;
+
+    // A tracer whose flush and shutdown take a configurable amount of time
+    struct SlowTracer {
+        flush_delay: Duration,
+        shutdown_delay: Duration,
+        shutdown_log: ShutdownLog,
+    }
+
+    impl SlowTracer {
+        fn new(flush_delay: Duration) -> Self {
+            Self {
+                flush_delay,
+                shutdown_delay: Duration::ZERO,
+                shutdown_log: ShutdownLog::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl TracerPort for SlowTracer {
+        async fn init(&self, _resource: &Resource) -> Result<(), TelemetryError> {
+            Ok(())
+        }
+
+        fn create_span(&self, _context: SpanContext) -> Box<dyn Span> {
+            Box::new(Noop)
+        }
+
+        async fn force_flush(&self) -> Result<(), TelemetryError> {
+            tokio::time::sleep(self.flush_delay).await;
+            Ok(())
+        }
+
+        async fn shutdown(&self) -> Result<(), TelemetryError> {
+            tokio::time::sleep(self.shutdown_delay).await;
+            self.shutdown_log.lock().unwrap().push(Signal::Traces);
+            Ok(())
+        }
+    }
+
+    // Metrics whose flush and shutdown always fail
+    #[derive(Default)]
+    struct FailingMetrics {
+        shutdown_log: ShutdownLog,
+    }
+
+    #[async_trait]
+    impl MetricsPort for FailingMetrics {
+        async fn init(&self, _resource: &Resource) -> Result<(), TelemetryError> {
+            Ok(())
+        }
+
+        fn create_counter(&self, _context: MetricContext) -> Box<dyn Counter> {
+            Box::new(Noop)
+        }
+
+        fn create_gauge(&self, _context: MetricContext) -> Box<dyn Gauge> {
+            Box::new(Noop)
+        }
+
+        fn create_histogram(&self, _context: MetricContext) -> Box<dyn Histogram> {
+            Box::new(Noop)
+        }
+
+        async fn force_flush(&self) -> Result<(), TelemetryError> {
+            Err(TelemetryError::export(Signal::Metrics, "exporter unavailable"))
+        }
+
+        async fn shutdown(&self) -> Result<(), TelemetryError> {
+            self.shutdown_log.lock().unwrap().push(Signal::Metrics);
+            Err(TelemetryError::export(Signal::Metrics, "exporter unavailable"))
+        }
+
+        fn health(&self) -> Option<SignalHealth> {
+            Some(SignalHealth {
+                failed: 2,
+                dropped: 40,
+                last_error: Some("exporter unavailable".to_string()),
+                ..SignalHealth::default()
+            })
+        }
+    }
+
+    // A logger relying on the default flush implementation
+    #[derive(Default)]
+    struct NoopLogger {
+        shutdown_log: ShutdownLog,
+        // The resource the logger was initialized with
+        resource: Arc<Mutex<Option<Resource>>>,
+    }
+
+    #[async_trait]
+    impl LoggerPort for NoopLogger {
+        async fn init(
+            &self,
+            resource: &Resource,
+            _filter: Option<EnvFilter>,
+        ) -> Result<(), TelemetryError> {
+            *self.resource.lock().unwrap() = Some(resource.clone());
+            Ok(())
+        }
+
+        fn log(&self, _context: LogContext) {}
+
+        fn log_error(
+            &self,
+            _error: &(dyn std::error::Error + 'static),
+            _target: Option<&str>,
+            _attributes: Vec<(String, AttributeValue)>,
+        ) {
+        }
+
+        async fn shutdown(&self) -> Result<(), TelemetryError> {
+            self.shutdown_log.lock().unwrap().push(Signal::Logs);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_force_flush_reports_per_signal_results() {
+        let service = TelemetryServiceBuilder::new()
+            .with_tracer(SlowTracer::new(Duration::from_millis(10)))
+            .with_metrics(FailingMetrics::default())
+            .with_logger(NoopLogger::default())
+            .build()
+            .unwrap();
+
+        let report = service.force_flush(Duration::from_secs(1)).await;
+
+        assert!(report.get(Signal::Traces).is_ok());
+        assert!(report.get(Signal::Logs).is_ok());
+        assert!(matches!(
+            report.get(Signal::Metrics),
+            Err(TelemetryError::Export {
+                signal: Signal::Metrics,
+                ..
+            })
+        ));
+        assert!(!report.is_ok());
+
+        let err = report.into_result().unwrap_err();
+        assert!(err.to_string().contains("metrics: "));
+
+        // The failing signal's error is preserved as the source
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(
+            source.to_string(),
+            "Failed to export metrics: exporter unavailable"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_force_flush_times_out_slow_signal() {
+        let service = TelemetryServiceBuilder::new()
+            .with_tracer(SlowTracer::new(Duration::from_secs(10)))
+            .with_metrics(FailingMetrics::default())
+            .with_logger(NoopLogger::default())
+            .build()
+            .unwrap();
+
+        let report = service.force_flush(Duration::from_millis(50)).await;
+
+        match report.get(Signal::Traces) {
+            Err(TelemetryError::Timeout { signal, timeout }) => {
+                assert_eq!(*signal, Signal::Traces);
+                assert_eq!(*timeout, Duration::from_millis(50));
+            }
+            other => panic!("Expected a flush timeout, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_logger_last() {
+        let shutdown_log = ShutdownLog::default();
+        let service = TelemetryServiceBuilder::new()
+            .with_tracer(SlowTracer {
+                flush_delay: Duration::ZERO,
+                shutdown_delay: Duration::ZERO,
+                shutdown_log: shutdown_log.clone(),
+            })
+            .with_metrics(FailingMetrics {
+                shutdown_log: shutdown_log.clone(),
+            })
+            .with_logger(NoopLogger {
+                shutdown_log: shutdown_log.clone(),
+                ..NoopLogger::default()
+            })
+            .build()
+            .unwrap();
+
+        let result = service.shutdown_with_timeout(Duration::from_secs(1)).await;
+
+        assert_eq!(
+            *shutdown_log.lock().unwrap(),
+            vec![Signal::Traces, Signal::Metrics, Signal::Logs]
+        );
+
+        match result {
+            Err(TelemetryError::Shutdown(report)) => {
+                assert!(report.get(Signal::Traces).is_none());
+                assert!(matches!(
+                    report.get(Signal::Metrics),
+                    Some(SignalFailure::Failed(_))
+                ));
+                assert!(report.get(Signal::Logs).is_none());
+            }
+            other => panic!("Expected Shutdown, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_with_timeout_bounds_total_time() {
+        let service = TelemetryServiceBuilder::new()
+            .with_tracer(SlowTracer {
+                flush_delay: Duration::ZERO,
+                shutdown_delay: Duration::from_secs(10),
+                shutdown_log: ShutdownLog::default(),
+            })
+            .with_metrics(FailingMetrics::default())
+            .with_logger(NoopLogger::default())
+            .build()
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let result = service.shutdown_with_timeout(Duration::from_millis(100)).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        match result {
+            Err(TelemetryError::Shutdown(report)) => {
+                assert!(matches!(
+                    report.get(Signal::Traces),
+                    Some(SignalFailure::TimedOut)
+                ));
+            }
+            other => panic!("Expected Shutdown, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_health_collects_every_signal() {
+        let service = TelemetryServiceBuilder::new()
+            .with_tracer(SlowTracer::new(Duration::ZERO))
+            .with_metrics(FailingMetrics::default())
+            .with_logger(NoopLogger::default())
+            .build()
+            .unwrap();
+
+        let health = service.health();
+
+        // Components that do not track their exports report nothing
+        assert!(health.get(Signal::Traces).is_none());
+        assert!(health.get(Signal::Logs).is_none());
+
+        let metrics = health.get(Signal::Metrics).unwrap();
+        assert_eq!(metrics.dropped, 40);
+        assert!(!metrics.is_healthy());
+        assert!(!health.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn test_services_are_initialized_with_their_own_resource() {
+        let mut resources = Vec::new();
+        for name in ["checkout", "billing"] {
+            let logger = NoopLogger::default();
+            let resource = logger.resource.clone();
+            let service = TelemetryServiceBuilder::new()
+                .with_tracer(SlowTracer::new(Duration::ZERO))
+                .with_metrics(FailingMetrics::default())
+                .with_logger(logger)
+                .with_resource(ResourceBuilder::new().with_service_name(name))
+                .build()
+                .unwrap();
+
+            service.init(None).await.unwrap();
+            resources.push(resource.lock().unwrap().take().unwrap());
+        }
+
+        let service_name = |resource: &Resource| {
+            resource
+                .get(&Key::from_static_str("service.name"))
+                .map(|value| value.to_string())
+        };
+        assert_eq!(service_name(&resources[0]).as_deref(), Some("checkout"));
+        assert_eq!(service_name(&resources[1]).as_deref(), Some("billing"));
+    }
+
+    // An OTLP collector recording every span it receives with the service name of its
+    // resource
+    #[derive(Clone, Default)]
+    struct Collector {
+        spans: Arc<Mutex<Vec<(String, String)>>>,
+    }
+
+    #[tonic::async_trait]
+    impl TraceService for Collector {
+        async fn export(
+            &self,
+            request: tonic::Request<ExportTraceServiceRequest>,
+        ) -> Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status> {
+            for resource_spans in request.into_inner().resource_spans {
+                let service_name = resource_spans
+                    .resource
+                    .iter()
+                    .flat_map(|resource| &resource.attributes)
+                    .find(|attribute| attribute.key == "service.name")
+                    .and_then(|attribute| match &attribute.value.as_ref()?.value {
+                        Some(Value::StringValue(name)) => Some(name.clone()),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+                let spans = resource_spans
+                    .scope_spans
+                    .into_iter()
+                    .flat_map(|scope| scope.spans)
+                    .map(|span| (span.name, service_name.clone()));
+                self.spans.lock().unwrap().extend(spans);
+            }
+
+            Ok(tonic::Response::new(ExportTraceServiceResponse {
+                partial_success: None,
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_datadog_spans_carry_their_own_service_resource() {
+        let collector = Collector::default();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        std::env::set_var(
+            "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+            format!("http://{}", listener.local_addr().unwrap()),
+        );
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(TraceServiceServer::new(collector.clone()))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        // Both services are initialized before either creates a span
+        let mut services = Vec::new();
+        for name in ["checkout", "billing"] {
+            let service = TelemetryServiceBuilder::new()
+                .with_tracer(DatadogTracer::new())
+                .with_metrics(FailingMetrics::default())
+                .with_logger(NoopLogger::default())
+                .with_resource(ResourceBuilder::new().with_service_name(name))
+                .build()
+                .unwrap();
+            service.init(None).await.unwrap();
+            services.push((name, service));
+        }
+        for (name, service) in &services {
+            service
+                .create_span(SpanContext::new(format!("{}-span", name)))
+                .end();
+        }
+        for (_, service) in &services {
+            service.shutdown().await.ok();
+        }
+
+        let mut spans = collector.spans.lock().unwrap().clone();
+        spans.sort();
+        assert_eq!(
+            spans,
+            vec![
+                ("billing-span".to_string(), "billing".to_string()),
+                ("checkout-span".to_string(), "checkout".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_datadog_spans_ended_twice_are_queued_once() {
+        let tracer = DatadogTracer::new();
+        tracer
+            .init(&Resource::builder_empty().build())
+            .await
+            .unwrap();
+
+        let span = tracer.create_span(SpanContext::new("twice".to_string()));
+        span.end();
+        span.end();
+
+        assert_eq!(tracer.health().unwrap().queue_depth, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_double_init_reports_already_initialized() {
+        let build = || {
+            TelemetryServiceBuilder::new()
+                .with_tracer(SlowTracer::new(Duration::ZERO))
+                .with_metrics(FailingMetrics::default())
+                .with_logger(NoopLogger::default())
+                .build()
+                .unwrap()
+        };
+
+        otel_tracing::telemetry::init(build(), None).await.unwrap();
+        let result = otel_tracing::telemetry::init(build(), None).await;
+
+        assert!(matches!(result, Err(TelemetryError::AlreadyInitialized)));
+    }
+
+    #[test]
+    fn test_builder_without_tracer_is_config_error() {
+        let result = TelemetryServiceBuilder::new()
+            .with_metrics(FailingMetrics::default())
+            .with_logger(NoopLogger::default())
+            .build();
+
+        assert!(matches!(result, Err(TelemetryError::Config { .. })));
+    }
+}
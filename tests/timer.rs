@@ -0,0 +1,104 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use otel_tracing::domain::metrics::{MetricUnit, TimeUnit};
+    use otel_tracing::domain::telemetry::MetricContext;
+    use otel_tracing::ports::metrics::{Histogram, HistogramExt, MetricsPort};
+    use otel_tracing::TelemetryServiceBuilder;
+
+    use crate::common::{Noop, RecordingMetrics};
+
+    // A histogram of `metrics` called "duration", in `unit` if any
+    fn histogram(metrics: &RecordingMetrics, unit: Option<TimeUnit>) -> Box<dyn Histogram> {
+        let context = MetricContext::new("duration".to_string());
+        metrics.create_histogram(match unit {
+            Some(unit) => context.with_unit(MetricUnit::Time(unit)),
+            None => context,
+        })
+    }
+
+    #[test]
+    fn test_durations_are_recorded_in_the_histogram_unit() {
+        let duration = Duration::from_millis(1500);
+        assert_eq!(TimeUnit::Nanosecond.convert(duration), 1.5e9);
+        assert_eq!(TimeUnit::Microsecond.convert(duration), 1.5e6);
+        assert_eq!(TimeUnit::Millisecond.convert(duration), 1500.0);
+        assert_eq!(TimeUnit::Second.convert(duration), 1.5);
+        assert_eq!(TimeUnit::Minute.convert(duration), 0.025);
+
+        let sleep = || std::thread::sleep(Duration::from_millis(20));
+        for (unit, min, max) in [
+            (Some(TimeUnit::Microsecond), 2e4, 1e6),
+            (Some(TimeUnit::Millisecond), 20.0, 1000.0),
+            (Some(TimeUnit::Second), 0.02, 1.0),
+            // Histograms without a time unit record seconds
+            (None, 0.02, 1.0),
+        ] {
+            let metrics = RecordingMetrics::default();
+            histogram(&metrics, unit.clone()).time(sleep);
+            let recorded = metrics.recorded("duration");
+            assert_eq!(recorded.len(), 1);
+            assert!(
+                (min..max).contains(&recorded[0].0),
+                "{:?}: {}",
+                unit,
+                recorded[0].0
+            );
+        }
+    }
+
+    #[test]
+    fn test_timers_record_once_with_their_attributes() {
+        let metrics = RecordingMetrics::default();
+        let histogram = histogram(&metrics, Some(TimeUnit::Millisecond));
+        for succeed in [true, false] {
+            let mut timer = histogram
+                .start_timer()
+                .with_attributes(vec![("route".to_string(), "/orders".into())]);
+            timer.set_attribute("outcome", "pending");
+            timer.set_attribute("outcome", if succeed { "ok" } else { "error" });
+        }
+        let elapsed = histogram.start_timer().stop();
+        histogram.start_timer().discard();
+
+        let recorded = metrics.recorded("duration");
+        assert_eq!(recorded.len(), 3);
+        for ((_, attributes), outcome) in recorded.iter().zip(["ok", "error"]) {
+            assert_eq!(attributes.len(), 2);
+            assert_eq!(attributes["route"], "/orders");
+            assert_eq!(attributes["outcome"], outcome);
+        }
+        assert_eq!(recorded[2].0, elapsed.as_nanos() as f64 / 1e6);
+        assert!(recorded[2].1.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_service_histograms_time_futures() {
+        let metrics = RecordingMetrics::default();
+        let service = TelemetryServiceBuilder::new()
+            .with_tracer(Noop)
+            .with_metrics(metrics.clone())
+            .with_logger(Noop)
+            .build()
+            .unwrap();
+        let histogram = service.create_histogram(
+            MetricContext::new("job.duration".to_string())
+                .with_unit(MetricUnit::Time(TimeUnit::Millisecond)),
+        );
+
+        let timed = histogram.time_async(async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            42
+        });
+        // Nothing is measured before the future is polled
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(timed.await, 42);
+
+        let recorded = metrics.recorded("job.duration");
+        assert_eq!(recorded.len(), 1);
+        assert!((20.0..200.0).contains(&recorded[0].0), "{}", recorded[0].0);
+    }
+}